@@ -0,0 +1,87 @@
+//! Compares counting the alive neighbors of every tile of a radius-150 map with [HexLayout::neighbor_counts]
+//! and by looking up the neighbors of each tile, and a game of life step written both ways.
+
+mod common;
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::layout::HexLayout;
+use hexing::utils::neighbors;
+
+/// The radius of the map.
+const RADIUS: usize = 150;
+
+/// The rule of the game of life of the `game-of-life` example, from the state of a cell and its number of alive neighbors.
+const fn life(alive: bool, count: u8) -> bool {
+    match (alive, count) {
+        (true, x) if x != 2 => false,
+        (false, 2) => true,
+        _ => alive,
+    }
+}
+
+/// Counts the alive neighbors of every tile by looking up the six neighbors of each tile.
+fn naive_counts(cells: &HexLayout<bool, isize>) -> Vec<(HexPosition<isize>, u8)> {
+    cells
+        .positions()
+        .map(|pos| {
+            let count = neighbors(*pos)
+                .into_iter()
+                .filter(|neighbor| cells.get(*neighbor) == Some(&true))
+                .count();
+            (*pos, count as u8)
+        })
+        .collect()
+}
+
+/// One step of the game of life, like the `game-of-life` example did before [HexLayout::neighbor_counts].
+fn naive_step(cells: &HexLayout<bool, isize>) -> HexLayout<bool, isize> {
+    cells.step_ca(|_, alive, around| life(*alive, around.filter(|n| **n).count() as u8))
+}
+
+/// One step of the game of life, like the `game-of-life` example does.
+fn counted_step(cells: &HexLayout<bool, isize>) -> HexLayout<bool, isize> {
+    let counts = cells.neighbor_counts(|alive| *alive);
+    cells.step_ca(|pos, alive, _| life(*alive, counts[&pos]))
+}
+
+/// Runs the benchmark.
+fn main() {
+    let mut rng = Lcg(919);
+    for percent in [10, 50] {
+        let mut cells: HexLayout<bool, isize> =
+            HexLayout::new_from_range(RADIUS + 1, HexPosition::ORIGIN);
+        for pos in HexPosition::ORIGIN.spiral(RADIUS) {
+            cells.set(pos, rng.below(100) < percent);
+        }
+
+        let counts = cells.neighbor_counts(|alive| *alive);
+        let naive = naive_counts(&cells);
+        assert_eq!(counts.len(), naive.len());
+        for (pos, count) in &naive {
+            assert_eq!(counts[pos], *count);
+        }
+        assert_eq!(naive_step(&cells), counted_step(&cells));
+
+        measure(
+            &format!("{percent}% alive, per-tile neighbor lookups"),
+            10,
+            || naive_counts(&cells),
+        );
+        measure(
+            &format!("{percent}% alive, HexLayout::neighbor_counts"),
+            10,
+            || cells.neighbor_counts(|alive| *alive),
+        );
+        measure(
+            &format!("{percent}% alive, game of life step, per-tile lookups"),
+            10,
+            || naive_step(&cells),
+        );
+        measure(
+            &format!("{percent}% alive, game of life step, neighbor_counts"),
+            10,
+            || counted_step(&cells),
+        );
+    }
+}
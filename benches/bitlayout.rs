@@ -0,0 +1,52 @@
+//! Compares the OR of two radius-300 masks stored in [HexBitLayout]s, word by word,
+//! with [HexLayout::or] on layouts storing only the blocked positions.
+
+mod common;
+
+use std::collections::HashSet;
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::bitlayout::HexBitLayout;
+use hexing::layout::HexLayout;
+use hexing::utils::{HexBound, HexIndexing};
+
+/// The radius of the masks.
+const RADIUS: usize = 300;
+
+/// Creates a mask where a third of the positions of `bound` are blocked, in both storages.
+fn random_mask(bound: HexBound<i32>, rng: &mut Lcg) -> (HexBitLayout<i32>, HexLayout<bool, i32>) {
+    let mut bits = HexBitLayout::new(bound);
+    let mut layout: HexLayout<bool, i32> = std::iter::empty().collect();
+    for pos in bound.iter(HexIndexing::RowMajor) {
+        if rng.below(3) == 0 {
+            bits.set(pos, true);
+            layout.set(pos, true);
+        }
+    }
+    (bits, layout)
+}
+
+/// Runs the benchmark.
+fn main() {
+    let bound = HexBound::new(HexPosition::ORIGIN, RADIUS);
+    let mut rng = Lcg(921);
+    let (bits_a, layout_a) = random_mask(bound, &mut rng);
+    let (bits_b, layout_b) = random_mask(bound, &mut rng);
+
+    let bits_or: HashSet<HexPosition<i32>> = (&bits_a | &bits_b)
+        .iter()
+        .filter(|(_, blocked)| *blocked)
+        .map(|(pos, _)| pos)
+        .collect();
+    assert_eq!(bits_or, layout_a.or(&layout_b));
+
+    println!(
+        "memory of a mask: {} bytes in a HexBitLayout",
+        std::mem::size_of_val(bits_a.as_words())
+    );
+    measure("OR of two masks, HexBitLayout", 20, || &bits_a | &bits_b);
+    measure("OR of two masks, HexLayout::or", 20, || {
+        layout_a.or(&layout_b)
+    });
+}
@@ -0,0 +1,44 @@
+//! Helpers shared by the benchmarks.
+//!
+//! The benchmarks don't use any framework: each one is a small program timing the code with [Instant]
+//! and printing the median of several runs. Run them in release mode with `cargo bench`.
+
+// Not every benchmark uses every helper.
+#![allow(dead_code)]
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` `runs` times, prints the median time of a run with `name`, and returns it.
+pub fn measure<R>(name: &str, runs: usize, mut f: impl FnMut() -> R) -> Duration {
+    let mut times: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    let median = times[runs / 2];
+    println!("{name:<50} {median:>12.2?}");
+    median
+}
+
+/// A small deterministic random number generator, so that every run uses the same inputs.
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    /// Returns a random number in `0..n`.
+    pub const fn below(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % n
+    }
+
+    /// Returns a random number in `-range..=range`.
+    pub const fn signed(&mut self, range: isize) -> isize {
+        self.below(2 * range as u64 + 1) as isize - range
+    }
+}
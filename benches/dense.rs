@@ -0,0 +1,177 @@
+//! Compares a smoothing pass and a game of life step on a radius-150 map, stored in a [HexLayout]
+//! and in dense storages in the orders of [HexIndexing], and checks that they all give the same result.
+
+mod common;
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::layout::HexLayout;
+use hexing::utils::{HexBound, HexIndexing, neighbors};
+
+/// The radius of the map.
+const RADIUS: usize = 150;
+
+/// The rule of the game of life of the `game-of-life` example, from the state of a cell and its number of alive neighbors.
+const fn life(alive: bool, count: usize) -> bool {
+    match (alive, count) {
+        (true, x) if x != 2 => false,
+        (false, 2) => true,
+        _ => alive,
+    }
+}
+
+/// The smoothing rule: the mean of the value and of the values of the neighbors.
+fn smooth(value: f64, neighbors: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = neighbors.fold((value, 1.0), |(sum, count), value| {
+        (sum + value, count + 1.0)
+    });
+    sum / count
+}
+
+/// One step of `rule` on a dense storage in the given order, looking the neighbors up with [HexBound::index_of].
+fn step_indexed<V: Copy, R>(
+    bound: &HexBound<isize>,
+    indexing: HexIndexing,
+    values: &[V],
+    rule: impl Fn(V, &mut dyn Iterator<Item = V>) -> R,
+) -> Vec<R> {
+    bound
+        .iter(indexing)
+        .zip(values)
+        .map(|(pos, value)| {
+            let mut around = neighbors(pos)
+                .into_iter()
+                .filter_map(|neighbor| bound.index_of(neighbor, indexing))
+                .map(|index| values[index]);
+            rule(*value, &mut around)
+        })
+        .collect()
+}
+
+/// One step of `rule` on a dense row-major storage, walking it with [HexBound::iter_neighbor_runs].
+fn step_runs<V: Copy, R>(
+    bound: &HexBound<isize>,
+    values: &[V],
+    rule: impl Fn(V, &mut dyn Iterator<Item = V>) -> R,
+) -> Vec<R> {
+    bound
+        .iter_neighbor_runs()
+        .map(|(index, _, indices)| {
+            let mut around = indices.into_iter().flatten().map(|index| values[index]);
+            rule(values[index], &mut around)
+        })
+        .collect()
+}
+
+/// Stores the data of `layout` densely in the given order.
+fn to_dense<V: Default + Copy>(
+    layout: &HexLayout<V, isize>,
+    bound: &HexBound<isize>,
+    indexing: HexIndexing,
+) -> Vec<V> {
+    bound
+        .iter(indexing)
+        .map(|pos| layout.get(pos).copied().unwrap_or_default())
+        .collect()
+}
+
+/// Checks that a dense storage in the given order has the same data as `layout`.
+fn assert_same<V: Default + PartialEq + std::fmt::Debug>(
+    layout: &HexLayout<V, isize>,
+    bound: &HexBound<isize>,
+    indexing: HexIndexing,
+    values: &[V],
+) {
+    assert_eq!(values.len(), layout.len());
+    for (pos, value) in bound.iter(indexing).zip(values) {
+        assert_eq!(layout.get(pos), Some(value));
+    }
+}
+
+/// Runs the benchmark.
+fn main() {
+    let bound = HexBound::new(HexPosition::ORIGIN, RADIUS);
+    let mut rng = Lcg(894);
+    let mut cells: HexLayout<bool, isize> =
+        HexLayout::new_from_range(RADIUS + 1, HexPosition::ORIGIN);
+    let mut heights: HexLayout<f64, isize> =
+        HexLayout::new_from_range(RADIUS + 1, HexPosition::ORIGIN);
+    for pos in bound.iter(HexIndexing::RowMajor) {
+        cells.set(pos, rng.below(2) == 0);
+        heights.set(pos, rng.below(1000) as f64);
+    }
+    let life_rule = |alive: bool, around: &mut dyn Iterator<Item = bool>| {
+        life(alive, around.filter(|n| *n).count())
+    };
+    let smooth_rule = |value: f64, around: &mut dyn Iterator<Item = f64>| smooth(value, around);
+
+    let cells_row = to_dense(&cells, &bound, HexIndexing::RowMajor);
+    let cells_spiral = to_dense(&cells, &bound, HexIndexing::Spiral);
+    let heights_row = to_dense(&heights, &bound, HexIndexing::RowMajor);
+    let heights_spiral = to_dense(&heights, &bound, HexIndexing::Spiral);
+
+    let next_cells = cells.step_ca(|_, alive, around| life(*alive, around.filter(|n| **n).count()));
+    let next_heights = heights.step_ca(|_, value, around| smooth(*value, around.copied()));
+    assert_same(
+        &next_cells,
+        &bound,
+        HexIndexing::RowMajor,
+        &step_indexed(&bound, HexIndexing::RowMajor, &cells_row, life_rule),
+    );
+    assert_same(
+        &next_cells,
+        &bound,
+        HexIndexing::Spiral,
+        &step_indexed(&bound, HexIndexing::Spiral, &cells_spiral, life_rule),
+    );
+    assert_same(
+        &next_cells,
+        &bound,
+        HexIndexing::RowMajor,
+        &step_runs(&bound, &cells_row, life_rule),
+    );
+    assert_same(
+        &next_heights,
+        &bound,
+        HexIndexing::RowMajor,
+        &step_indexed(&bound, HexIndexing::RowMajor, &heights_row, smooth_rule),
+    );
+    assert_same(
+        &next_heights,
+        &bound,
+        HexIndexing::Spiral,
+        &step_indexed(&bound, HexIndexing::Spiral, &heights_spiral, smooth_rule),
+    );
+    assert_same(
+        &next_heights,
+        &bound,
+        HexIndexing::RowMajor,
+        &step_runs(&bound, &heights_row, smooth_rule),
+    );
+
+    measure("game of life step, HexLayout::step_ca", 10, || {
+        cells.step_ca(|_, alive, around| life(*alive, around.filter(|n| **n).count()))
+    });
+    measure("game of life step, row-major with index_of", 10, || {
+        step_indexed(&bound, HexIndexing::RowMajor, &cells_row, life_rule)
+    });
+    measure("game of life step, spiral with index_of", 10, || {
+        step_indexed(&bound, HexIndexing::Spiral, &cells_spiral, life_rule)
+    });
+    measure("game of life step, iter_neighbor_runs", 10, || {
+        step_runs(&bound, &cells_row, life_rule)
+    });
+
+    measure("smoothing pass, HexLayout::step_ca", 10, || {
+        heights.step_ca(|_, value, around| smooth(*value, around.copied()))
+    });
+    measure("smoothing pass, row-major with index_of", 10, || {
+        step_indexed(&bound, HexIndexing::RowMajor, &heights_row, smooth_rule)
+    });
+    measure("smoothing pass, spiral with index_of", 10, || {
+        step_indexed(&bound, HexIndexing::Spiral, &heights_spiral, smooth_rule)
+    });
+    measure("smoothing pass, iter_neighbor_runs", 10, || {
+        step_runs(&bound, &heights_row, smooth_rule)
+    });
+}
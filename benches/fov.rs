@@ -0,0 +1,52 @@
+//! Compares [FovCache::update_blocked] with a new [HexLayout::field_of_view] each time a door
+//! of a radius-60 map is opened or closed.
+
+mod common;
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::layout::{FovCache, HexLayout};
+
+/// The radius of the map and of the field of view.
+const RADIUS: usize = 60;
+
+/// The number of times the door is toggled, with a new field of view after each toggle.
+const TOGGLES: usize = 100;
+
+/// Runs the benchmark.
+fn main() {
+    let mut rng = Lcg(895);
+    let mut map: HexLayout<bool, isize> =
+        HexLayout::new_from_range(RADIUS + 1, HexPosition::ORIGIN);
+    for pos in HexPosition::ORIGIN.spiral(RADIUS) {
+        map.set(pos, rng.below(10) == 0);
+    }
+    let center = HexPosition::ORIGIN;
+    let door = HexPosition(8, -3);
+    map.set(center, false);
+
+    let mut cache = FovCache::new(center, Some(RADIUS));
+    let mut checked = map.clone();
+    for i in 0..TOGGLES {
+        let visible = cache.update_blocked(&mut checked, door, i % 2 == 0).clone();
+        assert_eq!(visible, checked.field_of_view(center, Some(RADIUS)));
+    }
+
+    measure("new field_of_view after each toggle", 5, || {
+        let mut map = map.clone();
+        (0..TOGGLES)
+            .map(|i| {
+                map.set(door, i % 2 == 0);
+                map.field_of_view(center, Some(RADIUS)).len()
+            })
+            .sum::<usize>()
+    });
+    measure("FovCache::update_blocked after each toggle", 5, || {
+        let mut map = map.clone();
+        let mut cache = FovCache::new(center, Some(RADIUS));
+        cache.visible(&map);
+        (0..TOGGLES)
+            .map(|i| cache.update_blocked(&mut map, door, i % 2 == 0).len())
+            .sum::<usize>()
+    });
+}
@@ -0,0 +1,55 @@
+//! Compares the hashers of [HexLayout] on field of move and pathfinding queries.
+//!
+//! The `fxhash` feature changes the default hasher of the layouts and of the maps used by the algorithms,
+//! so run this benchmark once without it and once with it:
+//!
+//! ```text
+//! cargo bench --bench hashing
+//! cargo bench --bench hashing --features fxhash
+//! ```
+
+mod common;
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::layout::HexLayout;
+use hexing::utils::HexBuildHasher;
+
+/// The range of the map.
+const MAP_RANGE: usize = 60;
+
+/// Runs the benchmark.
+fn main() {
+    println!("hasher: {}", std::any::type_name::<HexBuildHasher>());
+
+    let mut rng = Lcg(892);
+    let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(MAP_RANGE, HexPosition::ORIGIN);
+    let positions: Vec<_> = map.positions().copied().collect();
+    for pos in &positions {
+        if rng.below(5) == 0 && pos.distance(HexPosition::ORIGIN) > 1 {
+            map.set(*pos, true);
+        }
+    }
+    let open: Vec<_> = map
+        .iter()
+        .filter(|(_, blocked)| !**blocked)
+        .map(|(pos, _)| *pos)
+        .collect();
+    let queries: Vec<_> = (0..1000)
+        .map(|_| {
+            let from = open[rng.below(open.len() as u64) as usize];
+            let to = open[rng.below(open.len() as u64) as usize];
+            (from, to)
+        })
+        .collect();
+
+    measure("field_of_move(range = 40)", 20, || {
+        map.field_of_move(HexPosition::ORIGIN, 40)
+    });
+    measure("1000 pathfinding queries", 5, || {
+        queries
+            .iter()
+            .map(|(from, to)| map.pathfinding(*from, *to).len())
+            .sum::<usize>()
+    });
+}
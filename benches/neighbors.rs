@@ -0,0 +1,112 @@
+//! Compares the allocations of a large pathfinding run when the neighbors are returned in a [HexNeighbors]
+//! and when they are collected into a `Vec`, like `neighbors_unblocked` used to do.
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::algorithms::astar;
+use hexing::layout::{HexLayout, HexNeighbors};
+
+/// An allocator counting the allocations of the program.
+struct CountingAllocator;
+
+/// The number of allocations since the start of the program.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+/// The allocator of the benchmark.
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// The range of the map.
+const MAP_RANGE: usize = 60;
+
+/// Returns the number of allocations made by `f`.
+fn count_allocations<R>(f: impl FnOnce() -> R) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    std::hint::black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// Runs the benchmark.
+fn main() {
+    let mut rng = Lcg(888);
+    let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(MAP_RANGE, HexPosition::ORIGIN);
+    let positions: Vec<_> = map.positions().copied().collect();
+    for pos in &positions {
+        if rng.below(5) == 0 {
+            map.set(*pos, true);
+        }
+    }
+    let open: Vec<_> = map
+        .iter()
+        .filter(|(_, blocked)| !**blocked)
+        .map(|(pos, _)| *pos)
+        .collect();
+    let queries: Vec<_> = (0..200)
+        .map(|_| {
+            let from = open[rng.below(open.len() as u64) as usize];
+            let to = open[rng.below(open.len() as u64) as usize];
+            (from, to)
+        })
+        .collect();
+
+    let inline = || {
+        queries
+            .iter()
+            .map(|(from, to)| {
+                let neighbors = |pos| -> HexNeighbors<isize> { map.neighbors_unblocked(pos) };
+                astar(
+                    *from,
+                    *to,
+                    neighbors,
+                    |_, _| 1,
+                    |pos| pos.distance(*to) as u32,
+                )
+                .map(|path| path.len())
+            })
+            .collect::<Vec<_>>()
+    };
+    let collected = || {
+        queries
+            .iter()
+            .map(|(from, to)| {
+                let neighbors = |pos| map.neighbors_unblocked(pos).collect::<Vec<_>>();
+                astar(
+                    *from,
+                    *to,
+                    neighbors,
+                    |_, _| 1,
+                    |pos| pos.distance(*to) as u32,
+                )
+                .map(|path| path.len())
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(inline(), collected());
+
+    println!(
+        "allocations with HexNeighbors: {}",
+        count_allocations(inline)
+    );
+    println!(
+        "allocations with Vec:          {}",
+        count_allocations(collected)
+    );
+    measure("200 pathfinding queries with HexNeighbors", 5, inline);
+    measure("200 pathfinding queries with Vec", 5, collected);
+}
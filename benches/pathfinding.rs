@@ -0,0 +1,62 @@
+//! Compares [DynamicPathfinder::replan] with a new A* search after each change of a map.
+
+mod common;
+
+use common::{Lcg, measure};
+use hexing::HexPosition;
+use hexing::layout::{DynamicPathfinder, HexLayout};
+
+/// The range of the map.
+const MAP_RANGE: usize = 40;
+
+/// The number of positions changed, with a new path searched after each change.
+const EDITS: usize = 200;
+
+/// Runs the benchmark.
+fn main() {
+    let mut rng = Lcg(885);
+    let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(MAP_RANGE, HexPosition::ORIGIN);
+    let mut positions: Vec<_> = map.positions().copied().collect();
+    positions.sort_by_key(|pos| (pos.0, pos.1));
+    for pos in &positions {
+        if rng.below(5) == 0 {
+            map.set(*pos, true);
+        }
+    }
+
+    let range = MAP_RANGE as isize - 1;
+    let (start, goal) = (HexPosition(-range, 0), HexPosition(range, 0));
+    map.set(start, false);
+    map.set(goal, false);
+    let edits: Vec<_> = (0..EDITS)
+        .map(|_| {
+            (
+                positions[rng.below(positions.len() as u64) as usize],
+                rng.below(5) == 0,
+            )
+        })
+        .filter(|(pos, _)| *pos != start && *pos != goal)
+        .collect();
+
+    measure("new search after each change", 5, || {
+        let mut map = map.clone();
+        edits
+            .iter()
+            .map(|(pos, blocked)| {
+                map.set(*pos, *blocked);
+                map.pathfinding(start, goal).len()
+            })
+            .sum::<usize>()
+    });
+    measure("DynamicPathfinder::replan after each change", 5, || {
+        let mut pathfinder = DynamicPathfinder::new(map.clone(), start, goal);
+        pathfinder.replan();
+        edits
+            .iter()
+            .map(|(pos, blocked)| {
+                pathfinder.update_blocked(*pos, *blocked);
+                pathfinder.replan().map_or(0, |path| path.len())
+            })
+            .sum::<usize>()
+    });
+}
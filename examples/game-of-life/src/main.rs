@@ -1,4 +1,4 @@
-use hexing::{layout::HexLayout, utils::neighbors, HexPosition};
+use hexing::{layout::HexLayout, HexPosition};
 use rand::Rng;
 
 const GRID_SIZE: usize = 10;
@@ -7,8 +7,13 @@ fn main() {
     let mut grid = HexLayout::<bool, isize>::new_from_range(GRID_SIZE, HexPosition(0, 0));
     initialize_grid(&mut grid);
 
+    let mut next_grid = grid.clone();
     for _ in 0..10 {
-        grid = next_generation(&grid);
+        let alive_neighbors = grid.neighbor_counts(|&alive| alive);
+        grid.step_ca_into(&mut next_grid, |pos, current_state, _| {
+            next_state(*current_state, alive_neighbors[&pos])
+        });
+        std::mem::swap(&mut grid, &mut next_grid);
     }
 }
 
@@ -20,25 +25,10 @@ fn initialize_grid(grid: &mut HexLayout<bool, isize>) {
     }
 }
 
-fn next_generation(grid: &HexLayout<bool, isize>) -> HexLayout<bool, isize> {
-    let mut next_grid = HexLayout::<bool, isize>::new_from_range(GRID_SIZE, HexPosition(0, 0));
-
-    for pos in grid.positions() {
-        let neighbors = neighbors(*pos);
-        let alive_neighbors = neighbors
-            .iter()
-            .filter(|&&neighbor| *grid.get(neighbor).unwrap_or(&false))
-            .count();
-
-        let current_state = *grid.get(*pos).unwrap();
-        let next_state = match (current_state, alive_neighbors) {
-            (true, x) if x < 2 || x > 2 => false,
-            (false, 2) => true,
-            _ => current_state,
-        };
-
-        next_grid.set(*pos, next_state);
+fn next_state(current_state: bool, alive_neighbors: u8) -> bool {
+    match (current_state, alive_neighbors) {
+        (true, x) if x < 2 || x > 2 => false,
+        (false, 2) => true,
+        _ => current_state,
     }
-
-    next_grid
 }
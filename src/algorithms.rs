@@ -0,0 +1,618 @@
+//! # Algorithms on hexagonal grids.
+//!
+//! This module contains the pathfinding, field of view and field of move algorithms as free functions.
+//! They don't depend on any storage: the grid is described by closures (neighbors, costs, opacity, ...),
+//! so they can be used with any data structure. [HexLayout](crate::layout::HexLayout) uses them internally.
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use priority_queue::PriorityQueue;
+
+use crate::utils::{HexBuildHasher, neighbors};
+use crate::*;
+
+/// Finds the cheapest path between two positions with the A* algorithm.
+///
+/// The grid is described by closures:
+/// - `neighbors` returns the positions that can be reached in one move from a position.
+/// - `cost` returns the cost of a move between two adjacent positions.
+/// - `heuristic` estimates the remaining cost from a position to `to`. It must never overestimate
+///   the real cost for the path to be the cheapest one (the distance to `to` is a good choice with unit costs).
+///
+/// Returns the path from `from` to `to` (both included), or `None` if `to` can't be reached.
+/// Ties between positions with the same estimated cost are broken by the heuristic, then by position,
+/// so the same path is always returned for the same grid, whatever the order of its storage.
+/// For more information, see the [documentation](https://www.redblobgames.com/pathfinding/a-star/introduction.html).
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use hexing::{algorithms::astar, utils::neighbors, HexPosition};
+///
+/// // An unbounded grid with a few walls, stored in a simple set.
+/// let walls: HashSet<_> = [HexPosition(1, 0), HexPosition(1, -1), HexPosition(0, 1)].into();
+/// let goal = HexPosition(3, 0);
+///
+/// let path = astar(
+///     HexPosition(0, 0),
+///     goal,
+///     |pos| neighbors(pos).into_iter().filter(|n| !walls.contains(n)),
+///     |_, _| 1,
+///     |pos| pos.distance(goal) as u32,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.len(), 7);
+/// assert!(path.iter().all(|pos| !walls.contains(pos)));
+/// ```
+pub fn astar<T, I, N, C, H>(
+    from: HexPosition<T>,
+    to: HexPosition<T>,
+    neighbors: N,
+    cost: C,
+    heuristic: H,
+) -> Option<Vec<HexPosition<T>>>
+where
+    T: Number,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+    C: Fn(HexPosition<T>, HexPosition<T>) -> u32,
+    H: Fn(HexPosition<T>) -> u32,
+{
+    astar_to_any(from, |pos| pos == to, neighbors, cost, heuristic)
+}
+
+/// Finds the cheapest path from a position to any of the positions for which `is_goal` returns `true`,
+/// with a single A* search.
+///
+/// The closures are the same as for [astar], but `heuristic` must estimate the remaining cost to the nearest goal
+/// (the distance to the nearest goal is a good choice with unit costs).
+/// Returns the path from `from` to the reached goal (both included), or `None` if no goal can be reached.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{algorithms::astar_to_any, utils::neighbors, HexPosition};
+///
+/// let goals = [HexPosition(4, 0), HexPosition(-2, 1)];
+/// let path = astar_to_any(
+///     HexPosition(0, 0),
+///     |pos| goals.contains(&pos),
+///     neighbors,
+///     |_, _| 1,
+///     |pos| goals.iter().map(|goal| pos.distance(*goal) as u32).min().unwrap_or(0),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(path.last(), Some(&HexPosition(-2, 1)));
+/// assert_eq!(path.len(), 3);
+/// ```
+pub fn astar_to_any<T, G, I, N, C, H>(
+    from: HexPosition<T>,
+    is_goal: G,
+    neighbors: N,
+    cost: C,
+    heuristic: H,
+) -> Option<Vec<HexPosition<T>>>
+where
+    T: Number,
+    G: Fn(HexPosition<T>) -> bool,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+    C: Fn(HexPosition<T>, HexPosition<T>) -> u32,
+    H: Fn(HexPosition<T>) -> u32,
+{
+    search(
+        from,
+        is_goal,
+        neighbors,
+        cost,
+        heuristic,
+        SearchOptions::default(),
+    )
+    .0
+}
+
+/// The options of a search with [astar_with_options].
+///
+/// The default options give the same search as [astar].
+///
+/// # Example
+///
+/// ```
+/// use hexing::algorithms::SearchOptions;
+///
+/// let dijkstra = SearchOptions { heuristic_weight: 0.0, ..Default::default() };
+/// assert_eq!(dijkstra.max_expansions, None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SearchOptions {
+    /// The factor applied to the heuristic. With `0.0`, the search is a Dijkstra search, which finds the cheapest
+    /// path even if the heuristic overestimates the costs. With `1.0`, it is a classic A* search. Above `1.0`,
+    /// the search usually explores fewer positions but the path can be more expensive than the cheapest one.
+    pub heuristic_weight: f32,
+
+    /// The maximum number of positions taken out of the frontier before the search is aborted, if any.
+    pub max_expansions: Option<usize>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            heuristic_weight: 1.0,
+            max_expansions: None,
+        }
+    }
+}
+
+/// Statistics about a search with [astar_with_options], to tune its [SearchOptions].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// The number of positions taken out of the frontier (including the goal).
+    pub expanded: usize,
+}
+
+/// Same as [astar], with [SearchOptions] to weight the heuristic or limit the search, and statistics about the search.
+///
+/// Returns `None` as the path if `to` can't be reached or if the search was aborted after
+/// [SearchOptions::max_expansions] expanded positions.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{algorithms::{astar_with_options, SearchOptions}, utils::{neighbors, HexBound}, HexPosition};
+///
+/// // Two walls, with a gap at opposite ends.
+/// let bound = HexBound::new(HexPosition(0, 0), 10);
+/// let is_wall = |pos: HexPosition<i32>| (pos.0 == -3 && pos.1 > -5) || (pos.0 == 3 && pos.1 < 5);
+/// let passable = |pos| neighbors(pos).into_iter().filter(move |n| bound.contains(*n) && !is_wall(*n));
+/// let (from, to) = (HexPosition(-7, 0), HexPosition(7, 0));
+/// let heuristic = |pos: HexPosition<i32>| pos.distance(to) as u32;
+///
+/// let search = |heuristic_weight, max_expansions| {
+///     let options = SearchOptions { heuristic_weight, max_expansions };
+///     astar_with_options(from, to, passable, |_, _| 1, heuristic, options)
+/// };
+/// let (dijkstra, dijkstra_stats) = search(0.0, None);
+/// let (classic, classic_stats) = search(1.0, None);
+/// let (greedy, greedy_stats) = search(3.0, None);
+/// let (dijkstra, classic, greedy) = (dijkstra.unwrap(), classic.unwrap(), greedy.unwrap());
+///
+/// // Dijkstra and A* find the cheapest path, the weighted search explores less but may find a longer one.
+/// assert_eq!(dijkstra.len(), classic.len());
+/// assert!(greedy.len() >= classic.len());
+/// assert!(dijkstra_stats.expanded > classic_stats.expanded);
+/// assert!(classic_stats.expanded > greedy_stats.expanded);
+///
+/// // The same search as astar with the default options.
+/// let path = hexing::algorithms::astar(from, to, passable, |_, _| 1, heuristic);
+/// assert_eq!(Some(classic), path);
+///
+/// // A search aborted before reaching the goal.
+/// let (aborted, stats) = search(1.0, Some(10));
+/// assert_eq!(aborted, None);
+/// assert_eq!(stats.expanded, 10);
+/// ```
+pub fn astar_with_options<T, I, N, C, H>(
+    from: HexPosition<T>,
+    to: HexPosition<T>,
+    neighbors: N,
+    cost: C,
+    heuristic: H,
+    options: SearchOptions,
+) -> (Option<Vec<HexPosition<T>>>, SearchStats)
+where
+    T: Number,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+    C: Fn(HexPosition<T>, HexPosition<T>) -> u32,
+    H: Fn(HexPosition<T>) -> u32,
+{
+    search(from, |pos| pos == to, neighbors, cost, heuristic, options)
+}
+
+/// The scale of the fixed-point priorities of [search], so that the weighted heuristic keeps some precision.
+const PRIORITY_SCALE: f64 = 1024.0;
+
+/// The A* search behind [astar_to_any] and [astar_with_options].
+fn search<T, G, I, N, C, H>(
+    from: HexPosition<T>,
+    is_goal: G,
+    neighbors: N,
+    cost: C,
+    heuristic: H,
+    options: SearchOptions,
+) -> (Option<Vec<HexPosition<T>>>, SearchStats)
+where
+    T: Number,
+    G: Fn(HexPosition<T>) -> bool,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+    C: Fn(HexPosition<T>, HexPosition<T>) -> u32,
+    H: Fn(HexPosition<T>) -> u32,
+{
+    let mut stats = SearchStats::default();
+    if is_goal(from) {
+        return (Some(vec![from]), stats);
+    }
+
+    let weight = f64::from(options.heuristic_weight.max(0.0)) * PRIORITY_SCALE;
+
+    let mut frontier = PriorityQueue::<_, _, HexBuildHasher>::with_default_hasher();
+    frontier.push(from, Reverse((0, 0, from.0.to_isize(), from.1.to_isize())));
+
+    let mut came_from: HashMap<HexPosition<T>, HexPosition<T>, HexBuildHasher> = HashMap::default();
+    let mut cost_so_far: HashMap<HexPosition<T>, u32, HexBuildHasher> = HashMap::default();
+    cost_so_far.insert(from, 0);
+
+    while let Some((current, _)) = frontier.pop() {
+        if options
+            .max_expansions
+            .is_some_and(|max| stats.expanded >= max)
+        {
+            return (None, stats);
+        }
+        stats.expanded += 1;
+
+        if is_goal(current) {
+            let mut path = vec![current];
+            let mut current = current;
+            while let Some(prev) = came_from.get(&current) {
+                path.push(*prev);
+                current = *prev;
+            }
+            path.reverse();
+            return (Some(path), stats);
+        }
+
+        let current_cost = cost_so_far.get(&current).copied().unwrap_or_default();
+        for next in neighbors(current) {
+            let new_cost = current_cost + cost(current, next);
+            if cost_so_far.get(&next).is_none_or(|c| new_cost < *c) {
+                cost_so_far.insert(next, new_cost);
+                let estimate = heuristic(next);
+                let priority = u64::from(new_cost) * PRIORITY_SCALE as u64
+                    + (f64::from(estimate) * weight).round() as u64;
+                frontier.push(
+                    next,
+                    Reverse((priority, estimate, next.0.to_isize(), next.1.to_isize())),
+                );
+                came_from.insert(next, current);
+            }
+        }
+    }
+
+    (None, stats)
+}
+
+/// Finds the cost of the cheapest path between two positions with the A* algorithm, without building the path.
+///
+/// The closures are the same as for [astar], and the returned cost is the sum of the costs of the moves
+/// along the path it would return. Returns `None` if `to` can't be reached.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use hexing::{algorithms::{astar, astar_cost}, utils::neighbors, HexPosition};
+///
+/// let walls: HashSet<_> = [HexPosition(1, 0), HexPosition(1, -1), HexPosition(0, 1)].into();
+/// let goal = HexPosition(3, 0);
+/// let passable = |pos| neighbors(pos).into_iter().filter(|n| !walls.contains(n));
+/// let heuristic = |pos: HexPosition<i32>| pos.distance(goal) as u32;
+///
+/// let path = astar(HexPosition(0, 0), goal, passable, |_, _| 1, heuristic).unwrap();
+/// let cost = astar_cost(HexPosition(0, 0), goal, passable, |_, _| 1, heuristic);
+/// assert_eq!(cost, Some(path.len() as u32 - 1));
+/// ```
+pub fn astar_cost<T, I, N, C, H>(
+    from: HexPosition<T>,
+    to: HexPosition<T>,
+    neighbors: N,
+    cost: C,
+    heuristic: H,
+) -> Option<u32>
+where
+    T: Number,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+    C: Fn(HexPosition<T>, HexPosition<T>) -> u32,
+    H: Fn(HexPosition<T>) -> u32,
+{
+    let mut frontier = PriorityQueue::<_, _, HexBuildHasher>::with_default_hasher();
+    frontier.push(from, Reverse((0, 0, from.0.to_isize(), from.1.to_isize())));
+
+    let mut cost_so_far: HashMap<HexPosition<T>, u32, HexBuildHasher> = HashMap::default();
+    cost_so_far.insert(from, 0);
+
+    while let Some((current, _)) = frontier.pop() {
+        let current_cost = cost_so_far.get(&current).copied().unwrap_or_default();
+        if current == to {
+            return Some(current_cost);
+        }
+
+        for next in neighbors(current) {
+            let new_cost = current_cost + cost(current, next);
+            if cost_so_far.get(&next).is_none_or(|c| new_cost < *c) {
+                cost_so_far.insert(next, new_cost);
+                let estimate = heuristic(next);
+                frontier.push(
+                    next,
+                    Reverse((
+                        new_cost + estimate,
+                        estimate,
+                        next.0.to_isize(),
+                        next.1.to_isize(),
+                    )),
+                );
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds the costs of the cheapest paths from `from` to each of the `targets`, with a single Dijkstra search.
+///
+/// The closures are the same as for [astar]. The search stops as soon as the cost of every target is known,
+/// so it doesn't explore the positions farther than the farthest target.
+/// The element `i` of the returned vector is the cost to `targets[i]`, or `None` if it can't be reached.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use hexing::{algorithms::dijkstra_costs, utils::neighbors, HexPosition};
+///
+/// // The grid is unbounded: the search must stop by itself.
+/// let explored = Cell::new(0);
+/// let costs = dijkstra_costs(
+///     HexPosition(0, 0),
+///     &[HexPosition(2, 0), HexPosition(0, 0), HexPosition(-1, 0)],
+///     |pos| {
+///         explored.set(explored.get() + 1);
+///         neighbors(pos)
+///     },
+///     |_, _| 1,
+/// );
+///
+/// assert_eq!(costs, vec![Some(2), Some(0), Some(1)]);
+/// // Only the positions within 2 moves are explored.
+/// assert!(explored.get() <= 19);
+/// ```
+pub fn dijkstra_costs<T, I, N, C>(
+    from: HexPosition<T>,
+    targets: &[HexPosition<T>],
+    neighbors: N,
+    cost: C,
+) -> Vec<Option<u32>>
+where
+    T: Number,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+    C: Fn(HexPosition<T>, HexPosition<T>) -> u32,
+{
+    let mut costs = vec![None; targets.len()];
+    let mut remaining: HashSet<HexPosition<T>, HexBuildHasher> = targets.iter().copied().collect();
+
+    let mut frontier = PriorityQueue::<_, _, HexBuildHasher>::with_default_hasher();
+    frontier.push(from, Reverse(0));
+    let mut cost_so_far: HashMap<HexPosition<T>, u32, HexBuildHasher> = HashMap::default();
+    cost_so_far.insert(from, 0);
+
+    while let Some((current, Reverse(current_cost))) = frontier.pop() {
+        if remaining.remove(&current) {
+            for (target, target_cost) in targets.iter().zip(costs.iter_mut()) {
+                if *target == current {
+                    *target_cost = Some(current_cost);
+                }
+            }
+            if remaining.is_empty() {
+                break;
+            }
+        }
+
+        for next in neighbors(current) {
+            let new_cost = current_cost + cost(current, next);
+            if cost_so_far.get(&next).is_none_or(|c| new_cost < *c) {
+                cost_so_far.insert(next, new_cost);
+                frontier.push(next, Reverse(new_cost));
+            }
+        }
+    }
+
+    costs
+}
+
+/// Computes the positions reachable from `start`, grouped by their exact number of moves.
+///
+/// The element `k` of the returned vector contains the positions reached in exactly `k` moves (and not less),
+/// where `neighbors` returns the positions that can be reached in one move from a position.
+/// The search stops after `range` moves or as soon as no new position can be reached,
+/// so the returned vector is truncated after the last non-empty fringe.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{algorithms::bfs_fringes, utils::neighbors, HexPosition};
+///
+/// let fringes = bfs_fringes(HexPosition(0, 0), 2, neighbors);
+///
+/// assert_eq!(fringes[0], vec![HexPosition(0, 0)]);
+/// assert_eq!(fringes[1].len(), 6);
+/// assert_eq!(fringes[2].len(), 12);
+/// ```
+pub fn bfs_fringes<T, I, N>(
+    start: HexPosition<T>,
+    range: usize,
+    neighbors: N,
+) -> Vec<Vec<HexPosition<T>>>
+where
+    T: Number,
+    I: IntoIterator<Item = HexPosition<T>>,
+    N: Fn(HexPosition<T>) -> I,
+{
+    let mut visited: HashSet<HexPosition<T>, HexBuildHasher> = HashSet::default();
+    visited.insert(start);
+    let mut fringes = vec![vec![start]];
+
+    for k in 1..=range {
+        let mut to_add = Vec::with_capacity(fringes[k - 1].len() * 6);
+
+        for pos in fringes[k - 1].iter() {
+            for neighbor in neighbors(*pos) {
+                if visited.insert(neighbor) {
+                    to_add.push(neighbor);
+                }
+            }
+        }
+
+        if to_add.is_empty() {
+            break;
+        }
+        fringes.push(to_add);
+    }
+
+    fringes
+}
+
+/// Computes the set of positions reachable from `start` within `range` moves, `start` included.
+///
+/// A move goes from a position to one of its six neighbors, if `passable` returns `true` for it.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{algorithms::bfs_reachable, HexPosition};
+///
+/// // Only the positions of the ring of radius 1 are passable.
+/// let reachable = bfs_reachable(HexPosition(0, 0), 3, |pos| pos.is_on_ring(HexPosition(0, 0), 1));
+/// assert_eq!(reachable.len(), 7);
+/// ```
+pub fn bfs_reachable<T, P>(
+    start: HexPosition<T>,
+    range: usize,
+    passable: P,
+) -> HashSet<HexPosition<T>>
+where
+    T: Number,
+    P: Fn(HexPosition<T>) -> bool,
+{
+    bfs_fringes(start, range, |pos| {
+        neighbors(pos)
+            .into_iter()
+            .filter(|neighbor| passable(*neighbor))
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Computes the set of positions visible from `center` within `range`.
+///
+/// A position is visible if every position of the line from `center` to it (both included) is in bounds
+/// (`in_bounds` returns `true`) and is not opaque (`is_opaque` returns `false`).
+/// Note that an opaque position is therefore never visible itself.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{algorithms::fov, utils::HexBound, HexPosition};
+///
+/// let bound = HexBound::new(HexPosition(0, 0), 3);
+/// let visible = fov(
+///     HexPosition(0, 0),
+///     3,
+///     |pos| pos == HexPosition(1, 0),
+///     |pos| bound.contains(pos),
+/// );
+///
+/// assert!(visible.contains(&HexPosition(0, 0)));
+/// assert!(!visible.contains(&HexPosition(1, 0)));
+/// assert!(!visible.contains(&HexPosition(3, 0)));
+/// assert!(visible.contains(&HexPosition(-3, 0)));
+/// ```
+pub fn fov<T, O, B>(
+    center: HexPosition<T>,
+    range: usize,
+    is_opaque: O,
+    in_bounds: B,
+) -> HashSet<HexPosition<T>>
+where
+    T: Number,
+    O: Fn(HexPosition<T>) -> bool,
+    B: Fn(HexPosition<T>) -> bool,
+{
+    fov_with_edges(center, range, is_opaque, in_bounds, |_, _| false)
+}
+
+/// Same as [fov], but the line of sight can also be stopped between two positions, like by a wall.
+///
+/// A position is hidden if `blocks_edge` returns `true` for two consecutive positions of the line from `center` to it.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{algorithms::fov_with_edges, utils::HexBound, HexPosition};
+///
+/// let bound = HexBound::new(HexPosition(0, 0), 3);
+/// let wall = (HexPosition(0, 0), HexPosition(1, 0));
+/// let visible = fov_with_edges(
+///     HexPosition(0, 0),
+///     3,
+///     |_| false,
+///     |pos| bound.contains(pos),
+///     |from, to| (from, to) == wall || (to, from) == wall,
+/// );
+///
+/// // Both positions are open, but the wall between them hides the other side.
+/// assert!(visible.contains(&HexPosition(0, 0)));
+/// assert!(!visible.contains(&HexPosition(1, 0)));
+/// assert!(!visible.contains(&HexPosition(3, 0)));
+/// assert!(visible.contains(&HexPosition(1, -1)));
+/// ```
+pub fn fov_with_edges<T, O, B, E>(
+    center: HexPosition<T>,
+    range: usize,
+    is_opaque: O,
+    in_bounds: B,
+    blocks_edge: E,
+) -> HashSet<HexPosition<T>>
+where
+    T: Number,
+    O: Fn(HexPosition<T>) -> bool,
+    B: Fn(HexPosition<T>) -> bool,
+    E: Fn(HexPosition<T>, HexPosition<T>) -> bool,
+{
+    center
+        .spiral(range)
+        .filter(|position| {
+            let mut previous = None;
+            center.line_to(*position).all(|between| {
+                let crossed = previous.is_some_and(|previous| blocks_edge(previous, between));
+                previous = Some(between);
+                !crossed && in_bounds(between) && !is_opaque(between)
+            })
+        })
+        .collect()
+}
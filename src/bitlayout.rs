@@ -0,0 +1,367 @@
+//! # Bit-packed obstacle layers.
+//!
+//! A [HexBitLayout] stores one bit per position of a fixed [HexBound], instead of a [HexLayout] entry.
+//! It is meant for large boolean masks, like blocked tiles or fog of war, and can be used in place of a
+//! `HexLayout<bool, _>` for pathfinding and field of view.
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use layout::{HexLayout, HexNeighbors, HexPath};
+use utils::{HexBound, HexIndexing};
+
+use crate::*;
+
+/// The number of bits in a word of a [HexBitLayout].
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A boolean layout over a fixed hexagonal area, storing one bit per position.
+///
+/// Like a `HexLayout<bool, _>`, `true` means that the position is blocked. The positions outside of the
+/// bound are not in the layout. The bitwise operators (`&`, `|`, `^` and `!`) combine whole layouts
+/// word by word, and panic if the bounds of the layouts are different.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use hexing::{bitlayout::HexBitLayout, utils::HexBound, HexPosition};
+///
+/// // A radius of 300 takes less than 40 KB.
+/// let mut walls: HexBitLayout<i32> = HexBitLayout::new(HexBound::new(HexPosition(0, 0), 300));
+/// assert_eq!(walls.len(), 270_901);
+/// assert!(std::mem::size_of_val(walls.as_words()) < 40_000);
+///
+/// assert_eq!(walls.set(HexPosition(120, -40), true), Some(false));
+/// assert_eq!(walls.get(HexPosition(120, -40)), Some(true));
+/// assert_eq!(walls.set(HexPosition(301, 0), true), None);
+///
+/// // The combinators give the same result as the set operations on the blocked positions.
+/// let bound = HexBound::new(HexPosition(3, -1), 6);
+/// let mut seed = 42u64;
+/// let mut random_layout = || {
+///     let mut layout = HexBitLayout::new(bound);
+///     for pos in bound.iter(hexing::utils::HexIndexing::RowMajor) {
+///         seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///         layout.set(pos, (seed >> 33) % 3 == 0);
+///     }
+///     layout
+/// };
+/// let (a, b) = (random_layout(), random_layout());
+/// let blocked = |layout: &HexBitLayout<i32>| -> HashSet<HexPosition<i32>> {
+///     layout.iter().filter(|(_, blocked)| *blocked).map(|(pos, _)| pos).collect()
+/// };
+///
+/// assert_eq!(blocked(&(&a & &b)), &blocked(&a) & &blocked(&b));
+/// assert_eq!(blocked(&(&a | &b)), &blocked(&a) | &blocked(&b));
+/// assert_eq!(blocked(&(&a ^ &b)), &blocked(&a) ^ &blocked(&b));
+/// assert_eq!((!&a).count_ones(), a.len() - a.count_ones());
+///
+/// let mut c = a.clone();
+/// c |= &b;
+/// assert_eq!(c, &a | &b);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexBitLayout<T: Number> {
+    /// The area covered by the layout.
+    bound: HexBound<T>,
+
+    /// The bits of the positions of `bound`, in the order of [HexIndexing::RowMajor].
+    /// The bits after the last position are always unset.
+    words: Vec<u64>,
+}
+
+impl<T: Number> HexBitLayout<T> {
+    /// Creates a new layout covering `bound`, where no position is blocked.
+    pub fn new(bound: HexBound<T>) -> Self {
+        Self {
+            bound,
+            words: vec![0; bound.len().div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Creates a layout from a [HexLayout], covering its [bounding hex](HexLayout::bounding_hex).
+    ///
+    /// The positions of the bound that are missing from `layout` are blocked, so paths and fields of move
+    /// are the same in both layouts. An empty layout gives a single blocked position at the origin.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{bitlayout::HexBitLayout, layout::HexLayout, HexPosition};
+    ///
+    /// let mut seed = 7u64;
+    /// let mut layout: HexLayout<bool, isize> = HexLayout::new_from_range(9, HexPosition(1, -2));
+    /// layout.apply(|_, blocked| {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     *blocked = (seed >> 33) % 10 < 3;
+    /// });
+    /// let bits = HexBitLayout::from_layout(&layout);
+    ///
+    /// assert_eq!(bits.bound(), layout.bounding_hex().unwrap());
+    /// assert_eq!(bits.count_ones(), layout.blocked_count());
+    /// assert!(layout.iter().all(|(pos, blocked)| bits.get(*pos) == Some(*blocked)));
+    /// assert!(bits.to_layout().iter().all(|(pos, blocked)| layout.get(*pos) == Some(blocked)));
+    ///
+    /// let open: Vec<_> = layout.entries_sorted().into_iter().filter(|(_, blocked)| !**blocked).map(|(pos, _)| pos).collect();
+    /// for (i, &from) in open.iter().enumerate().step_by(7) {
+    ///     let to = open[(i * 13 + 5) % open.len()];
+    ///     assert_eq!(bits.pathfinding(from, to), layout.pathfinding(from, to));
+    ///     assert_eq!(bits.field_of_view(from, Some(4)), layout.field_of_view(from, Some(4)));
+    ///     assert_eq!(bits.field_of_view(from, None), layout.field_of_view(from, None));
+    ///     assert_eq!(bits.field_of_move(from, 5), layout.field_of_move(from, 5));
+    ///     assert!(bits.neighbors_unblocked(from).eq(layout.neighbors_unblocked(from)));
+    /// }
+    /// ```
+    pub fn from_layout(layout: &HexLayout<bool, T>) -> Self {
+        let bound = layout
+            .bounding_hex()
+            .unwrap_or_else(|| HexBound::new(HexPosition::ORIGIN, 0));
+        let mut result = !&Self::new(bound);
+        for (pos, blocked) in layout.iter() {
+            if !blocked {
+                result.set(*pos, false);
+            }
+        }
+        result
+    }
+
+    /// Converts the layout to a [HexLayout] with every position of the bound.
+    pub fn to_layout(&self) -> HexLayout<bool, T> {
+        self.iter().collect()
+    }
+
+    /// Returns the area covered by the layout.
+    pub const fn bound(&self) -> HexBound<T> {
+        self.bound
+    }
+
+    /// Returns the number of positions in the layout, which is the number of positions of its bound.
+    pub const fn len(&self) -> usize {
+        self.bound.len()
+    }
+
+    /// Returns `false`: the bound of a layout always contains at least its center.
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the words storing the bits of the positions, in the order of [HexIndexing::RowMajor].
+    /// The bit `i % 64` of the word `i / 64` is the bit of the position at index `i`.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    /// Returns whether the position is blocked, or `None` if it is outside of the bound.
+    pub fn get(&self, pos: HexPosition<T>) -> Option<bool> {
+        let index = self.bound.index_of(pos, HexIndexing::RowMajor)?;
+        Some(self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1)
+    }
+
+    /// Sets whether the position is blocked, and returns the previous value.
+    /// Returns `None` and does nothing if the position is outside of the bound.
+    pub fn set(&mut self, pos: HexPosition<T>, blocked: bool) -> Option<bool> {
+        let index = self.bound.index_of(pos, HexIndexing::RowMajor)?;
+        let (word, mask) = (&mut self.words[index / WORD_BITS], 1 << (index % WORD_BITS));
+        let previous = *word & mask != 0;
+        if blocked {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+        Some(previous)
+    }
+
+    /// Sets every position of the layout to `blocked`.
+    pub fn fill(&mut self, blocked: bool) {
+        self.words.fill(if blocked { u64::MAX } else { 0 });
+        self.clear_tail();
+    }
+
+    /// Returns the number of blocked positions.
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns an iterator over the positions of the layout and whether they are blocked,
+    /// in the order of [HexIndexing::RowMajor].
+    pub fn iter(&self) -> impl Iterator<Item = (HexPosition<T>, bool)> + '_ {
+        self.bound
+            .iter(HexIndexing::RowMajor)
+            .enumerate()
+            .map(|(index, pos)| {
+                let blocked = self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1;
+                (pos, blocked)
+            })
+    }
+
+    /// Returns an iterator over all the neighbors that are in the layout and not blocked,
+    /// like [HexLayout::neighbors_unblocked].
+    pub fn neighbors_unblocked(&self, pos: HexPosition<T>) -> HexNeighbors<T> {
+        let mut result_neighbors = HexNeighbors::default();
+        for direction in HexDirection::iter() {
+            let neighbor = pos + direction.to_vector();
+            if self.get(neighbor) == Some(false) {
+                result_neighbors.push(neighbor);
+            }
+        }
+        result_neighbors
+    }
+
+    /// Finds the shortest path between two positions, like [HexLayout::pathfinding].
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if either `from` or `to` are outside of the bound.
+    pub fn pathfinding(&self, from: HexPosition<T>, to: HexPosition<T>) -> HexPath<T> {
+        if from == to {
+            return HexPath(vec![from]);
+        }
+
+        if !self.bound.contains(from) || !self.bound.contains(to) {
+            panic!("Position not in layout");
+        }
+
+        HexPath(
+            algorithms::astar(
+                from,
+                to,
+                |pos| self.neighbors_unblocked(pos),
+                |_, _| 1,
+                |pos| pos.distance(to).to_isize() as u32,
+            )
+            .unwrap_or_else(|| vec![to]),
+        )
+    }
+
+    /// Computes the set of positions visible from `center`, like [HexLayout::field_of_view].
+    ///
+    /// Without a range, the whole layout is considered.
+    pub fn field_of_view(
+        &self,
+        center: HexPosition<T>,
+        range: Option<usize>,
+    ) -> HashSet<HexPosition<T>> {
+        if self.get(center) != Some(false) {
+            return HashSet::new();
+        }
+        let range = range.unwrap_or_else(|| {
+            center.distance(self.bound.center()).to_isize() as usize + self.bound.radius()
+        });
+
+        algorithms::fov(
+            center,
+            range,
+            |pos| self.get(pos) == Some(true),
+            |pos| self.bound.contains(pos),
+        )
+    }
+
+    /// Computes the set of positions reachable from `pos` within `range` steps, like [HexLayout::field_of_move].
+    pub fn field_of_move(&self, pos: HexPosition<T>, range: usize) -> HashSet<HexPosition<T>> {
+        algorithms::bfs_fringes(pos, range, |pos| self.neighbors_unblocked(pos))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Unsets the bits after the last position of the layout.
+    fn clear_tail(&mut self) {
+        let used = self.len() % WORD_BITS;
+        if let (Some(last), true) = (self.words.last_mut(), used != 0) {
+            *last &= (1 << used) - 1;
+        }
+    }
+
+    /// Combines the words of two layouts with `f`.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if the layouts don't have the same bound.
+    fn combine(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(self.bound, other.bound, "Layouts with different bounds");
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word = f(*word, *other);
+        }
+    }
+}
+
+impl<T: Number> BitAndAssign<&Self> for HexBitLayout<T> {
+    fn bitand_assign(&mut self, rhs: &Self) {
+        self.combine(rhs, |a, b| a & b);
+    }
+}
+
+impl<T: Number> BitOrAssign<&Self> for HexBitLayout<T> {
+    fn bitor_assign(&mut self, rhs: &Self) {
+        self.combine(rhs, |a, b| a | b);
+    }
+}
+
+impl<T: Number> BitXorAssign<&Self> for HexBitLayout<T> {
+    fn bitxor_assign(&mut self, rhs: &Self) {
+        self.combine(rhs, |a, b| a ^ b);
+    }
+}
+
+impl<T: Number> BitAnd for &HexBitLayout<T> {
+    type Output = HexBitLayout<T>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result &= rhs;
+        result
+    }
+}
+
+impl<T: Number> BitOr for &HexBitLayout<T> {
+    type Output = HexBitLayout<T>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result |= rhs;
+        result
+    }
+}
+
+impl<T: Number> BitXor for &HexBitLayout<T> {
+    type Output = HexBitLayout<T>;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        let mut result = self.clone();
+        result ^= rhs;
+        result
+    }
+}
+
+impl<T: Number> Not for &HexBitLayout<T> {
+    type Output = HexBitLayout<T>;
+
+    fn not(self) -> Self::Output {
+        let mut result = self.clone();
+        for word in &mut result.words {
+            *word = !*word;
+        }
+        result.clear_tail();
+        result
+    }
+}
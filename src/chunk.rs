@@ -0,0 +1,241 @@
+//! # Chunked storage for unbounded maps.
+//!
+//! A [HexChunkMap] splits the plane into super-hexes (see [super_hex_center](crate::utils::super_hex_center)),
+//! called chunks, and stores the data of each loaded chunk in a dense block.
+//! Chunks can be loaded and unloaded independently, so only the part of the world around the players is kept in memory.
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use layout::{HexLayout, HexPath};
+use utils::{
+    HexBound, HexBuildHasher, HexIndexing, super_hex_center, super_hex_members, to_super_hex,
+};
+
+use crate::*;
+
+/// A map of hexagonal positions stored by chunks, for unbounded worlds.
+///
+/// Each chunk is the super-hex of radius `chunk_radius` around its center, and is identified by its
+/// super position (see [to_super_hex]). The positions of a chunk are all loaded or unloaded together.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{chunk::HexChunkMap, HexPosition};
+///
+/// let mut map: HexChunkMap<u8, i32> = HexChunkMap::new(2);
+///
+/// // Setting a position loads its chunk, with default data.
+/// map.set(HexPosition(2, 0), 5);
+/// assert_eq!(map.loaded_chunks().count(), 1);
+/// assert_eq!(map.len(), 19);
+///
+/// // (3, -1) is on the other side of the seam, in an unloaded chunk.
+/// assert_eq!(map.get(HexPosition(2, 0)), Some(&5));
+/// assert_eq!(map.get(HexPosition(3, -1)), None);
+///
+/// map.load_chunk(map.chunk_of(HexPosition(3, -1)), |pos| pos.0 as u8);
+/// assert_eq!(map.get(HexPosition(3, -1)), Some(&3));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HexChunkMap<D, T: Number> {
+    /// The radius of the chunks.
+    chunk_radius: usize,
+
+    /// The data of the loaded chunks, in the order of [HexIndexing::Spiral] around their center.
+    chunks: HashMap<HexPosition<T>, Vec<D>, HexBuildHasher>,
+}
+
+impl<D: Default, T: Number> HexChunkMap<D, T> {
+    /// Creates a new map without any loaded chunk. Each chunk covers the positions within `chunk_radius` of its center.
+    pub fn new(chunk_radius: usize) -> Self {
+        Self {
+            chunk_radius,
+            chunks: HashMap::default(),
+        }
+    }
+
+    /// Returns the radius of the chunks.
+    pub const fn chunk_radius(&self) -> usize {
+        self.chunk_radius
+    }
+
+    /// Returns the chunk containing the given position.
+    pub fn chunk_of(&self, pos: HexPosition<T>) -> HexPosition<T> {
+        to_super_hex(pos, self.chunk_radius)
+    }
+
+    /// Returns an iterator over the loaded chunks.
+    pub fn loaded_chunks(&self) -> impl Iterator<Item = &HexPosition<T>> {
+        self.chunks.keys()
+    }
+
+    /// Returns `true` if the given chunk is loaded.
+    pub fn is_loaded(&self, chunk: HexPosition<T>) -> bool {
+        self.chunks.contains_key(&chunk)
+    }
+
+    /// Loads a chunk, creating the data of each of its positions with `init`.
+    ///
+    /// Returns `false`, and changes nothing, if the chunk was already loaded.
+    pub fn load_chunk(
+        &mut self,
+        chunk: HexPosition<T>,
+        init: impl FnMut(HexPosition<T>) -> D,
+    ) -> bool {
+        if self.chunks.contains_key(&chunk) {
+            return false;
+        }
+        let data = super_hex_members(chunk, self.chunk_radius)
+            .map(init)
+            .collect();
+        self.chunks.insert(chunk, data);
+        true
+    }
+
+    /// Unloads a chunk and returns its positions and their data, for example to save them.
+    /// The returned layout is empty if the chunk wasn't loaded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{chunk::HexChunkMap, HexPosition};
+    ///
+    /// let mut map: HexChunkMap<i32, i32> = HexChunkMap::new(3);
+    /// let chunk = HexPosition(1, -2);
+    /// map.load_chunk(chunk, |pos| pos.0 * 100 + pos.1);
+    ///
+    /// let saved = map.unload_chunk(chunk);
+    /// assert_eq!(saved.len(), 37);
+    /// assert!(!map.is_loaded(chunk));
+    /// assert!(map.unload_chunk(chunk).is_empty());
+    ///
+    /// // Reloading from the saved layout gives the same data back.
+    /// map.load_chunk(chunk, |pos| *saved.get(pos).unwrap());
+    /// for (pos, data) in saved.iter() {
+    ///     assert_eq!(map.get(*pos), Some(data));
+    /// }
+    /// ```
+    pub fn unload_chunk(&mut self, chunk: HexPosition<T>) -> HexLayout<D, T> {
+        self.chunks
+            .remove(&chunk)
+            .into_iter()
+            .flat_map(|data| super_hex_members(chunk, self.chunk_radius).zip(data))
+            .collect()
+    }
+
+    /// Returns the index of a position in the data of its chunk, and the chunk.
+    fn locate(&self, pos: HexPosition<T>) -> (HexPosition<T>, usize) {
+        let chunk = self.chunk_of(pos);
+        let bound = HexBound::new(
+            super_hex_center(chunk, self.chunk_radius),
+            self.chunk_radius,
+        );
+        let index = bound
+            .index_of(pos, HexIndexing::Spiral)
+            .expect("a position is always within its super-hex chunk");
+        (chunk, index)
+    }
+
+    /// Returns a reference to the data at the given position, or `None` if its chunk is not loaded.
+    pub fn get(&self, pos: HexPosition<T>) -> Option<&D> {
+        let (chunk, index) = self.locate(pos);
+        self.chunks.get(&chunk)?.get(index)
+    }
+
+    /// Returns a mutable reference to the data at the given position, or `None` if its chunk is not loaded.
+    pub fn get_mut(&mut self, pos: HexPosition<T>) -> Option<&mut D> {
+        let (chunk, index) = self.locate(pos);
+        self.chunks.get_mut(&chunk)?.get_mut(index)
+    }
+
+    /// Sets the data at the given position, and returns the previous data.
+    ///
+    /// If the chunk of the position is not loaded, it is loaded with the default data first.
+    pub fn set(&mut self, pos: HexPosition<T>, data: D) -> D {
+        let (chunk, index) = self.locate(pos);
+        let radius = self.chunk_radius;
+        let chunk_data = self.chunks.entry(chunk).or_insert_with(|| {
+            super_hex_members(chunk, radius)
+                .map(|_| D::default())
+                .collect()
+        });
+        std::mem::replace(&mut chunk_data[index], data)
+    }
+
+    /// Returns the number of loaded positions.
+    pub fn len(&self) -> usize {
+        self.chunks.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if no chunk is loaded.
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Returns an iterator over all the loaded positions.
+    pub fn positions(&self) -> impl Iterator<Item = HexPosition<T>> + '_ {
+        self.iter().map(|(pos, _)| pos)
+    }
+
+    /// Returns an iterator over all the loaded positions and their data.
+    pub fn iter(&self) -> impl Iterator<Item = (HexPosition<T>, &D)> {
+        self.chunks
+            .iter()
+            .flat_map(|(chunk, data)| super_hex_members(*chunk, self.chunk_radius).zip(data))
+    }
+}
+
+impl<T: Number> HexChunkMap<bool, T> {
+    /// Finds the shortest path between two positions over the loaded chunks, like [HexLayout::pathfinding].
+    ///
+    /// The positions of unloaded chunks are considered blocked. Returns `None` if there is no path.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{chunk::HexChunkMap, HexPosition};
+    ///
+    /// let mut map: HexChunkMap<bool, i32> = HexChunkMap::new(1);
+    /// for chunk in [HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0)] {
+    ///     map.load_chunk(chunk, |_| false);
+    /// }
+    ///
+    /// // The path crosses the seams between the three chunks.
+    /// let path = map.pathfinding(HexPosition(-1, 0), HexPosition(7, -2)).unwrap();
+    /// assert_eq!(path.len() as i32, HexPosition(-1, 0).distance(HexPosition(7, -2)) + 1);
+    ///
+    /// map.unload_chunk(HexPosition(1, 0));
+    /// assert_eq!(map.pathfinding(HexPosition(-1, 0), HexPosition(7, -2)), None);
+    /// ```
+    pub fn pathfinding(&self, from: HexPosition<T>, to: HexPosition<T>) -> Option<HexPath<T>> {
+        algorithms::astar(
+            from,
+            to,
+            |pos| {
+                HexDirection::iter()
+                    .map(|direction| pos + direction.to_vector())
+                    .into_iter()
+                    .filter(|neighbor| self.get(*neighbor) == Some(&false))
+            },
+            |_, _| 1,
+            |pos| pos.distance(to).to_isize() as u32,
+        )
+        .map(HexPath)
+    }
+}
@@ -0,0 +1,356 @@
+//! # Edges and vertices of the hexagons.
+//!
+//! A [HexEdge] is a side shared by two hexagons (for roads or walls) and a [HexVertex] is a corner shared by
+//! three hexagons (for settlements). Both have a canonical representation, so the same edge or vertex is equal
+//! and has the same hash whatever the hexagon it was built from.
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+
+use utils::HexBuildHasher;
+
+use crate::*;
+
+/// The fields of a serialized [HexEdge] or [HexVertex], which may not be in their canonical representation.
+///
+/// They are deserialized into this type first, then given to [HexEdge::new] or [HexVertex::new].
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HexAndDirection<T: Number> {
+    /// The hexagon.
+    hex: HexPosition<T>,
+
+    /// The direction from the hexagon.
+    direction: HexDirection,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Number> From<HexAndDirection<T>> for HexEdge<T> {
+    fn from(value: HexAndDirection<T>) -> Self {
+        Self::new(value.hex, value.direction)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: Number> From<HexAndDirection<T>> for HexVertex<T> {
+    fn from(value: HexAndDirection<T>) -> Self {
+        Self::new(value.hex, value.direction)
+    }
+}
+
+/// Returns the direction following `direction` in [HexDirection::iter], counterclockwise.
+const fn next_direction(direction: HexDirection) -> HexDirection {
+    match direction {
+        HexDirection::Right => HexDirection::UpRight,
+        HexDirection::UpRight => HexDirection::UpLeft,
+        HexDirection::UpLeft => HexDirection::Left,
+        HexDirection::Left => HexDirection::DownLeft,
+        HexDirection::DownLeft => HexDirection::DownRight,
+        HexDirection::DownRight => HexDirection::Right,
+    }
+}
+
+/// Returns the direction preceding `direction` in [HexDirection::iter], clockwise.
+const fn previous_direction(direction: HexDirection) -> HexDirection {
+    match direction {
+        HexDirection::Right => HexDirection::DownRight,
+        HexDirection::UpRight => HexDirection::Right,
+        HexDirection::UpLeft => HexDirection::UpRight,
+        HexDirection::Left => HexDirection::UpLeft,
+        HexDirection::DownLeft => HexDirection::Left,
+        HexDirection::DownRight => HexDirection::DownLeft,
+    }
+}
+
+/// The side shared by a hexagon and its neighbor in a given direction.
+///
+/// It is stored as the lower of the two hexagons (by `r`, then by `q`) and the direction of the other one,
+/// which is always [HexDirection::Right], [HexDirection::DownLeft] or [HexDirection::DownRight].
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use hexing::{edge::HexEdge, HexDirection, HexPosition};
+///
+/// // The same edge, built from both sides.
+/// for direction in HexDirection::iter() {
+///     let pos = HexPosition(2, -1);
+///     let edge = HexEdge::new(pos, direction);
+///     let other_side = HexEdge::new(pos + direction.to_vector(), direction.opposite());
+///     assert_eq!(edge, other_side);
+///
+///     let set: HashSet<_> = [edge, other_side].into_iter().collect();
+///     assert_eq!(set.len(), 1);
+/// }
+///
+/// let edge = HexEdge::new(HexPosition(0, 0), HexDirection::UpLeft);
+/// assert_eq!(edge.hex(), HexPosition(0, -1));
+/// assert_eq!(edge.direction(), HexDirection::DownRight);
+///
+/// // An edge touches 2 hexagons, 2 vertices and 4 other edges.
+/// assert_eq!(edge.adjacent_hexes(), [HexPosition(0, -1), HexPosition(0, 0)]);
+/// assert_eq!(edge.adjacent_vertices().len(), 2);
+/// assert_eq!(edge.adjacent_edges().iter().collect::<HashSet<_>>().len(), 4);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "HexAndDirection<T>"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HexEdge<T: Number> {
+    /// The lower of the two hexagons.
+    hex: HexPosition<T>,
+
+    /// The direction of the other hexagon.
+    direction: HexDirection,
+}
+
+impl<T: Number> HexEdge<T> {
+    /// Creates the edge between `hex` and its neighbor in `direction`.
+    pub fn new(hex: HexPosition<T>, direction: HexDirection) -> Self {
+        match direction {
+            HexDirection::Right | HexDirection::DownLeft | HexDirection::DownRight => {
+                Self { hex, direction }
+            }
+            _ => Self {
+                hex: hex + direction.to_vector(),
+                direction: direction.opposite(),
+            },
+        }
+    }
+
+    /// Returns the edge between two positions, or `None` if they are not neighbors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{edge::HexEdge, HexDirection, HexPosition};
+    ///
+    /// let edge = HexEdge::between(HexPosition(1, 1), HexPosition(1, 0));
+    /// assert_eq!(edge, Some(HexEdge::new(HexPosition(1, 0), HexDirection::DownRight)));
+    /// assert_eq!(HexEdge::between(HexPosition(0, 0), HexPosition(2, 0)), None);
+    /// ```
+    pub fn between(a: HexPosition<T>, b: HexPosition<T>) -> Option<Self> {
+        HexDirection::iter()
+            .into_iter()
+            .find(|direction| a + direction.to_vector() == b)
+            .map(|direction| Self::new(a, direction))
+    }
+
+    /// Returns the lower of the two hexagons of the edge.
+    pub const fn hex(&self) -> HexPosition<T> {
+        self.hex
+    }
+
+    /// Returns the direction from [HexEdge::hex] to the other hexagon of the edge.
+    pub const fn direction(&self) -> HexDirection {
+        self.direction
+    }
+
+    /// Returns the two hexagons sharing the edge, the lower one first.
+    pub fn adjacent_hexes(&self) -> [HexPosition<T>; 2] {
+        [self.hex, self.hex + self.direction.to_vector()]
+    }
+
+    /// Returns the two ends of the edge.
+    pub fn adjacent_vertices(&self) -> [HexVertex<T>; 2] {
+        [
+            HexVertex::new(self.hex, previous_direction(self.direction)),
+            HexVertex::new(self.hex, self.direction),
+        ]
+    }
+
+    /// Returns the four edges sharing an end with the edge.
+    pub fn adjacent_edges(&self) -> [Self; 4] {
+        let [a, b] = self.adjacent_vertices().map(|vertex| {
+            vertex
+                .adjacent_edges()
+                .into_iter()
+                .filter(|edge| edge != self)
+        });
+        let mut edges = a.chain(b);
+        std::array::from_fn(|_| edges.next().unwrap_or(*self))
+    }
+}
+
+/// The corner shared by a hexagon and its neighbors in two consecutive directions.
+///
+/// There are two kinds of vertices: the corner of a hexagon between its neighbors in [HexDirection::DownRight]
+/// and [HexDirection::Right], and the corner between its neighbors in [HexDirection::DownLeft] and
+/// [HexDirection::DownRight]. Every vertex is stored as one of these two corners of a single hexagon.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+/// use hexing::{edge::HexVertex, HexDirection, HexPosition};
+///
+/// // The same vertex, built from its three hexagons.
+/// for direction in HexDirection::iter() {
+///     let vertex = HexVertex::new(HexPosition(-1, 3), direction);
+///     let hexes = vertex.adjacent_hexes();
+///     for hex in hexes {
+///         let same = HexDirection::iter()
+///             .into_iter()
+///             .map(|direction| HexVertex::new(hex, direction))
+///             .filter(|other| *other == vertex)
+///             .count();
+///         assert_eq!(same, 1);
+///     }
+/// }
+///
+/// let vertex = HexVertex::new(HexPosition(0, 0), HexDirection::Right);
+/// assert_eq!(vertex.hex(), HexPosition(1, -1));
+/// assert_eq!(vertex.direction(), HexDirection::DownLeft);
+///
+/// // A vertex touches 3 hexagons, 3 edges and 3 other vertices.
+/// assert_eq!(vertex.adjacent_hexes().iter().collect::<HashSet<_>>().len(), 3);
+/// assert_eq!(vertex.adjacent_edges().iter().collect::<HashSet<_>>().len(), 3);
+/// assert_eq!(vertex.adjacent_vertices().iter().collect::<HashSet<_>>().len(), 3);
+/// for edge in vertex.adjacent_edges() {
+///     assert!(edge.adjacent_vertices().contains(&vertex));
+/// }
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "HexAndDirection<T>"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HexVertex<T: Number> {
+    /// The hexagon whose corner is the vertex.
+    hex: HexPosition<T>,
+
+    /// The first of the two directions of the corner, [HexDirection::DownLeft] or [HexDirection::DownRight].
+    direction: HexDirection,
+}
+
+impl<T: Number> HexVertex<T> {
+    /// Creates the corner of `hex` shared with its neighbors in `direction`
+    /// and in the next direction counterclockwise (see [HexDirection::iter]).
+    pub fn new(hex: HexPosition<T>, direction: HexDirection) -> Self {
+        let (offset, direction) = match direction {
+            HexDirection::DownLeft | HexDirection::DownRight => (None, direction),
+            HexDirection::Left => (Some(HexDirection::Left), HexDirection::DownRight),
+            HexDirection::UpLeft => (Some(HexDirection::UpLeft), HexDirection::DownLeft),
+            HexDirection::UpRight => (Some(HexDirection::UpLeft), HexDirection::DownRight),
+            HexDirection::Right => (Some(HexDirection::UpRight), HexDirection::DownLeft),
+        };
+        Self {
+            hex: offset.map_or(hex, |offset| hex + offset.to_vector()),
+            direction,
+        }
+    }
+
+    /// Returns the hexagon whose corner is the vertex, in its canonical representation.
+    pub const fn hex(&self) -> HexPosition<T> {
+        self.hex
+    }
+
+    /// Returns the direction of the corner of [HexVertex::hex], [HexDirection::DownLeft] or [HexDirection::DownRight].
+    pub const fn direction(&self) -> HexDirection {
+        self.direction
+    }
+
+    /// Returns the three hexagons sharing the vertex.
+    pub fn adjacent_hexes(&self) -> [HexPosition<T>; 3] {
+        [
+            self.hex,
+            self.hex + self.direction.to_vector(),
+            self.hex + next_direction(self.direction).to_vector(),
+        ]
+    }
+
+    /// Returns the three edges ending at the vertex.
+    pub fn adjacent_edges(&self) -> [HexEdge<T>; 3] {
+        let next = next_direction(self.direction);
+        [
+            HexEdge::new(self.hex, self.direction),
+            HexEdge::new(self.hex, next),
+            HexEdge::new(self.hex + self.direction.to_vector(), next_direction(next)),
+        ]
+    }
+
+    /// Returns the three vertices at the other end of the edges ending at the vertex.
+    pub fn adjacent_vertices(&self) -> [Self; 3] {
+        self.adjacent_edges().map(|edge| {
+            let [a, b] = edge.adjacent_vertices();
+            if a == *self { b } else { a }
+        })
+    }
+}
+
+/// A set of walls on the edges of the hexagons, for maps with thin walls between the positions.
+///
+/// It is used alongside a [HexLayout](crate::layout::HexLayout) of blocked positions, by
+/// [HexLayout::pathfinding_with_walls](crate::layout::HexLayout::pathfinding_with_walls) and the other `_with_walls` methods.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{edge::{HexEdge, WallLayout}, HexDirection, HexPosition};
+///
+/// let mut walls = WallLayout::new();
+/// let edge = HexEdge::new(HexPosition(0, 0), HexDirection::UpLeft);
+///
+/// assert!(!walls.set_wall(edge, true));
+/// assert!(walls.is_wall(HexEdge::new(HexPosition(0, -1), HexDirection::DownRight)));
+/// assert!(walls.blocks(HexPosition(0, -1), HexPosition(0, 0)));
+/// assert_eq!(walls.len(), 1);
+///
+/// assert!(walls.set_wall(edge, false));
+/// assert!(walls.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WallLayout<T: Number>(HashSet<HexEdge<T>, HexBuildHasher>);
+
+impl<T: Number> WallLayout<T> {
+    /// Creates a new [WallLayout] without any wall.
+    pub fn new() -> Self {
+        Self(HashSet::default())
+    }
+
+    /// Adds (`blocked` is `true`) or removes a wall on the edge. Returns `true` if there was a wall before.
+    pub fn set_wall(&mut self, edge: HexEdge<T>, blocked: bool) -> bool {
+        if blocked {
+            !self.0.insert(edge)
+        } else {
+            self.0.remove(&edge)
+        }
+    }
+
+    /// Returns `true` if there is a wall on the edge.
+    pub fn is_wall(&self, edge: HexEdge<T>) -> bool {
+        self.0.contains(&edge)
+    }
+
+    /// Returns `true` if a wall separates two neighbors. Positions that are not neighbors are never separated.
+    pub fn blocks(&self, a: HexPosition<T>, b: HexPosition<T>) -> bool {
+        HexEdge::between(a, b).is_some_and(|edge| self.is_wall(edge))
+    }
+
+    /// Returns an iterator over the edges with a wall.
+    pub fn walls(&self) -> impl Iterator<Item = &HexEdge<T>> {
+        self.0.iter()
+    }
+
+    /// Returns the number of walls.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there is no wall.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
@@ -110,13 +110,22 @@
 //! }
 //! ```
 
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::io::{self, Read, Write};
+
+use priority_queue::PriorityQueue;
 
 #[cfg(feature = "noise")]
 use noise::NoiseFn;
 
-use priority_queue::PriorityQueue;
-use utils::neighbors;
+use algorithms::{SearchOptions, SearchStats};
+use edge::WallLayout;
+use shape::HexShape;
+use storage::TileData;
+use utils::{HexBound, HexBuildHasher, HexIndexing, OffsetLayout, neighbors};
 
 use crate::*;
 
@@ -124,6 +133,7 @@ use crate::*;
 ///
 /// - `[D]` represents the data stored at each position in the grid. It must implement the `Default` trait to be initialized when creating a new, empty `HexLayout`.
 /// - `[T]` denotes the type of the coordinates in the grid.
+/// - `[H]` is the hasher of the underlying `HashMap`, [HexBuildHasher] by default.
 ///
 /// ## Why use a `HexLayout`?
 ///
@@ -158,8 +168,33 @@ use crate::*;
 /// In a video game, hexagonal grids can be managed using the `HexLayout` structure. This allows for different data to be recorded independently.
 /// For example, a `blocked_layout` structure where `T` is a `bool` can be used to record blocked positions in the grid, enabling pathfinding, field of view, and movement field calculations.
 /// Another layer can be used to track the number of resources available at each hexagonal position, etc.
+///
+/// A layout can be cloned, for example to keep the previous frame of a simulation:
+///
+/// ```rust
+/// use hexing::{layout::HexLayout, HexPosition};
+///
+/// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+/// map.set(HexPosition(1, 0), 5);
+///
+/// let mut next = map.clone();
+/// next.set(HexPosition(1, 0), 6);
+/// next.delete(HexPosition(0, 1));
+///
+/// assert_eq!(map.get(HexPosition(1, 0)), Some(&5));
+/// assert_eq!(map.get(HexPosition(0, 1)), Some(&0));
+/// assert_eq!(map.len(), 7);
+/// ```
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-pub struct HexLayout<D: Default, T: Number>(HashMap<HexPosition<T>, D>);
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "D: Serialize, T: Serialize",
+        deserialize = "D: Deserialize<'de>, T: Deserialize<'de>, H: BuildHasher + Default"
+    ))
+)]
+#[derive(Clone)]
+pub struct HexLayout<D: Default, T: Number, H = HexBuildHasher>(HashMap<HexPosition<T>, D, H>);
 
 impl<T: Default> HexLayout<T, isize> {
     /// Creates a new layout with the given range and center position.
@@ -178,7 +213,7 @@ impl<T: Default> HexLayout<T, isize> {
     /// assert_eq!(map2.len(), 7);
     /// ```
     pub fn new_from_range(range: usize, center: HexPosition<isize>) -> Self {
-        let mut grid = HashMap::new();
+        let mut grid = HashMap::default();
 
         let range = range as isize - 1;
 
@@ -195,7 +230,62 @@ impl<T: Default> HexLayout<T, isize> {
     }
 }
 
-impl<T: Number> HexLayout<f64, T> {
+impl HexLayout<bool, isize> {
+    /// Creates a layout from a text map, where each non-whitespace character is a position,
+    /// blocked if it is `blocked_char`.
+    ///
+    /// Each line is a row of the map, from `r = 0` downward, and the rows are staggered like pointy-top hexagons:
+    /// the positions are two characters apart, and each row is shifted by one character from the row above.
+    /// The character at column `x` of line `y` is the position `((x - y) / 2, y)`.
+    ///
+    /// Returns [HexError::MisalignedChar] if a non-whitespace character is between two positions,
+    /// that is if `x - y` is odd.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexError, HexPosition};
+    ///
+    /// let map = HexLayout::from_ascii(
+    ///     "\
+    /// . . # .
+    ///  . # . .
+    ///   . . .",
+    ///     '#',
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(map.len(), 11);
+    /// assert_eq!(map.get(HexPosition(2, 0)), Some(&true));
+    /// assert_eq!(map.get(HexPosition(1, 1)), Some(&true));
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&false));
+    /// assert_eq!(map.get(HexPosition(2, 2)), Some(&false));
+    /// assert_eq!(map.get(HexPosition(3, 2)), None);
+    ///
+    /// // Two adjacent characters can't both be positions.
+    /// assert_eq!(HexLayout::from_ascii(". ##", '#'), Err(HexError::MisalignedChar(0, 3)));
+    /// assert_eq!(HexLayout::from_ascii(". .\n. .", '#'), Err(HexError::MisalignedChar(1, 0)));
+    /// ```
+    pub fn from_ascii(s: &str, blocked_char: char) -> Result<Self, HexError> {
+        let mut layout = HashMap::default();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if c.is_whitespace() {
+                    continue;
+                }
+                let shifted = x as isize - y as isize;
+                if shifted.rem_euclid(2) != 0 {
+                    return Err(HexError::MisalignedChar(y, x));
+                }
+                let pos = HexPosition(shifted.div_euclid(2), y as isize);
+                layout.insert(pos, c == blocked_char);
+            }
+        }
+        Ok(Self(layout))
+    }
+}
+
+impl<T: Number, H: BuildHasher + Default> HexLayout<f64, T, H> {
     /// Initializes a noise map for the layout.
     ///
     /// The data type `[D]` must be a `f64` and the noise function `[NoiseFn]` must be of dimension 2.
@@ -223,16 +313,100 @@ impl<T: Number> HexLayout<f64, T> {
     /// ```
     #[cfg(feature = "noise")]
     pub fn init_noise<N: NoiseFn<f64, 2>>(&mut self, noise: N) {
-        let keys: Vec<_> = self.positions().cloned().collect();
-        for pos in keys {
+        self.init_noise_with(noise, 1.0, (0.0, 0.0));
+    }
+
+    /// Initializes a noise map for the layout, sampling the noise at `pixel * frequency + offset`
+    /// where `pixel` are the pixel coordinates of each position.
+    ///
+    /// This allows to reuse the same noise function at another scale, or for several chunks of a world
+    /// with different offsets. [HexLayout::init_noise] is the same with a frequency of `1.0` and no offset.
+    ///
+    /// Note: You must include the `noise` crate in your project to use this function.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    /// use noise::Perlin;
+    ///
+    /// let mut a = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// a.init_noise_with(Perlin::new(7), 0.3, (0.0, 0.0));
+    ///
+    /// let mut b = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// b.init_noise_with(Perlin::new(7), 0.3, (100.5, -20.25));
+    ///
+    /// assert_ne!(a, b);
+    /// for value in a.data().chain(b.data()) {
+    ///     assert!((-1.0..=1.0).contains(value));
+    /// }
+    /// ```
+    #[cfg(feature = "noise")]
+    pub fn init_noise_with<N: NoiseFn<f64, 2>>(
+        &mut self,
+        noise: N,
+        frequency: f64,
+        offset: (f64, f64),
+    ) {
+        for (pos, value) in self.0.iter_mut() {
+            let pixel = pos.to_pixel_coordinates();
+            *value = noise.get([
+                (pixel.0 as f64).mul_add(frequency, offset.0),
+                (pixel.1 as f64).mul_add(frequency, offset.1),
+            ]);
+        }
+    }
+
+    /// Initializes a noise map for the layout by blending several noise functions.
+    ///
+    /// Each noise function is given with its weight, and the value of each position is the weighted sum
+    /// of the noise values divided by the total weight. If the total weight is zero, all the values are `0.0`.
+    ///
+    /// Note: You must include the `noise` crate in your project to use this function.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    /// use noise::{NoiseFn, Perlin};
+    ///
+    /// let sources: [(Box<dyn NoiseFn<f64, 2>>, f64); 2] = [
+    ///     (Box::new(Perlin::new(1)), 3.0),
+    ///     (Box::new(Perlin::new(2)), 1.0),
+    /// ];
+    ///
+    /// let mut map = HexLayout::new_from_range(8, HexPosition(0, 0));
+    /// map.init_noise_blend(&sources);
+    ///
+    /// for (pos, value) in map.iter() {
+    ///     assert!((-1.0..=1.0).contains(value));
+    ///
+    ///     let (x, y) = pos.to_pixel_coordinates();
+    ///     let point = [x as f64, y as f64];
+    ///     let expected = (3.0 * Perlin::new(1).get(point) + Perlin::new(2).get(point)) / 4.0;
+    ///     assert!((value - expected).abs() < 1e-12);
+    /// }
+    /// ```
+    #[cfg(feature = "noise")]
+    pub fn init_noise_blend(&mut self, sources: &[(Box<dyn NoiseFn<f64, 2>>, f64)]) {
+        let total_weight: f64 = sources.iter().map(|(_, weight)| weight).sum();
+        for (pos, value) in self.0.iter_mut() {
             let position = pos.to_pixel_coordinates();
-            let noise_value = noise.get([position.0 as f64, position.1 as f64]);
-            self.set(pos, noise_value);
+            let point = [position.0 as f64, position.1 as f64];
+            *value = if total_weight == 0.0 {
+                0.0
+            } else {
+                sources
+                    .iter()
+                    .map(|(noise, weight)| noise.get(point) * weight)
+                    .sum::<f64>()
+                    / total_weight
+            };
         }
     }
 }
 
-impl<T: Default, S: Number> HexLayout<T, S> {
+impl<T: Default, S: Number, H: BuildHasher + Default> HexLayout<T, S, H> {
     /// Returns a reference to the data associated with the given position if it exists, otherwise returns `None`.
     ///
     /// ## Examples
@@ -248,6 +422,24 @@ impl<T: Default, S: Number> HexLayout<T, S> {
         self.0.get(&pos)
     }
 
+    /// Returns the data associated with each of the given positions, in the same order.
+    /// A position that is not in the layout gives `None`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<i32, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), 5);
+    ///
+    /// let data = map.get_many(&[HexPosition(0, 0), HexPosition(1, 0), HexPosition(4, 0)]);
+    /// assert_eq!(data, vec![Some(&0), Some(&5), None]);
+    /// ```
+    pub fn get_many<'a>(&'a self, positions: &[HexPosition<S>]) -> Vec<Option<&'a T>> {
+        positions.iter().map(|pos| self.get(*pos)).collect()
+    }
+
     /// Returns a mutable reference to the data associated with the given position if it exists, otherwise returns `None`.
     ///
     /// ## Examples
@@ -278,6 +470,61 @@ impl<T: Default, S: Number> HexLayout<T, S> {
         self.0.insert(pos, data)
     }
 
+    /// Sets the data at the given position to the value computed by `f` from the current data
+    /// (`None` if the position is not in the layout), and returns the previous data.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut visits: HexLayout<i32, isize> = HexLayout::new_from_range(0, HexPosition(0, 0));
+    /// let increment = |count: Option<&i32>| count.map_or(1, |count| count + 1);
+    ///
+    /// assert_eq!(visits.update(HexPosition(2, -1), increment), None);
+    /// assert_eq!(visits.update(HexPosition(2, -1), increment), Some(1));
+    /// assert_eq!(visits.get(HexPosition(2, -1)), Some(&2));
+    /// ```
+    pub fn update(&mut self, pos: HexPosition<S>, f: impl FnOnce(Option<&T>) -> T) -> Option<T> {
+        match self.0.entry(pos) {
+            Entry::Occupied(mut entry) => {
+                let data = f(Some(entry.get()));
+                Some(entry.insert(data))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(f(None));
+                None
+            }
+        }
+    }
+
+    /// Sets the data at the given position only if the position is not in the layout yet.
+    /// Returns `true` if the data was inserted, `false` if the position already had data, which is left unchanged.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
+    /// map.set(HexPosition(0, 0), 3);
+    ///
+    /// assert!(!map.set_if_absent(HexPosition(0, 0), 7));
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&3));
+    ///
+    /// assert!(map.set_if_absent(HexPosition(1, 0), 7));
+    /// assert_eq!(map.get(HexPosition(1, 0)), Some(&7));
+    /// ```
+    pub fn set_if_absent(&mut self, pos: HexPosition<S>, data: T) -> bool {
+        match self.0.entry(pos) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(data);
+                true
+            }
+        }
+    }
+
     /// Deletes the data at the given position if it exists. Returns the data if it existed, otherwise returns `None`.
     ///
     /// ## Examples
@@ -296,6 +543,115 @@ impl<T: Default, S: Number> HexLayout<T, S> {
         self.0.remove(&pos)
     }
 
+    /// Returns mutable references to the data at two different positions at the same time.
+    ///
+    /// Returns `None` if `a` and `b` are the same position or if one of them is not in the layout.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<u32, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), 10);
+    ///
+    /// let (a, b) = map.get_disjoint_mut(HexPosition(1, 0), HexPosition(0, 1)).unwrap();
+    /// *b += *a;
+    /// *a = 0;
+    /// assert_eq!(map.get(HexPosition(0, 1)), Some(&10));
+    ///
+    /// assert!(map.get_disjoint_mut(HexPosition(1, 0), HexPosition(1, 0)).is_none());
+    /// assert!(map.get_disjoint_mut(HexPosition(1, 0), HexPosition(5, 0)).is_none());
+    /// ```
+    pub fn get_disjoint_mut(
+        &mut self,
+        a: HexPosition<S>,
+        b: HexPosition<S>,
+    ) -> Option<(&mut T, &mut T)> {
+        if a == b {
+            return None;
+        }
+        match self.0.get_disjoint_mut([&a, &b]) {
+            [Some(a), Some(b)] => Some((a, b)),
+            _ => None,
+        }
+    }
+
+    /// Swaps the data at two positions. Returns `false`, and changes nothing, if one of them is not in the layout.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<usize, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let positions: Vec<_> = HexPosition(0, 0).spiral(2).collect();
+    /// for (i, pos) in positions.iter().enumerate() {
+    ///     map.set(*pos, i);
+    /// }
+    ///
+    /// assert!(map.swap(HexPosition(0, 0), HexPosition(1, 0)));
+    /// assert!(map.swap(HexPosition(0, 0), HexPosition(0, 0)));
+    /// assert!(!map.swap(HexPosition(0, 0), HexPosition(9, 0)));
+    ///
+    /// // Random swaps keep every value exactly once.
+    /// let mut seed: u64 = 3;
+    /// for _ in 0..200 {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     let a = positions[(seed >> 33) as usize % positions.len()];
+    ///     let b = positions[(seed >> 45) as usize % positions.len()];
+    ///     assert!(map.swap(a, b));
+    /// }
+    /// let mut values: Vec<usize> = map.data().copied().collect();
+    /// values.sort();
+    /// assert_eq!(values, (0..19).collect::<Vec<_>>());
+    /// ```
+    pub fn swap(&mut self, a: HexPosition<S>, b: HexPosition<S>) -> bool {
+        if a == b {
+            return self.0.contains_key(&a);
+        }
+        match self.get_disjoint_mut(a, b) {
+            Some((a, b)) => {
+                std::mem::swap(a, b);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the data at `from` to `to`, removing `from` from the layout.
+    /// Returns the data that was at `to` before, or `None` if there was none.
+    ///
+    /// Nothing changes if `from` is not in the layout or if `from` and `to` are the same position.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), 4);
+    /// map.set(HexPosition(0, 1), 2);
+    ///
+    /// assert_eq!(map.move_data(HexPosition(1, 0), HexPosition(0, 1)), Some(2));
+    /// assert_eq!(map.get(HexPosition(1, 0)), None);
+    /// assert_eq!(map.get(HexPosition(0, 1)), Some(&4));
+    ///
+    /// // Moving to a position outside of the layout adds it.
+    /// assert_eq!(map.move_data(HexPosition(0, 1), HexPosition(3, 0)), None);
+    /// assert_eq!(map.get(HexPosition(3, 0)), Some(&4));
+    ///
+    /// assert_eq!(map.move_data(HexPosition(8, 8), HexPosition(0, 0)), None);
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&0));
+    /// ```
+    pub fn move_data(&mut self, from: HexPosition<S>, to: HexPosition<S>) -> Option<T> {
+        if from == to {
+            return None;
+        }
+        let data = self.0.remove(&from)?;
+        self.0.insert(to, data)
+    }
+
     /// Returns an iterator over all the positions in the layout.
     ///
     /// ## Examples
@@ -351,430 +707,4554 @@ impl<T: Default, S: Number> HexLayout<T, S> {
         self.0.values_mut()
     }
 
-    /// Returns an iterator over all the positions and data in the layout.
+    /// Returns an iterator over all the positions and data as mutable references in the layout.
     ///
     /// ## Examples
     ///
     /// ```rust
-    ///
     /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let map: HexLayout<f64, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
+    /// let mut map: HexLayout<isize, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
     ///
-    /// for (pos, data) in map.iter() {
-    ///     assert_eq!(*pos, HexPosition(0, 0));
-    ///     assert_eq!(*data, f64::default());
+    /// for (pos, data) in map.iter_mut() {
+    ///     *data = pos.0;
     /// }
+    ///
+    /// assert_eq!(map.get(HexPosition(1, -1)), Some(&1));
     /// ```
-    pub fn iter(&self) -> impl Iterator<Item = (&HexPosition<S>, &T)> {
-        self.0.iter()
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&HexPosition<S>, &mut T)> {
+        self.0.iter_mut()
     }
 
-    /// Returns the number of positions in the layout.
+    /// Calls `f` on every position of the layout with a mutable reference to its data,
+    /// to update the data in place depending on the position.
     ///
     /// ## Examples
     ///
     /// ```rust
     /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let map: HexLayout<f64, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
+    /// let mut map: HexLayout<isize, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.apply(|pos, data| *data = pos.distance(HexPosition(0, 0)));
     ///
-    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&0));
+    /// assert_eq!(map.get(HexPosition(2, -1)), Some(&2));
+    /// assert_eq!(map.get(HexPosition(-3, 0)), Some(&3));
     /// ```
-    pub fn len(&self) -> usize {
-        self.0.len()
+    pub fn apply(&mut self, mut f: impl FnMut(HexPosition<S>, &mut T)) {
+        for (pos, data) in self.0.iter_mut() {
+            f(*pos, data);
+        }
     }
 
-    /// Returns `true` if the layout is empty, otherwise returns `false`.
+    /// Returns an iterator over the neighbors of `pos` that are in the layout and not blocked,
+    /// where `is_blocked` tells if the data of a position blocks it.
+    ///
+    /// This is [HexLayout::neighbors_unblocked] for any type of data, to use pathfinding or field of view algorithms
+    /// (see the [algorithms] module) on any layout. Like [HexLayout::neighbors_unblocked], it doesn't allocate.
     ///
     /// ## Examples
     ///
     /// ```rust
-    /// use hexing::{layout::HexLayout, HexPosition};
-    ///
-    /// let mut map: HexLayout<f64, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
-    ///
-    /// assert!(!map.is_empty());
-    ///
-    /// map.delete(HexPosition(0, 0));
+    /// use hexing::{algorithms::astar, layout::HexLayout, HexPosition};
+    ///
+    /// #[derive(Default, PartialEq)]
+    /// enum Terrain {
+    ///     #[default]
+    ///     Grass,
+    ///     Water,
+    ///     Wall,
+    /// }
     ///
-    /// assert!(map.is_empty());
+    /// let mut map: HexLayout<Terrain, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), Terrain::Wall);
+    /// map.set(HexPosition(0, 1), Terrain::Water);
+    ///
+    /// let is_wall = |terrain: &Terrain| *terrain == Terrain::Wall;
+    /// let passable = map.neighbors_passable(HexPosition(0, 0), is_wall);
+    /// assert_eq!(passable.len(), 5);
+    /// assert!(!passable.as_slice().contains(&HexPosition(1, 0)));
+    ///
+    /// let goal = HexPosition(3, 0);
+    /// let path = astar(
+    ///     HexPosition(0, 0),
+    ///     goal,
+    ///     |pos| map.neighbors_passable(pos, is_wall),
+    ///     |_, _| 1,
+    ///     |pos| pos.distance(goal) as u32,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(path.len(), 5);
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.0.is_empty()
+    pub fn neighbors_passable(
+        &self,
+        pos: HexPosition<S>,
+        is_blocked: impl Fn(&T) -> bool,
+    ) -> HexNeighbors<S> {
+        let mut result_neighbors = HexNeighbors::default();
+        for direction in HexDirection::iter() {
+            let neighbor = pos + direction.to_vector();
+            if self.0.get(&neighbor).is_some_and(|data| !is_blocked(data)) {
+                result_neighbors.push(neighbor);
+            }
+        }
+        result_neighbors
     }
 
-    /// Clears all data from the layout, leaving it empty.
+    /// Returns an iterator over all the positions and data in the layout.
     ///
     /// ## Examples
     ///
     /// ```rust
-    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let mut map: HexLayout<f64, isize> = HexLayout::new_from_range(7, HexPosition(0, 0));
-    ///
-    /// assert!(!map.is_empty());
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// map.clear();
+    /// let map: HexLayout<f64, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
     ///
-    /// assert!(map.is_empty());
+    /// for (pos, data) in map.iter() {
+    ///     assert_eq!(*pos, HexPosition(0, 0));
+    ///     assert_eq!(*data, f64::default());
+    /// }
     /// ```
-    pub fn clear(&mut self) {
-        self.0.clear();
+    pub fn iter(&self) -> impl Iterator<Item = (&HexPosition<S>, &T)> {
+        self.0.iter()
     }
 
-    /// Computes the logical AND operation between two layouts, returning a set of positions that exist in both layouts.
+    /// Returns the neighbor of `pos` in the given direction, if it exists in the layout.
     ///
     /// ## Examples
     ///
     /// ```rust
-    /// use hexing::{layout::HexLayout, HexPosition};
+    /// use hexing::{layout::HexLayout, HexDirection, HexPosition};
     ///
-    /// let mut map1: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
-    /// let mut map2: HexLayout<f64, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
     ///
-    /// let and = map1.and(&map2);
+    /// assert_eq!(map.step(HexPosition(0, 0), HexDirection::Right), Some(HexPosition(1, 0)));
+    /// assert_eq!(map.step(HexPosition(1, 0), HexDirection::Right), None);
+    /// ```
+    pub fn step(&self, pos: HexPosition<S>, direction: HexDirection) -> Option<HexPosition<S>> {
+        let next = pos + direction.to_vector();
+        self.0.contains_key(&next).then_some(next)
+    }
+
+    /// Returns an iterator over the positions of the layout within `bound`, with their data.
     ///
-    /// assert_eq!(and.len(), 4);
+    /// The positions of `bound` are looked up in the layout if there are fewer of them than positions in the layout,
+    /// otherwise the layout is scanned, so a large bound around a small layout stays cheap.
+    /// The order of the positions is unspecified.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, utils::HexBound, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(6, HexPosition(0, 0));
+    /// map.set(HexPosition(4, 0), 3);
+    ///
+    /// // A bound hanging off the edge of the layout, and a bound containing the whole layout.
+    /// for bound in [HexBound::new(HexPosition(5, 0), 2), HexBound::new(HexPosition(1, 1), 20)] {
+    ///     let region: HashSet<_> = map.region(&bound).collect();
+    ///     let expected: HashSet<_> = map
+    ///         .iter()
+    ///         .filter(|(pos, _)| bound.contains(**pos))
+    ///         .map(|(pos, data)| (*pos, data))
+    ///         .collect();
+    ///     assert_eq!(region, expected);
+    /// }
+    ///
+    /// assert_eq!(map.region(&HexBound::new(HexPosition(5, 0), 2)).count(), 9);
+    /// assert_eq!(map.region(&HexBound::new(HexPosition(1, 1), 20)).count(), 91);
     /// ```
-    pub fn and(&self, other: &Self) -> HashSet<HexPosition<S>> {
-        self.0
-            .keys()
-            .filter_map(|pos| {
-                if other.0.contains_key(pos) {
-                    Some(*pos)
-                } else {
-                    None
-                }
-            })
-            .collect()
+    pub fn region(&self, bound: &HexBound<S>) -> impl Iterator<Item = (HexPosition<S>, &T)> {
+        let bound = *bound;
+        // Walk the smallest of the bound and the layout, looking the positions up in the other.
+        let (from_bound, from_layout) = if bound.len() <= self.0.len() {
+            (Some(bound.center().spiral(bound.radius())), None)
+        } else {
+            (None, Some(self.0.iter()))
+        };
+
+        let from_bound = from_bound
+            .into_iter()
+            .flatten()
+            .filter_map(|pos| self.0.get(&pos).map(|data| (pos, data)));
+        let from_layout = from_layout
+            .into_iter()
+            .flatten()
+            .filter(move |(pos, _)| bound.contains(**pos))
+            .map(|(pos, data)| (*pos, data));
+        from_bound.chain(from_layout)
     }
 
-    /// Computes the logical OR operation between two layouts, returning a set of all unique positions that exist in either layout.
+    /// Returns an iterator over the positions of the layout within `radius` of `pos` (`pos` included), with their data.
+    ///
+    /// Unlike [HexLayout::field_of_view] and [HexLayout::field_of_move], nothing blocks the positions:
+    /// this is the same as [HexLayout::region] with a [HexBound] of the given radius around `pos`.
     ///
     /// ## Examples
     ///
     /// ```rust
     /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let mut map1: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
-    /// let mut map2: HexLayout<f64, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(-4, 2), HexPosition(4, -2), true);
     ///
-    /// let or = map1.or(&map2);
+    /// for (pos, radius) in [(HexPosition(0, 0), 2), (HexPosition(4, -1), 3), (HexPosition(2, 2), 0), (HexPosition(9, 0), 0)] {
+    ///     let brute_force = map.iter().filter(|(other, _)| other.distance(pos) <= radius).count();
+    ///     assert_eq!(map.within_range(pos, radius as usize).count(), brute_force);
     ///
-    /// assert_eq!(or.len(), 22);
+    ///     let blocked = map.iter().filter(|(other, blocked)| other.distance(pos) <= radius && **blocked).count();
+    ///     assert_eq!(map.count_within_range(pos, radius as usize, |blocked| *blocked), blocked);
+    /// }
+    ///
+    /// assert_eq!(map.within_range(HexPosition(2, 2), 0).count(), 1);
+    /// assert_eq!(map.within_range(HexPosition(9, 0), 0).count(), 0);
     /// ```
-    pub fn or(&self, other: &Self) -> HashSet<HexPosition<S>> {
-        let mut result = HashSet::with_capacity(self.0.len() + other.0.len());
-        result.extend(self.0.keys().copied());
-        result.extend(other.0.keys().copied());
-        result
+    pub fn within_range(
+        &self,
+        pos: HexPosition<S>,
+        radius: usize,
+    ) -> impl Iterator<Item = (HexPosition<S>, &T)> {
+        self.region(&HexBound::new(pos, radius))
     }
 
-    /// Computes the logical XOR operation between two layouts, returning a set of positions that exist in one layout but not the other.
+    /// Returns an iterator over the positions of the line from `a` to `b` (see [HexPosition::line_to])
+    /// that are in the layout, in the order of the line.
     ///
     /// ## Examples
     ///
     /// ```rust
     /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let mut map1: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
-    /// let mut map2: HexLayout<f64, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
-    ///
-    /// let xor = map1.xor(&map2);
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// map.delete(HexPosition(-1, 0));
     ///
-    /// assert_eq!(xor.len(), 18);
+    /// let line: Vec<_> = map.line_in_layout(HexPosition(-5, 0), HexPosition(5, 0)).collect();
+    /// assert_eq!(line, vec![HexPosition(-2, 0), HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0)]);
+    /// assert_eq!(map.line_in_layout(HexPosition(4, 0), HexPosition(0, 4)).count(), 0);
     /// ```
-    pub fn xor(&self, other: &Self) -> HashSet<HexPosition<S>> {
-        let mut result = HashSet::with_capacity(self.0.len() + other.0.len());
-
-        result.extend(self.0.keys().filter(|k| !other.0.contains_key(k)).copied());
-        result.extend(other.0.keys().filter(|k| !self.0.contains_key(k)).copied());
+    pub fn line_in_layout(
+        &self,
+        a: HexPosition<S>,
+        b: HexPosition<S>,
+    ) -> impl Iterator<Item = HexPosition<S>> + '_ {
+        a.line_to(b).filter(|pos| self.0.contains_key(pos))
+    }
 
-        result
+    /// Returns the number of positions of the layout within `radius` of `pos` (`pos` included)
+    /// whose data matches `predicate`, see [HexLayout::within_range].
+    pub fn count_within_range(
+        &self,
+        pos: HexPosition<S>,
+        radius: usize,
+        predicate: impl Fn(&T) -> bool,
+    ) -> usize {
+        self.within_range(pos, radius)
+            .filter(|(_, data)| predicate(data))
+            .count()
     }
-}
 
-impl<S: Number> HexLayout<bool, S> {
-    /// Finds the shortest path between two positions on a hexagonal grid.
-    /// To use the `pathfinding` feature, the data associated with each position must be a [bool] in order to represent whether the position is blocked or not.
-    /// ``True`` means that the position is blocked, and ``False`` means that the position is not blocked.
+    /// Returns the `k` positions of the layout nearest to `from` whose data matches `predicate`, with their data.
     ///
-    /// # Parameters
+    /// The rings around `from` are explored outward, and the search stops at the first ring completing the `k` matches,
+    /// so only the positions closer than the `k`-th match (or at the same distance) are examined.
+    /// Without enough matches, it stops once every position of the layout was found in the rings.
+    /// The result is sorted by distance, then by `q` and `r`. It has fewer than `k` elements if there aren't enough matches.
     ///
-    /// - `from`: The starting position on the hexagonal grid. This position must be a `HexPosition<S>`.
-    /// - `to`: The target position on the hexagonal grid. This position must also be a `HexPosition<S>`.
-    ///
-    /// # Returns
-    ///
-    /// This function returns a `Vec<HexPosition<S>>` representing the shortest path from `from` to `to`.
-    /// The path is returned in order from start to end. If `from` and `to` are the same, the vector will contain only `from`.
-    ///
-    /// # Panics
-    ///
-    /// The function will panic if either `from` or `to` are not present in the hexagonal grid.
-    ///
-    /// # Example
+    /// ## Examples
     ///
     /// ```rust
-    /// use hexing::{HexPosition, layout::HexLayout};
-    ///
-    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// use std::cell::Cell;
     ///
-    /// map.set(HexPosition(-1, 1), true);
-    /// map.set(HexPosition(1, -1), true);
-    /// map.set(HexPosition(1, 0), true);
-    /// map.set(HexPosition(0, 1), true);
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let start = HexPosition(0, 0);
-    /// let goal = HexPosition(0, 2);
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(11, HexPosition(0, 0));
+    /// let mut seed: u64 = 11;
+    /// for pos in HexPosition(0, 0).spiral(10) {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     map.set(pos, (seed >> 60) as u8);
+    /// }
     ///
-    /// let path = map.pathfinding(start, goal);
+    /// for (from, k) in [(HexPosition(0, 0), 3), (HexPosition(8, -2), 10), (HexPosition(20, 0), 4)] {
+    ///     let mut expected: Vec<_> = map.iter().filter(|(_, data)| **data == 0).map(|(pos, data)| (*pos, data)).collect();
+    ///     expected.sort_by_key(|(pos, _)| (pos.distance(from), pos.0, pos.1));
+    ///     expected.truncate(k);
     ///
-    /// for pos in &path {
-    ///     println!("Position: {:?}", pos);
+    ///     assert_eq!(map.nearest_where(from, k, |data| *data == 0), expected);
     /// }
-    /// assert_eq!(path, vec![HexPosition(0, 0), HexPosition(-1, 0), HexPosition(-2, 1), HexPosition(-2, 2), HexPosition(-1, 2), HexPosition(0, 2)]);
-    /// ```
-    ///
-    /// # Note
     ///
-    /// This implementation uses the A* algorithm to guarantee finding the shortest path.
-    /// The heuristic used is tailored to hexagonal grids, where the axial distance is used to estimate the cost to the destination.
-    pub fn pathfinding(&self, from: HexPosition<S>, to: HexPosition<S>) -> Vec<HexPosition<S>> {
-        if from == to {
-            return vec![from];
-        }
-
-        if !self.0.contains_key(&from) || !self.0.contains_key(&to) {
-            panic!("Position not in layout");
+    /// // Only the positions near `from` are examined.
+    /// let examined = Cell::new(0);
+    /// map.set(HexPosition(1, 0), 42);
+    /// let nearest = map.nearest_where(HexPosition(0, 0), 1, |data| {
+    ///     examined.set(examined.get() + 1);
+    ///     *data == 42
+    /// });
+    /// assert_eq!(nearest, vec![(HexPosition(1, 0), &42)]);
+    /// assert_eq!(examined.get(), 7);
+    ///
+    /// // Without enough matches, the search ends after the farthest position of the layout.
+    /// let sparse: HexLayout<u8, isize> = [(HexPosition(-30, 5), 1), (HexPosition(40, 0), 2)].into_iter().collect();
+    /// assert_eq!(sparse.nearest_where(HexPosition(0, 0), 5, |_| true), vec![(HexPosition(-30, 5), &1), (HexPosition(40, 0), &2)]);
+    /// assert!(sparse.nearest_where(HexPosition(0, 0), 5, |_| false).is_empty());
+    /// ```
+    pub fn nearest_where(
+        &self,
+        from: HexPosition<S>,
+        k: usize,
+        predicate: impl Fn(&T) -> bool,
+    ) -> Vec<(HexPosition<S>, &T)> {
+        let mut matches = Vec::new();
+        if k == 0 {
+            return matches;
         }
 
-        let mut frontier = PriorityQueue::new();
-        frontier.push(from, 0);
-
-        let mut came_from: HashMap<HexPosition<S>, Option<HexPosition<S>>> = HashMap::new();
-        came_from.insert(from, None);
-
-        let mut cost_so_far: HashMap<HexPosition<S>, i32> = HashMap::new();
-        cost_so_far.insert(from, 0);
-
-        while let Some((current, _)) = frontier.pop() {
-            if current == to {
-                break;
-            }
-
-            for next in self.neighbors_unblocked(current) {
-                if came_from.contains_key(&next) {
-                    continue;
-                }
-
-                let Some(new_cost) = cost_so_far.get(&current).map(|c| c + 1) else {
-                    continue;
-                };
-
-                if !cost_so_far.contains_key(&next)
-                    || Some(new_cost) < cost_so_far.get(&next).copied()
-                {
-                    cost_so_far.insert(next, new_cost);
-                    frontier.push(next, -new_cost - next.distance(to).to_isize() as i32);
-                    came_from.insert(next, Some(current));
+        // The number of positions of the layout found in the rings explored so far.
+        let mut visited = 0;
+        let mut radius = 0;
+        while matches.len() < k && visited < self.0.len() {
+            for pos in from.spiral_range(radius, radius) {
+                if let Some(data) = self.0.get(&pos) {
+                    visited += 1;
+                    if predicate(data) {
+                        matches.push((pos, data));
+                    }
                 }
             }
+            radius += 1;
         }
 
-        let mut path = vec![to];
-        let mut current = to;
-        while let Some(Some(prev)) = came_from.get(&current) {
-            path.push(*prev);
-            current = *prev;
-        }
-        path.reverse();
-        path
+        matches.sort_by_key(|(pos, _)| {
+            (
+                pos.distance(from).to_isize(),
+                pos.0.to_isize(),
+                pos.1.to_isize(),
+            )
+        });
+        matches.truncate(k);
+        matches
     }
 
-    /// Calculates the positions visible from a given position on a hexagonal map.
-    ///
-    /// This function returns a set of positions that are visible from the `center` position.
-    /// Visibility is determined based on distance and obstacles. Obstacles are defined as positions
-    /// with an associated value of `true`.
+    /// Returns an iterator over the positions of the layout within `bound`, with their data as mutable references.
     ///
-    /// # Parameters
+    /// Unlike [HexLayout::region], the whole layout is always scanned: mutable references can't be
+    /// taken one lookup at a time.
     ///
-    /// - `center`: The starting position from which visibility is calculated.
-    /// - `range`: An optional parameter specifying the maximum visibility range. If `None`, there is no
-    ///   range limit.
+    /// ## Examples
     ///
-    /// # Return Value
+    /// ```rust
+    /// use hexing::{layout::HexLayout, utils::HexBound, HexPosition};
     ///
-    /// Returns a `HashSet` containing the positions visible from `center`. A position is considered
-    /// visible if it is within the specified range (if a radius is provided) and if there are no obstacles
-    /// blocking the line of sight between `center` and the position.
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(6, HexPosition(0, 0));
     ///
-    /// # Example
+    /// for (_, data) in map.region_mut(&HexBound::new(HexPosition(5, 0), 2)) {
+    ///     *data += 1;
+    /// }
     ///
+    /// assert_eq!(map.data().filter(|data| **data == 1).count(), 9);
+    /// assert_eq!(map.get(HexPosition(3, 0)), Some(&1));
+    /// assert_eq!(map.get(HexPosition(2, 0)), Some(&0));
     /// ```
-    /// use hexing::{layout::HexLayout, HexPosition};
+    pub fn region_mut(
+        &mut self,
+        bound: &HexBound<S>,
+    ) -> impl Iterator<Item = (HexPosition<S>, &mut T)> {
+        let bound = *bound;
+        self.0
+            .iter_mut()
+            .filter(move |(pos, _)| bound.contains(**pos))
+            .map(|(pos, data)| (*pos, data))
+    }
+
+    /// Groups the positions of the layout into clusters: two adjacent positions are in the same cluster
+    /// if `same` returns `true` for their data.
     ///
-    /// let mut map = HexLayout::new_from_range(6, HexPosition(0, 0));
+    /// Each cluster is sorted by `q`, then by `r`, and the clusters are sorted by decreasing size
+    /// (then by their first position), so every position of the layout is in exactly one cluster.
     ///
-    /// let start_pos = HexPosition::new(0, 0);
+    /// ## Examples
     ///
-    /// map.set(HexPosition(0, 1), true);
-    /// map.set(HexPosition(1, 0), true);
-    /// map.set(HexPosition(-2, 0), true);
-    /// map.set(HexPosition(-2, 1), true);
-    /// map.set(HexPosition(0, -2), true);
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let reachable_positions = map.field_of_view(start_pos, None);
+    /// #[derive(Default, Clone, PartialEq)]
+    /// enum Terrain {
+    ///     #[default]
+    ///     Plain,
+    ///     Forest,
+    /// }
     ///
-    /// let range = 2;
-    /// let reachable_positions_with_range = map.field_of_view(start_pos, Some(range));
+    /// let mut map: HexLayout<Terrain, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_ring(HexPosition(-3, 0), 1, Terrain::Forest);
+    /// map.fill_line(HexPosition(2, -2), HexPosition(2, 1), Terrain::Forest);
+    /// map.set(HexPosition(0, 4), Terrain::Forest);
     ///
-    /// for pos in reachable_positions {
-    ///    println!("{}", pos);
-    /// }
+    /// let forests: Vec<_> = map
+    ///     .clusters_by(|a, b| a == b)
+    ///     .into_iter()
+    ///     .filter(|cluster| map.get(cluster[0]) == Some(&Terrain::Forest))
+    ///     .collect();
     ///
-    /// for pos in reachable_positions_with_range {
-    ///     println!("{}", pos);
-    /// }
+    /// assert_eq!(forests.iter().map(Vec::len).collect::<Vec<_>>(), vec![6, 4, 1]);
+    /// assert_eq!(forests[2], vec![HexPosition(0, 4)]);
+    ///
+    /// let largest = map.largest_cluster_where(|terrain| *terrain == Terrain::Forest);
+    /// assert_eq!(largest, forests[0]);
     /// ```
-    pub fn field_of_view(
-        &self,
-        center: HexPosition<S>,
-        range: Option<usize>,
-    ) -> HashSet<HexPosition<S>> {
-        let mut visibles = HashSet::new();
+    pub fn clusters_by(&self, same: impl Fn(&T, &T) -> bool) -> Vec<Vec<HexPosition<S>>> {
+        self.clusters_among(|_| true, same)
+    }
+
+    /// Returns the largest cluster of adjacent positions whose data matches `predicate`, see [HexLayout::clusters_by].
+    /// The cluster is empty if no position matches.
+    pub fn largest_cluster_where(&self, predicate: impl Fn(&T) -> bool) -> Vec<HexPosition<S>> {
+        self.clusters_among(&predicate, |_, _| true)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+    }
 
-        for position in self.positions() {
-            let mut is_visible = true;
+    /// Groups the positions whose data matches `include` into clusters, see [HexLayout::clusters_by].
+    fn clusters_among(
+        &self,
+        include: impl Fn(&T) -> bool,
+        same: impl Fn(&T, &T) -> bool,
+    ) -> Vec<Vec<HexPosition<S>>> {
+        let mut visited: HashSet<HexPosition<S>, HexBuildHasher> = HashSet::default();
+        let mut clusters = Vec::new();
 
-            if let Some(radius) = range {
-                if position.distance(center).to_isize() > radius as isize {
-                    is_visible = false;
-                }
+        for (start, data) in &self.0 {
+            if !include(data) || !visited.insert(*start) {
+                continue;
             }
 
-            for position_between in center.line_to(*position) {
-                if !self.0.contains_key(&position_between)
-                    || self.get(position_between) == Some(&true)
-                {
-                    is_visible = false;
-                    break;
+            let mut cluster = vec![*start];
+            let mut index = 0;
+            while let Some(current) = cluster.get(index).copied() {
+                index += 1;
+                let Some(current_data) = self.0.get(&current) else {
+                    continue;
+                };
+                for neighbor in neighbors(current) {
+                    if let Some(neighbor_data) = self.0.get(&neighbor) {
+                        if include(neighbor_data)
+                            && same(current_data, neighbor_data)
+                            && visited.insert(neighbor)
+                        {
+                            cluster.push(neighbor);
+                        }
+                    }
                 }
             }
 
-            if is_visible {
-                visibles.insert(*position);
-            }
+            cluster.sort_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()));
+            clusters.push(cluster);
         }
 
-        visibles
+        clusters.sort_by_key(|cluster| {
+            (
+                Reverse(cluster.len()),
+                cluster[0].0.to_isize(),
+                cluster[0].1.to_isize(),
+            )
+        });
+        clusters
     }
 
-    /// Computes the set of positions reachable from a given starting position within a specified range.
+    /// Returns a position of the layout for which `predicate` returns `true`, or `None` if there is none.
     ///
-    /// This function performs a breadth-first search to determine all the positions that can be reached from the
-    /// starting position `pos` within a movement range of `range` steps. It accounts for obstacles or blocked positions
-    /// using the `neighbors_unblocked` method, which provides a list of neighboring positions that are not blocked.
+    /// The positions are checked in an arbitrary order, so if several of them match, any of them can be returned.
+    /// See [HexLayout::find_sorted] for a deterministic result.
     ///
-    /// # Arguments
+    /// ## Examples
     ///
-    /// * `pos` - The starting position from which to compute the field of move. It is of type `HexPosition<S>`.
-    /// * `range` - The maximum number of steps that can be taken from the starting position. It is of type `usize`.
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// # Returns
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(2, -1), 255);
     ///
-    /// Returns a `HashSet<HexPosition<S>>` containing all the positions reachable within the specified range, including
-    /// the starting position itself.
+    /// assert_eq!(map.find(|_, data| *data == 255), Some(HexPosition(2, -1)));
+    /// assert_eq!(map.find(|_, data| *data == 1), None);
+    /// ```
+    pub fn find(&self, predicate: impl Fn(&HexPosition<S>, &T) -> bool) -> Option<HexPosition<S>> {
+        self.0
+            .iter()
+            .find(|(pos, data)| predicate(pos, data))
+            .map(|(pos, _)| *pos)
+    }
+
+    /// Returns the smallest position of the layout, by `q` then by `r`, for which `predicate` returns `true`.
     ///
-    /// # Example
+    /// ## Examples
     ///
     /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// use hexing::HexPosition;
-    /// use hexing::layout::HexLayout;
+    /// let map: HexLayout<u8, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
     ///
-    /// let mut map = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// assert_eq!(map.find_sorted(|pos, _| pos.0 >= 2), Some(HexPosition(2, -3)));
+    /// ```
+    pub fn find_sorted(
+        &self,
+        predicate: impl Fn(&HexPosition<S>, &T) -> bool,
+    ) -> Option<HexPosition<S>> {
+        self.0
+            .iter()
+            .filter(|(pos, data)| predicate(pos, data))
+            .map(|(pos, _)| *pos)
+            .min_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()))
+    }
+
+    /// Returns the position of the layout closest to `pos`, which is `pos` itself if it is in the layout.
     ///
-    /// let start_pos = HexPosition::new(0, 0);
-    /// let range = 2;
+    /// Among several positions at the same distance, the smallest one by `q`, then by `r`, is returned.
+    /// Returns `None` if the layout is empty.
     ///
-    /// let reachable_positions = map.field_of_move(start_pos, range);
-    /// assert_eq!(reachable_positions.len(), 19);
+    /// ## Examples
     ///
-    /// map.set(HexPosition(0, 1), true);
-    /// map.set(HexPosition(1, 0), true);
-    /// map.set(HexPosition(0, -2), true);
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let reachable_positions = map.field_of_move(start_pos, range);
-    /// assert_eq!(reachable_positions.len(), 13);
+    /// let map: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// assert_eq!(map.closest(HexPosition(1, -1)), Some(HexPosition(1, -1)));
+    /// assert_eq!(map.closest(HexPosition(6, 0)), Some(HexPosition(2, 0)));
+    /// assert_eq!(map.closest(HexPosition(4, -4)), Some(HexPosition(2, -2)));
+    /// // Several border positions are at distance 2 of (3, 1).
+    /// assert_eq!(map.closest(HexPosition(3, 1)), Some(HexPosition(1, 1)));
     /// ```
+    pub fn closest(&self, pos: HexPosition<S>) -> Option<HexPosition<S>> {
+        if self.0.contains_key(&pos) {
+            return Some(pos);
+        }
+        self.0.keys().copied().min_by_key(|other| {
+            (
+                other.distance(pos).to_isize(),
+                other.0.to_isize(),
+                other.1.to_isize(),
+            )
+        })
+    }
+
+    /// Returns the positions and data of the layout, sorted by `q`, then by `r`.
     ///
-    /// # Notes
+    /// Unlike [HexLayout::iter], the order is always the same, which is useful for reproducible exports.
     ///
-    /// The function uses a breadth-first approach, iterating level by level up to the specified range. It maintains
-    /// a set of visited positions to avoid reprocessing and ensure that each position is added only once. The number
-    /// of positions added at each level is managed to ensure the function scales efficiently with the size of the range.
+    /// ## Examples
     ///
-    /// # Complexity
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// The time complexity is O(n), where n is the number of positions within the specified range, assuming neighbor
-    /// checks are constant-time operations.
-    pub fn field_of_move(&self, pos: HexPosition<S>, range: usize) -> HashSet<HexPosition<S>> {
-        let mut visited = HashSet::new();
-        visited.insert(pos);
-        let mut fringes = Vec::new();
-        fringes.push(vec![pos]);
-
-        for k in 1..=range {
-            fringes.push(Vec::new());
-
-            let mut to_add = Vec::with_capacity(fringes[k - 1].len() * 6);
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, -1), 4);
+    ///
+    /// let entries = map.entries_sorted();
+    /// assert_eq!(
+    ///     entries,
+    ///     vec![
+    ///         (HexPosition(-1, 0), &0),
+    ///         (HexPosition(-1, 1), &0),
+    ///         (HexPosition(0, -1), &0),
+    ///         (HexPosition(0, 0), &0),
+    ///         (HexPosition(0, 1), &0),
+    ///         (HexPosition(1, -1), &4),
+    ///         (HexPosition(1, 0), &0),
+    ///     ]
+    /// );
+    /// ```
+    pub fn entries_sorted(&self) -> Vec<(HexPosition<S>, &T)> {
+        let mut entries: Vec<(HexPosition<S>, &T)> =
+            self.0.iter().map(|(pos, data)| (*pos, data)).collect();
+        entries.sort_by_key(|(pos, _)| (pos.0.to_isize(), pos.1.to_isize()));
+        entries
+    }
 
-            for pos in fringes[k - 1].iter() {
-                for neighbor in self.neighbors_unblocked(*pos) {
-                    if visited.contains(&neighbor) {
-                        continue;
-                    }
+    /// Consumes the layout and returns its positions and data, sorted by `q`, then by `r` like [HexLayout::entries_sorted].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<String, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, -1), "tower".to_string());
+    ///
+    /// let entries = map.to_vec_sorted();
+    /// assert_eq!(entries.len(), 7);
+    /// assert_eq!(entries[0].0, HexPosition(-1, 0));
+    /// assert_eq!(entries[5], (HexPosition(1, -1), "tower".to_string()));
+    /// ```
+    pub fn to_vec_sorted(self) -> Vec<(HexPosition<S>, T)> {
+        let mut entries: Vec<(HexPosition<S>, T)> = self.0.into_iter().collect();
+        entries.sort_by_key(|(pos, _)| (pos.0.to_isize(), pos.1.to_isize()));
+        entries
+    }
 
-                    visited.insert(neighbor);
-                    to_add.push(neighbor);
-                }
-            }
+    /// Returns the map storing the data of the layout.
+    pub const fn as_map(&self) -> &HashMap<HexPosition<S>, T, H> {
+        &self.0
+    }
 
-            fringes[k] = to_add;
-        }
+    /// Consumes the layout and returns the map storing its data, without cloning it.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map = HashMap::from([(HexPosition(0, 0), 1), (HexPosition(2, -1), 5)]);
+    /// let layout: HexLayout<u8, isize> = HexLayout::from(map.clone());
+    /// assert_eq!(layout.get(HexPosition(2, -1)), Some(&5));
+    /// assert_eq!(layout.as_map().len(), 2);
+    ///
+    /// let round_trip: HashMap<_, _> = layout.into_inner().into_iter().collect();
+    /// assert_eq!(round_trip, map);
+    /// ```
+    pub fn into_inner(self) -> HashMap<HexPosition<S>, T, H> {
+        self.0
+    }
 
-        visited
+    /// Creates a layout from a rectangular grid, where `rows[row][col]` is the data of the hexagon at the offset
+    /// coordinates `(col, row)` (see [OffsetLayout]), and the hexagon at `(0, 0)` is at `origin`.
+    ///
+    /// This is useful to import maps made with tile editors or loaded from CSV files. The rows don't need to have the
+    /// same length.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, utils::OffsetLayout, HexPosition};
+    ///
+    /// let rows = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+    /// let map: HexLayout<i32, i32> = HexLayout::from_dense_rows(rows, HexPosition(5, 5), OffsetLayout::OddR);
+    ///
+    /// assert_eq!(map.len(), 12);
+    /// assert_eq!(map.get(HexPosition(5, 5)), Some(&0));
+    /// assert_eq!(map.get(HexPosition(8, 5)), Some(&3));
+    /// assert_eq!(map.get(HexPosition(5, 6)), Some(&4));
+    /// assert_eq!(map.get(HexPosition(4, 7)), Some(&8));
+    /// assert_eq!(map.get(HexPosition(7, 7)), Some(&11));
+    /// ```
+    pub fn from_dense_rows(
+        rows: Vec<Vec<T>>,
+        origin: HexPosition<S>,
+        offset_layout: OffsetLayout,
+    ) -> Self {
+        rows.into_iter()
+            .enumerate()
+            .flat_map(|(row, data)| {
+                data.into_iter().enumerate().map(move |(col, data)| {
+                    (
+                        origin + offset_layout.to_hex(col as isize, row as isize),
+                        data,
+                    )
+                })
+            })
+            .collect()
     }
 
-    /// Returns a list of all the neighbors that are not blocked.
+    /// Returns the number of positions in the layout.
     ///
-    /// # Example
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<f64, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
     ///
+    /// assert_eq!(map.len(), 1);
     /// ```
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the layout is empty, otherwise returns `false`.
+    ///
+    /// ## Examples
     ///
+    /// ```rust
     /// use hexing::{layout::HexLayout, HexPosition};
     ///
-    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map: HexLayout<f64, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
     ///
-    /// let neighbors = map.neighbors_unblocked(HexPosition(0, 0));
-    /// assert_eq!(neighbors.len(), 6);
+    /// assert!(!map.is_empty());
     ///
     /// map.delete(HexPosition(0, 0));
-    /// map.set(HexPosition(1, 0), true);
     ///
-    /// let neighbors = map.neighbors_unblocked(HexPosition(1, 1));
-    /// assert_eq!(neighbors.len(), 3); // edge of the map
+    /// assert!(map.is_empty());
     /// ```
-    pub fn neighbors_unblocked(&self, pos: HexPosition<S>) -> Vec<HexPosition<S>> {
-        let mut result_neighbors = Vec::with_capacity(6);
-        for neighbor in neighbors(pos) {
-            if !self.0.get(&neighbor).unwrap_or(&true) {
-                result_neighbors.push(neighbor);
-            }
-        }
-        result_neighbors
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Clears all data from the layout, leaving it empty.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<f64, isize> = HexLayout::new_from_range(7, HexPosition(0, 0));
+    ///
+    /// assert!(!map.is_empty());
+    ///
+    /// map.clear();
+    ///
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Computes the next generation of a cellular automaton running on the layout, like the game of life,
+    /// fire spreading or cave smoothing.
+    ///
+    /// The new data of each position is `rule(position, data, neighbors)`, where `neighbors` iterates over
+    /// the data of the neighbors that are in the layout. All the positions are computed from the current generation,
+    /// and the result has the same positions as the layout.
+    /// See [HexLayout::step_ca_into] to reuse the memory of a previous generation.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::{HexLayout, NeighborIter}, utils::neighbors, HexPosition};
+    ///
+    /// const GRID_SIZE: usize = 8;
+    ///
+    /// // The update loop that the `game-of-life` example used before `step_ca`.
+    /// fn next_generation(grid: &HexLayout<bool, isize>) -> HexLayout<bool, isize> {
+    ///     let mut next_grid = HexLayout::<bool, isize>::new_from_range(GRID_SIZE, HexPosition(0, 0));
+    ///
+    ///     for pos in grid.positions() {
+    ///         let neighbors = neighbors(*pos);
+    ///         let alive_neighbors = neighbors
+    ///             .iter()
+    ///             .filter(|&&neighbor| *grid.get(neighbor).unwrap_or(&false))
+    ///             .count();
+    ///
+    ///         let current_state = *grid.get(*pos).unwrap();
+    ///         let next_state = match (current_state, alive_neighbors) {
+    ///             (true, x) if x < 2 || x > 2 => false,
+    ///             (false, 2) => true,
+    ///             _ => current_state,
+    ///         };
+    ///
+    ///         next_grid.set(*pos, next_state);
+    ///     }
+    ///
+    ///     next_grid
+    /// }
+    ///
+    /// // The same rule, given to `step_ca_into`.
+    /// fn next_state(_pos: HexPosition<isize>, current_state: &bool, neighbors: NeighborIter<'_, bool, isize>) -> bool {
+    ///     let alive_neighbors = neighbors.filter(|&&alive| alive).count();
+    ///     match (*current_state, alive_neighbors) {
+    ///         (true, x) if x < 2 || x > 2 => false,
+    ///         (false, 2) => true,
+    ///         _ => *current_state,
+    ///     }
+    /// }
+    ///
+    /// // A blinker-like pattern: a short line of alive cells.
+    /// let mut grid: HexLayout<bool, isize> = HexLayout::new_from_range(GRID_SIZE, HexPosition(0, 0));
+    /// for pos in [HexPosition(-1, 0), HexPosition(0, 0), HexPosition(1, 0)] {
+    ///     grid.set(pos, true);
+    /// }
+    ///
+    /// let mut expected = grid.clone();
+    /// let mut next_grid = grid.clone();
+    /// for _ in 0..6 {
+    ///     expected = next_generation(&expected);
+    ///
+    ///     grid.step_ca_into(&mut next_grid, next_state);
+    ///     std::mem::swap(&mut grid, &mut next_grid);
+    ///     assert_eq!(grid, expected);
+    ///     assert_eq!(grid, next_grid.step_ca(next_state));
+    /// }
+    /// assert!(grid.iter().any(|(_, alive)| *alive));
+    /// ```
+    pub fn step_ca(
+        &self,
+        rule: impl Fn(HexPosition<S>, &T, NeighborIter<'_, T, S, H>) -> T,
+    ) -> Self {
+        let mut next = Self(HashMap::with_capacity_and_hasher(
+            self.0.len(),
+            H::default(),
+        ));
+        self.step_ca_into(&mut next, rule);
+        next
+    }
+
+    /// Same as [HexLayout::step_ca], but writes the next generation into `out` instead of allocating a new layout.
+    ///
+    /// The previous content of `out` is discarded, but its memory is reused: swapping two layouts between
+    /// the generations avoids any allocation after the first one.
+    pub fn step_ca_into(
+        &self,
+        out: &mut Self,
+        rule: impl Fn(HexPosition<S>, &T, NeighborIter<'_, T, S, H>) -> T,
+    ) {
+        out.0.clear();
+        for (pos, data) in &self.0 {
+            let neighbors = NeighborIter {
+                layout: self,
+                center: *pos,
+                index: 0,
+            };
+            out.0.insert(*pos, rule(*pos, data, neighbors));
+        }
+    }
+
+    /// Counts, for each position of the layout, its neighbors in the layout whose data matches `predicate`.
+    ///
+    /// The counts are computed in a single pass: each matching position increments the count of its neighbors,
+    /// so only the neighbors of the matching positions are looked up, instead of the neighbors of every position.
+    /// See [HexLayout::neighbor_counts_sealed] to count the missing neighbors as matching.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, utils::neighbors, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(-3, 0), HexPosition(3, 0), true);
+    /// map.fill_ring(HexPosition(1, -2), 1, true);
+    ///
+    /// let counts = map.neighbor_counts(|blocked| *blocked);
+    /// assert_eq!(counts.len(), map.len());
+    /// assert_eq!(counts[&HexPosition(0, 0)], 4);
+    /// assert_eq!(counts[&HexPosition(1, -2)], 6);
+    ///
+    /// // The same counts as looking up the neighbors of every position.
+    /// for (pos, count) in &counts {
+    ///     let naive = neighbors(*pos).into_iter().filter(|n| map.get(*n) == Some(&true)).count();
+    ///     assert_eq!(*count as usize, naive);
+    /// }
+    /// ```
+    pub fn neighbor_counts(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+    ) -> HashMap<HexPosition<S>, u8, HexBuildHasher> {
+        self.count_neighbors(predicate, false)
+    }
+
+    /// Same as [HexLayout::neighbor_counts], but the neighbors that are not in the layout are counted as matching,
+    /// so the border of the layout behaves as if it was surrounded by matching positions.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// let counts = map.neighbor_counts_sealed(|blocked| *blocked);
+    /// assert_eq!(counts[&HexPosition(0, 0)], 0);
+    /// assert_eq!(counts[&HexPosition(1, 0)], 0);
+    /// assert_eq!(counts[&HexPosition(2, 0)], 3);
+    /// assert_eq!(counts[&HexPosition(2, -1)], 2);
+    /// ```
+    pub fn neighbor_counts_sealed(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+    ) -> HashMap<HexPosition<S>, u8, HexBuildHasher> {
+        self.count_neighbors(predicate, true)
+    }
+
+    /// Counts the matching neighbors of each position, see [HexLayout::neighbor_counts].
+    fn count_neighbors(
+        &self,
+        predicate: impl Fn(&T) -> bool,
+        missing_match: bool,
+    ) -> HashMap<HexPosition<S>, u8, HexBuildHasher> {
+        let mut counts: HashMap<HexPosition<S>, u8, HexBuildHasher> =
+            self.0.keys().map(|pos| (*pos, 0)).collect();
+
+        for (pos, data) in &self.0 {
+            if !missing_match && !predicate(data) {
+                continue;
+            }
+            let matches = predicate(data);
+            let mut missing = 0;
+            for direction in HexDirection::iter() {
+                match counts.get_mut(&(*pos + direction.to_vector())) {
+                    Some(count) if matches => *count += 1,
+                    Some(_) => {}
+                    None => missing += 1,
+                }
+            }
+            if missing_match {
+                if let Some(count) = counts.get_mut(pos) {
+                    *count += missing;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Removes all the positions matching `predicate` and returns them with their data, in no particular order.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<f64, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    ///
+    /// // An explosion destroys everything within one tile of the origin.
+    /// let destroyed = map.remove_where(|pos, _| pos.distance(HexPosition(0, 0)) <= 1);
+    ///
+    /// assert_eq!(destroyed.len(), 7);
+    /// assert_eq!(map.len(), 37 - 7);
+    /// assert_eq!(map.get(HexPosition(1, 0)), None);
+    /// ```
+    pub fn remove_where(
+        &mut self,
+        predicate: impl Fn(&HexPosition<S>, &T) -> bool,
+    ) -> Vec<(HexPosition<S>, T)> {
+        let removed: Vec<HexPosition<S>> = self
+            .0
+            .iter()
+            .filter(|(pos, data)| predicate(pos, data))
+            .map(|(pos, _)| *pos)
+            .collect();
+        removed
+            .into_iter()
+            .filter_map(|pos| self.0.remove(&pos).map(|data| (pos, data)))
+            .collect()
+    }
+
+    /// Splits the layout into several layouts, grouping the positions by the key computed from their data.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<i32, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// for (i, pos) in HexPosition(0, 0).spiral(1).enumerate() {
+    ///     map.set(pos, i as i32);
+    /// }
+    ///
+    /// let parts = map.partition_by(|data| data % 2 == 0);
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[&true].len(), 4);
+    /// assert_eq!(parts[&false].len(), 3);
+    /// assert_eq!(parts[&true].get(HexPosition(0, 0)), Some(&0));
+    /// ```
+    pub fn partition_by<K: Eq + Hash>(&self, key: impl Fn(&T) -> K) -> HashMap<K, Self>
+    where
+        T: Clone,
+    {
+        let mut result: HashMap<K, Self> = HashMap::new();
+        for (pos, data) in self.iter() {
+            result
+                .entry(key(data))
+                .or_insert_with(|| Self(HashMap::default()))
+                .set(*pos, data.clone());
+        }
+        result
+    }
+
+    /// Returns `true` if the [HexShape] can be placed with its anchor at `anchor`:
+    /// every hexagon it covers must be in the layout, with data for which `is_free` returns `true`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, shape::HexShape, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let triomino = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(0, 1)]);
+    ///
+    /// assert!(map.can_place(&triomino, HexPosition(0, 0), |blocked| !blocked));
+    ///
+    /// // A covered hexagon is missing.
+    /// assert!(!map.can_place(&triomino, HexPosition(2, 0), |blocked| !blocked));
+    ///
+    /// // A covered hexagon is blocked.
+    /// map.set(HexPosition(0, 1), true);
+    /// assert!(!map.can_place(&triomino, HexPosition(0, 0), |blocked| !blocked));
+    /// ```
+    pub fn can_place(
+        &self,
+        shape: &HexShape<S>,
+        anchor: HexPosition<S>,
+        is_free: impl Fn(&T) -> bool,
+    ) -> bool {
+        shape
+            .translated_to(anchor)
+            .all(|pos| self.get(pos).is_some_and(&is_free))
+    }
+
+    /// Sets `value` on every hexagon covered by the [HexShape] placed with its anchor at `anchor`.
+    ///
+    /// Hexagons missing from the layout are created. Use [HexLayout::can_place] first to check the placement.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, shape::HexShape, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// map.place(&HexShape::hexagon(1), HexPosition(1, 0), 7);
+    ///
+    /// assert_eq!(map.data().filter(|data| **data == 7).count(), 7);
+    /// assert_eq!(map.get(HexPosition(2, 0)), Some(&7));
+    /// ```
+    pub fn place(&mut self, shape: &HexShape<S>, anchor: HexPosition<S>, value: T)
+    where
+        T: Clone,
+    {
+        for pos in shape.translated_to(anchor) {
+            self.set(pos, value.clone());
+        }
+    }
+
+    /// Returns a [HexBound] containing every position of the layout, or `None` if the layout is empty.
+    ///
+    /// The center of the bound is the middle of the extents of the layout, so the bound is tight
+    /// for hexagonal layouts but is not guaranteed to be the smallest one for arbitrary shapes.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(2, -1));
+    /// let bound = map.bounding_hex().unwrap();
+    ///
+    /// assert_eq!(bound.center(), HexPosition(2, -1));
+    /// assert_eq!(bound.radius(), 2);
+    /// assert!(map.positions().all(|pos| bound.contains(*pos)));
+    /// ```
+    pub fn bounding_hex(&self) -> Option<HexBound<S>> {
+        let mut positions = self.positions();
+        let first = positions.next()?;
+        let first = (first.0.to_isize(), first.1.to_isize());
+
+        let (mut min, mut max) = (first, first);
+        for pos in positions {
+            let (q, r) = (pos.0.to_isize(), pos.1.to_isize());
+            min = (std::cmp::min(min.0, q), std::cmp::min(min.1, r));
+            max = (std::cmp::max(max.0, q), std::cmp::max(max.1, r));
+        }
+
+        let center =
+            utils::axial_round(((min.0 + max.0) as f32 / 2.0, (min.1 + max.1) as f32 / 2.0));
+        let center = HexPosition(
+            S::from_isize(center.0 as isize),
+            S::from_isize(center.1 as isize),
+        );
+        let radius = self
+            .positions()
+            .map(|pos| center.distance(*pos).to_isize() as usize)
+            .max()
+            .unwrap_or(0);
+
+        Some(HexBound::new(center, radius))
+    }
+
+    /// Returns the positions whose pixel center (see [HexPosition::to_pixel_coordinates]) has a `y` coordinate
+    /// between `y_min` and `y_max` (both included), sorted by their `x` coordinate.
+    ///
+    /// This is useful to draw the hexagons row by row, so that they overlap correctly.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<u8, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    ///
+    /// let row = map.positions_in_pixel_row(1.0, 2.0);
+    /// assert_eq!(row.len(), 6);
+    /// for pos in &row {
+    ///     let (_, y) = pos.to_pixel_coordinates();
+    ///     assert!((1.0..=2.0).contains(&y));
+    /// }
+    /// assert!(row.windows(2).all(|pair| pair[0].to_pixel_coordinates().0 < pair[1].to_pixel_coordinates().0));
+    /// ```
+    pub fn positions_in_pixel_row(&self, y_min: f32, y_max: f32) -> Vec<HexPosition<S>> {
+        let mut row: Vec<(f32, HexPosition<S>)> = self
+            .0
+            .keys()
+            .map(|pos| (pos.to_pixel_coordinates(), *pos))
+            .filter(|((_, y), _)| (y_min..=y_max).contains(y))
+            .map(|((x, _), pos)| (x, pos))
+            .collect();
+        row.sort_by(|a, b| a.0.total_cmp(&b.0));
+        row.into_iter().map(|(_, pos)| pos).collect()
+    }
+
+    /// Returns a radius around `center` large enough for the hexagonal area to contain every position of the layout.
+    fn covering_radius(&self, center: HexPosition<S>) -> usize {
+        self.bounding_hex().map_or(0, |bound| {
+            center.distance(bound.center()).to_isize() as usize + bound.radius()
+        })
+    }
+
+    /// Returns an iterator over the positions and data of the layout, walking rings of increasing radius around `center`.
+    ///
+    /// Positions that are not in the layout are skipped, and the iteration stops after the last ring
+    /// that can contain a position of the layout (computed from [HexLayout::bounding_hex]), so it ends even on sparse layouts.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<i32, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// map.delete(HexPosition(1, 0));
+    ///
+    /// let spiral: Vec<_> = map.iter_spiral(HexPosition(0, 0)).collect();
+    /// assert_eq!(spiral.len(), 18);
+    /// assert_eq!(spiral[0].0, HexPosition(0, 0));
+    /// assert!(spiral.iter().all(|(pos, _)| *pos != HexPosition(1, 0)));
+    ///
+    /// // A sparse layout with two tiles far apart.
+    /// let mut sparse: HexLayout<i32, isize> = HexLayout::new_from_range(0, HexPosition(0, 0));
+    /// sparse.set(HexPosition(-20, 5), 1);
+    /// sparse.set(HexPosition(20, -5), 2);
+    ///
+    /// let spiral: Vec<_> = sparse.iter_spiral(HexPosition(-20, 5)).collect();
+    /// assert_eq!(spiral, vec![(HexPosition(-20, 5), &1), (HexPosition(20, -5), &2)]);
+    /// ```
+    pub fn iter_spiral(
+        &self,
+        center: HexPosition<S>,
+    ) -> impl Iterator<Item = (HexPosition<S>, &T)> + '_ {
+        center
+            .spiral(self.covering_radius(center))
+            .filter_map(move |pos| self.get(pos).map(|data| (pos, data)))
+    }
+
+    /// Computes the logical AND operation between two layouts, returning a set of positions that exist in both layouts.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map1: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map2: HexLayout<f64, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    ///
+    /// let and = map1.and(&map2);
+    ///
+    /// assert_eq!(and.len(), 4);
+    /// ```
+    pub fn and(&self, other: &Self) -> HashSet<HexPosition<S>> {
+        self.0
+            .keys()
+            .filter_map(|pos| {
+                if other.0.contains_key(pos) {
+                    Some(*pos)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Computes the logical OR operation between two layouts, returning a set of all unique positions that exist in either layout.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map1: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map2: HexLayout<f64, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    ///
+    /// let or = map1.or(&map2);
+    ///
+    /// assert_eq!(or.len(), 22);
+    /// ```
+    pub fn or(&self, other: &Self) -> HashSet<HexPosition<S>> {
+        let mut result = HashSet::with_capacity(self.0.len() + other.0.len());
+        result.extend(self.0.keys().copied());
+        result.extend(other.0.keys().copied());
+        result
+    }
+
+    /// Computes the logical XOR operation between two layouts, returning a set of positions that exist in one layout but not the other.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map1: HexLayout<f64, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map2: HexLayout<f64, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    ///
+    /// let xor = map1.xor(&map2);
+    ///
+    /// assert_eq!(xor.len(), 18);
+    /// ```
+    pub fn xor(&self, other: &Self) -> HashSet<HexPosition<S>> {
+        let mut result = HashSet::with_capacity(self.0.len() + other.0.len());
+
+        result.extend(self.0.keys().filter(|k| !other.0.contains_key(k)).copied());
+        result.extend(other.0.keys().filter(|k| !self.0.contains_key(k)).copied());
+
+        result
+    }
+}
+
+impl<D: Default + Clone, S: Number, H: BuildHasher + Default + Clone> HexLayout<D, S, H> {
+    /// Sets `value` at every position of the layout within `bound`. Returns the number of positions written.
+    ///
+    /// Only the positions already in the layout are written, see [HexLayout::fill_bound_or_insert] to add the missing ones.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, utils::HexBound, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// // Half of the bound is outside of the layout.
+    /// let bound = HexBound::new(HexPosition(2, 0), 1);
+    /// assert_eq!(map.fill_bound(&bound, 5), 4);
+    /// assert_eq!(map.get(HexPosition(1, 0)), Some(&5));
+    /// assert_eq!(map.len(), 19);
+    ///
+    /// assert_eq!(map.fill_bound_or_insert(&bound, 6), 7);
+    /// assert_eq!(map.len(), 22);
+    /// ```
+    pub fn fill_bound(&mut self, bound: &HexBound<S>, value: D) -> usize {
+        self.fill_positions(bound.center().spiral(bound.radius()), value, false)
+    }
+
+    /// Like [HexLayout::fill_bound], but adds the positions that are not in the layout.
+    pub fn fill_bound_or_insert(&mut self, bound: &HexBound<S>, value: D) -> usize {
+        self.fill_positions(bound.center().spiral(bound.radius()), value, true)
+    }
+
+    /// Sets `value` at every position of the layout on the ring of the given radius around `center`.
+    /// Returns the number of positions written.
+    ///
+    /// Only the positions already in the layout are written, see [HexLayout::fill_ring_or_insert] to add the missing ones.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// assert_eq!(map.fill_ring(HexPosition(0, 0), 2, 1), 12);
+    /// assert_eq!(map.fill_ring(HexPosition(2, 0), 1, 1), 3);
+    /// assert_eq!(map.fill_ring_or_insert(HexPosition(2, 0), 1, 1), 6);
+    /// assert_eq!(map.fill_ring(HexPosition(0, 0), 0, 1), 1);
+    /// ```
+    pub fn fill_ring(&mut self, center: HexPosition<S>, radius: usize, value: D) -> usize {
+        self.fill_positions(center.spiral_range(radius, radius), value, false)
+    }
+
+    /// Like [HexLayout::fill_ring], but adds the positions that are not in the layout.
+    pub fn fill_ring_or_insert(
+        &mut self,
+        center: HexPosition<S>,
+        radius: usize,
+        value: D,
+    ) -> usize {
+        self.fill_positions(center.spiral_range(radius, radius), value, true)
+    }
+
+    /// Sets `value` at every position of the layout on the line from `a` to `b` (both included).
+    /// Returns the number of positions written.
+    ///
+    /// Only the positions already in the layout are written, see [HexLayout::fill_line_or_insert] to add the missing ones.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// assert_eq!(map.fill_line(HexPosition(-2, 0), HexPosition(4, 0), true), 5);
+    /// assert_eq!(map.get(HexPosition(2, 0)), Some(&true));
+    /// assert_eq!(map.fill_line_or_insert(HexPosition(-2, 0), HexPosition(4, 0), true), 7);
+    /// assert_eq!(map.get(HexPosition(4, 0)), Some(&true));
+    /// ```
+    pub fn fill_line(&mut self, a: HexPosition<S>, b: HexPosition<S>, value: D) -> usize {
+        self.fill_positions(a.line_to(b), value, false)
+    }
+
+    /// Like [HexLayout::fill_line], but adds the positions that are not in the layout.
+    pub fn fill_line_or_insert(&mut self, a: HexPosition<S>, b: HexPosition<S>, value: D) -> usize {
+        self.fill_positions(a.line_to(b), value, true)
+    }
+
+    /// Sets `value` at every position of the layout for which `predicate` returns `true`.
+    /// Returns the number of positions written.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<i32, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), 4);
+    ///
+    /// assert_eq!(map.fill_where(|pos, data| pos.0 > 0 && *data == 0, 2), 6);
+    /// assert_eq!(map.get(HexPosition(1, 0)), Some(&4));
+    /// assert_eq!(map.get(HexPosition(2, 0)), Some(&2));
+    /// ```
+    pub fn fill_where(
+        &mut self,
+        predicate: impl Fn(HexPosition<S>, &D) -> bool,
+        value: D,
+    ) -> usize {
+        let mut written = 0;
+        for (pos, data) in self.0.iter_mut() {
+            if predicate(*pos, data) {
+                *data = value.clone();
+                written += 1;
+            }
+        }
+        written
+    }
+
+    /// Same as [HexLayout::and], but returns a layout with the data of `self` at the positions in both layouts.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map1: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map2: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    /// map1.fill_where(|_, _| true, 1);
+    /// map2.fill_where(|_, _| true, 2);
+    ///
+    /// let and = map1.intersection_layout(&map2);
+    /// assert_eq!(and.positions().copied().collect::<std::collections::HashSet<_>>(), map1.and(&map2));
+    /// assert!(and.data().all(|data| *data == 1));
+    /// ```
+    pub fn intersection_layout(&self, other: &Self) -> Self {
+        let mut result = Self(HashMap::with_capacity_and_hasher(
+            std::cmp::min(self.0.len(), other.0.len()),
+            H::default(),
+        ));
+        result.0.extend(
+            self.0
+                .iter()
+                .filter(|(pos, _)| other.0.contains_key(pos))
+                .map(|(pos, data)| (*pos, data.clone())),
+        );
+        result
+    }
+
+    /// Same as [HexLayout::or], but returns a layout with the data of `self`, and the data of `other`
+    /// at the positions that are only in `other`.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map1: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map2: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    /// map1.fill_where(|_, _| true, 1);
+    /// map2.fill_where(|_, _| true, 2);
+    ///
+    /// let or = map1.union_layout(&map2);
+    /// assert_eq!(or.positions().copied().collect::<std::collections::HashSet<_>>(), map1.or(&map2));
+    /// assert_eq!(or.get(HexPosition(-1, 0)), Some(&1));
+    /// assert_eq!(or.get(HexPosition(-3, 0)), Some(&2));
+    /// ```
+    pub fn union_layout(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.0.reserve(other.0.len());
+        for (pos, data) in &other.0 {
+            result.0.entry(*pos).or_insert_with(|| data.clone());
+        }
+        result
+    }
+
+    /// Same as [HexLayout::xor], but returns a layout with the data of the layout containing each position.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map1: HexLayout<u8, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let mut map2: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(-2, 0));
+    /// map1.fill_where(|_, _| true, 1);
+    /// map2.fill_where(|_, _| true, 2);
+    ///
+    /// let xor = map1.symmetric_difference_layout(&map2);
+    /// assert_eq!(xor.positions().copied().collect::<std::collections::HashSet<_>>(), map1.xor(&map2));
+    /// assert_eq!(xor.get(HexPosition(-1, 0)), None);
+    /// assert_eq!(xor.get(HexPosition(2, 0)), Some(&1));
+    /// assert_eq!(xor.get(HexPosition(-3, 0)), Some(&2));
+    /// ```
+    pub fn symmetric_difference_layout(&self, other: &Self) -> Self {
+        let mut result = Self(HashMap::with_capacity_and_hasher(
+            self.0.len() + other.0.len(),
+            H::default(),
+        ));
+        for (layout, other) in [(self, other), (other, self)] {
+            result.0.extend(
+                layout
+                    .0
+                    .iter()
+                    .filter(|(pos, _)| !other.0.contains_key(pos))
+                    .map(|(pos, data)| (*pos, data.clone())),
+            );
+        }
+        result
+    }
+
+    /// Makes the layout invariant under `symmetry`, for example to generate fair maps for several players.
+    ///
+    /// The positions are grouped by orbit (see [HexSymmetry::orbit]), and `resolve` gets the data of the positions
+    /// of each orbit that are in the layout. Its result is written to every position of the orbit,
+    /// adding the positions missing from the layout.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexDirection, HexPosition, HexSymmetry, HexWinding};
+    ///
+    /// let center = HexPosition(0, 0);
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(6, center);
+    /// let mut seed = 5u64;
+    /// map.apply(|_, data| {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     *data = (seed >> 33) as u8;
+    /// });
+    /// assert!(!map.is_symmetric(HexSymmetry::Rotation3(center)));
+    ///
+    /// map.symmetrize(HexSymmetry::Rotation3(center), |values| **values.iter().max().unwrap());
+    /// assert!(map.is_symmetric(HexSymmetry::Rotation3(center)));
+    ///
+    /// // Rotating the map by 120 degrees gives the same map.
+    /// let rotated: HexLayout<u8, isize> = map
+    ///     .iter()
+    ///     .map(|(pos, data)| (pos.rotate(center, 2, HexWinding::CounterClockwise), *data))
+    ///     .collect();
+    /// assert_eq!(rotated.entries_sorted(), map.entries_sorted());
+    ///
+    /// // The missing positions of the orbits are added.
+    /// let mut bases: HexLayout<u8, isize> = HexLayout::new_from_range(1, HexPosition(3, 0));
+    /// bases.symmetrize(HexSymmetry::Rotation3(center), |values| *values[0]);
+    /// assert_eq!(bases.len(), 3);
+    /// assert!(bases.get(HexPosition(-3, 3)).is_some());
+    ///
+    /// // A mirror axis through the corners of the central hexagon.
+    /// let mirror = HexSymmetry::MirrorBetween(center, HexDirection::UpLeft);
+    /// assert!(!map.is_symmetric(mirror));
+    /// map.symmetrize(mirror, |values| **values.iter().min().unwrap());
+    /// assert!(map.is_symmetric(mirror));
+    /// assert_eq!(map.get(HexPosition(0, -1)), map.get(HexPosition(-1, 0)));
+    /// ```
+    pub fn symmetrize(&mut self, symmetry: HexSymmetry<S>, resolve: impl Fn(Vec<&D>) -> D) {
+        let mut positions: Vec<HexPosition<S>> = self.0.keys().copied().collect();
+        positions.sort_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()));
+
+        let mut done: HashSet<HexPosition<S>, HexBuildHasher> = HashSet::default();
+        let mut resolved = Vec::new();
+        for pos in positions {
+            if done.contains(&pos) {
+                continue;
+            }
+            let orbit = symmetry.orbit(pos);
+            let values = orbit
+                .iter()
+                .filter_map(|member| self.0.get(member))
+                .collect();
+            let value = resolve(values);
+            done.extend(orbit.iter().copied());
+            resolved.push((orbit, value));
+        }
+
+        for (orbit, value) in resolved {
+            for member in orbit {
+                self.0.insert(member, value.clone());
+            }
+        }
+    }
+
+    /// Sets `value` at the given positions, adding the missing ones if `insert` is `true`.
+    /// Returns the number of positions written.
+    fn fill_positions(
+        &mut self,
+        positions: impl Iterator<Item = HexPosition<S>>,
+        value: D,
+        insert: bool,
+    ) -> usize {
+        let mut written = 0;
+        for pos in positions {
+            if let Some(data) = self.0.get_mut(&pos) {
+                *data = value.clone();
+                written += 1;
+            } else if insert {
+                self.0.insert(pos, value.clone());
+                written += 1;
+            }
+        }
+        written
+    }
+}
+
+impl<D: Default + PartialEq + Clone, S: Number, H: BuildHasher + Default + Clone>
+    HexLayout<D, S, H>
+{
+    /// Returns the changes to apply to this layout to make it equal to `other`, see [HexLayoutPatch].
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let a: HexLayout<u8, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// assert!(a.diff(&a.clone()).is_empty());
+    ///
+    /// // Random edits, and a round-trip through the patch.
+    /// let mut b = a.clone();
+    /// let mut seed: u64 = 7;
+    /// for _ in 0..50 {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     let pos = HexPosition((seed >> 33) as isize % 5, (seed >> 45) as isize % 5);
+    ///     match (seed >> 20) % 3 {
+    ///         0 => {
+    ///             b.delete(pos);
+    ///         }
+    ///         value => {
+    ///             b.set(pos, value as u8);
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let patch = a.diff(&b);
+    /// assert!(!patch.is_empty());
+    ///
+    /// let mut synced = a.clone();
+    /// synced.apply_patch(&patch);
+    /// assert_eq!(synced, b);
+    /// ```
+    pub fn diff(&self, other: &Self) -> HexLayoutPatch<D, S> {
+        let mut changed: Vec<(HexPosition<S>, D)> = other
+            .0
+            .iter()
+            .filter(|(pos, data)| self.0.get(pos) != Some(*data))
+            .map(|(pos, data)| (*pos, data.clone()))
+            .collect();
+        let mut removed: Vec<HexPosition<S>> = self
+            .0
+            .keys()
+            .filter(|pos| !other.0.contains_key(pos))
+            .copied()
+            .collect();
+
+        changed.sort_by_key(|(pos, _)| (pos.0.to_isize(), pos.1.to_isize()));
+        removed.sort_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()));
+        HexLayoutPatch { changed, removed }
+    }
+
+    /// Applies a patch computed by [HexLayout::diff].
+    ///
+    /// The patch doesn't depend on the layout it was computed from: its positions are set or deleted
+    /// whatever their current value is (the last writer wins), and the other positions are left unchanged.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let server: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// let mut next = server.clone();
+    /// next.set(HexPosition(1, 0), 1);
+    /// next.delete(HexPosition(0, 1));
+    /// let patch = server.diff(&next);
+    ///
+    /// // The client changed its own copy in the meantime.
+    /// let mut client = server.clone();
+    /// client.set(HexPosition(1, 0), 2);
+    /// client.set(HexPosition(-1, 0), 2);
+    /// client.apply_patch(&patch);
+    ///
+    /// assert_eq!(client.get(HexPosition(1, 0)), Some(&1));
+    /// assert_eq!(client.get(HexPosition(0, 1)), None);
+    /// assert_eq!(client.get(HexPosition(-1, 0)), Some(&2));
+    /// ```
+    pub fn apply_patch(&mut self, patch: &HexLayoutPatch<D, S>) {
+        for pos in &patch.removed {
+            self.0.remove(pos);
+        }
+        for (pos, data) in &patch.changed {
+            self.0.insert(*pos, data.clone());
+        }
+    }
+
+    /// Smooths a layout of categories (like biomes) by replacing, `passes` times, the data of each position
+    /// with the most common data among the position and its neighbors.
+    ///
+    /// The neighbors that are not in the layout don't vote. On a tie, the position keeps its data if it is one
+    /// of the most common ones, otherwise it takes the first of them found around it (in the order of [HexDirection::iter]),
+    /// so the result is deterministic and a boundary between two equal groups doesn't oscillate.
+    /// Each pass is a step of [HexLayout::step_ca_into], with two buffers swapped between the passes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    /// enum Biome {
+    ///     #[default]
+    ///     Forest,
+    ///     Desert,
+    /// }
+    ///
+    /// // A single desert tile in a forest disappears.
+    /// let mut map: HexLayout<Biome, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(1, -1), Biome::Desert);
+    /// let smoothed = map.majority_smooth(1);
+    /// assert!(smoothed.data().all(|biome| *biome == Biome::Forest));
+    ///
+    /// // Two tiles voting for each other keep their own data.
+    /// let mut pair: HexLayout<Biome, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
+    /// pair.set(HexPosition(1, 0), Biome::Desert);
+    /// assert_eq!(pair.majority_smooth(5), pair);
+    ///
+    /// // A straight boundary between two halves is stable.
+    /// let mut halves: HexLayout<Biome, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// halves.fill_where(|pos, _| pos.0 >= 0, Biome::Desert);
+    /// for passes in 0..4 {
+    ///     assert_eq!(halves.majority_smooth(passes), halves);
+    /// }
+    /// ```
+    pub fn majority_smooth(&self, passes: usize) -> Self {
+        let mut current = self.clone();
+        let mut next = Self(HashMap::with_capacity_and_hasher(
+            self.0.len(),
+            H::default(),
+        ));
+
+        for _ in 0..passes {
+            current.step_ca_into(&mut next, |_, data, neighbors| {
+                let mut votes: Vec<(&D, usize)> = Vec::with_capacity(7);
+                for vote in std::iter::once(data).chain(neighbors) {
+                    match votes.iter_mut().find(|(value, _)| *value == vote) {
+                        Some((_, count)) => *count += 1,
+                        None => votes.push((vote, 1)),
+                    }
+                }
+                let best = votes.iter().map(|(_, count)| *count).max().unwrap_or(0);
+                votes
+                    .iter()
+                    .find(|(_, count)| *count == best)
+                    .map_or_else(|| data.clone(), |(value, _)| (*value).clone())
+            });
+            std::mem::swap(&mut current, &mut next);
+        }
+        current
+    }
+
+    /// Returns `true` if the layout is invariant under `symmetry`: every image of a position of the layout
+    /// is in the layout, with the same data. See [HexLayout::symmetrize].
+    pub fn is_symmetric(&self, symmetry: HexSymmetry<S>) -> bool {
+        self.0.iter().all(|(pos, data)| {
+            symmetry
+                .orbit(*pos)
+                .into_iter()
+                .all(|member| self.0.get(&member) == Some(data))
+        })
+    }
+}
+
+impl<D: Default + TileData, S: Number, H: BuildHasher + Default> HexLayout<D, S, H> {
+    /// Writes the layout to `writer`, with every position and its data.
+    ///
+    /// See [HexLayout::save_compact] for a smaller format when most positions hold the default data.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), 3);
+    ///
+    /// let mut bytes = Vec::new();
+    /// map.save(&mut bytes).unwrap();
+    ///
+    /// let loaded: HexLayout<u8, isize> = HexLayout::load(bytes.as_slice()).unwrap();
+    /// assert_eq!(loaded, map);
+    /// ```
+    pub fn save(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&[storage::PLAIN_FORMAT])?;
+        (self.0.len() as u64).write_to(&mut writer)?;
+        for (pos, data) in &self.0 {
+            storage::write_position(&mut writer, *pos)?;
+            data.write_to(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the layout to `writer`, without the data of the positions holding the default data.
+    ///
+    /// The positions are stored as two bit sets over the [bounding hex](HexLayout::bounding_hex)
+    /// (one for the positions in the layout, one for the ones holding non-default data), followed by the non-default data.
+    /// The missing positions of the layout stay missing when it is loaded.
+    /// Note that this format is large for layouts whose positions are few and far apart.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<f64, isize> = HexLayout::new_from_range(31, HexPosition(0, 0));
+    /// for pos in HexPosition(0, 0).spiral(30).step_by(10) {
+    ///     map.set(pos, 1.5);
+    /// }
+    /// // A few holes.
+    /// map.delete(HexPosition(3, -1));
+    /// map.delete(HexPosition(0, 0));
+    ///
+    /// let (mut plain, mut compact) = (Vec::new(), Vec::new());
+    /// map.save(&mut plain).unwrap();
+    /// map.save_compact(&mut compact).unwrap();
+    /// assert!(compact.len() * 10 < plain.len());
+    ///
+    /// let loaded: HexLayout<f64, isize> = HexLayout::load(compact.as_slice()).unwrap();
+    /// assert_eq!(loaded, map);
+    /// assert_eq!(loaded.get(HexPosition(3, -1)), None);
+    /// ```
+    pub fn save_compact(&self, mut writer: impl Write) -> io::Result<()>
+    where
+        D: PartialEq,
+    {
+        writer.write_all(&[storage::COMPACT_FORMAT])?;
+        (self.0.len() as u64).write_to(&mut writer)?;
+        let Some(bound) = self.bounding_hex() else {
+            return Ok(());
+        };
+        storage::write_position(&mut writer, bound.center())?;
+        (bound.radius() as u64).write_to(&mut writer)?;
+
+        let default = D::default();
+        let entries: Vec<Option<&D>> = bound
+            .iter(HexIndexing::RowMajor)
+            .map(|pos| self.0.get(&pos))
+            .collect();
+        let present: Vec<bool> = entries.iter().map(Option::is_some).collect();
+        let non_default: Vec<bool> = entries
+            .iter()
+            .map(|data| data.is_some_and(|data| *data != default))
+            .collect();
+        storage::write_bits(&mut writer, &present)?;
+        storage::write_bits(&mut writer, &non_default)?;
+
+        for data in entries.into_iter().flatten() {
+            if *data != default {
+                data.write_to(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a layout written by [HexLayout::save] or [HexLayout::save_compact].
+    ///
+    /// Returns an error with the [io::ErrorKind::InvalidData] kind if the data is not a saved layout.
+    ///
+    /// ## Examples
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, i32> = HexLayout::load([2, 0, 0, 0, 0, 0, 0, 0, 0].as_slice()).unwrap();
+    /// assert!(map.is_empty());
+    ///
+    /// assert!(HexLayout::<bool, i32>::load([9].as_slice()).is_err());
+    ///
+    /// // A compact layout of one position, in a bounding hex too large to exist.
+    /// let mut bytes = vec![2, 1, 0, 0, 0, 0, 0, 0, 0];
+    /// bytes.extend([0; 16]);
+    /// bytes.extend(u64::MAX.to_le_bytes());
+    /// let error = HexLayout::<bool, i32>::load(bytes.as_slice()).unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    ///
+    /// bytes.truncate(25);
+    /// bytes.extend((1_u64 << 33).to_le_bytes());
+    /// let error = HexLayout::<bool, isize>::load(bytes.as_slice()).unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    ///
+    /// // Or too large for the coordinates.
+    /// bytes.truncate(25);
+    /// bytes.extend(40_000_u64.to_le_bytes());
+    /// let error = HexLayout::<bool, i16>::load(bytes.as_slice()).unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    ///
+    /// // Or just larger than the data.
+    /// bytes.truncate(25);
+    /// bytes.extend(1000_u64.to_le_bytes());
+    /// let error = HexLayout::<bool, i32>::load(bytes.as_slice()).unwrap_err();
+    /// assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    /// ```
+    pub fn load(mut reader: impl Read) -> io::Result<Self> {
+        let format = u8::read_from(&mut reader)?;
+        let len = u64::read_from(&mut reader)? as usize;
+        let mut layout = Self(HashMap::default());
+
+        match format {
+            storage::PLAIN_FORMAT => {
+                for _ in 0..len {
+                    let pos = storage::read_position(&mut reader)?;
+                    layout.0.insert(pos, D::read_from(&mut reader)?);
+                }
+            }
+            storage::COMPACT_FORMAT if len == 0 => {}
+            storage::COMPACT_FORMAT => {
+                let bound: HexBound<S> = storage::read_bound(&mut reader, len)?;
+                let present = storage::read_bits(&mut reader, bound.len())?;
+                let non_default = storage::read_bits(&mut reader, bound.len())?;
+
+                for (i, pos) in bound.iter(HexIndexing::RowMajor).enumerate() {
+                    match (present[i], non_default[i]) {
+                        (true, true) => layout.0.insert(pos, D::read_from(&mut reader)?),
+                        (true, false) => layout.0.insert(pos, D::default()),
+                        (false, true) => {
+                            return Err(storage::invalid_data("data outside the layout"));
+                        }
+                        (false, false) => None,
+                    };
+                }
+            }
+            _ => return Err(storage::invalid_data("unknown layout format")),
+        }
+
+        if layout.len() != len {
+            return Err(storage::invalid_data("wrong number of positions"));
+        }
+        Ok(layout)
+    }
+}
+
+impl<S: Number, H: BuildHasher + Default + Clone> HexLayout<bool, S, H> {
+    /// Returns the number of blocked positions in the layout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), true);
+    /// map.set(HexPosition(0, 1), true);
+    ///
+    /// assert_eq!(map.blocked_count(), 2);
+    /// assert_eq!(map.open_count(), 5);
+    /// assert_eq!(map.open_ratio(), 5.0 / 7.0);
+    /// ```
+    pub fn blocked_count(&self) -> usize {
+        self.0.values().filter(|blocked| **blocked).count()
+    }
+
+    /// Returns the number of unblocked positions in the layout.
+    pub fn open_count(&self) -> usize {
+        self.0.len() - self.blocked_count()
+    }
+
+    /// Returns the proportion of unblocked positions in the layout, between `0.0` and `1.0`.
+    /// An empty layout has a ratio of `0.0`.
+    pub fn open_ratio(&self) -> f32 {
+        if self.0.is_empty() {
+            0.0
+        } else {
+            self.open_count() as f32 / self.0.len() as f32
+        }
+    }
+
+    /// Finds the shortest path between two positions on a hexagonal grid.
+    /// To use the `pathfinding` feature, the data associated with each position must be a [bool] in order to represent whether the position is blocked or not.
+    /// ``True`` means that the position is blocked, and ``False`` means that the position is not blocked.
+    ///
+    /// # Parameters
+    ///
+    /// - `from`: The starting position on the hexagonal grid. This position must be a `HexPosition<S>`.
+    /// - `to`: The target position on the hexagonal grid. This position must also be a `HexPosition<S>`.
+    ///
+    /// # Returns
+    ///
+    /// This function returns a [HexPath] representing the shortest path from `from` to `to`.
+    /// The path is returned in order from start to end. If `from` and `to` are the same, the vector will contain only `from`.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if either `from` or `to` are not present in the hexagonal grid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// map.set(HexPosition(-1, 1), true);
+    /// map.set(HexPosition(1, -1), true);
+    /// map.set(HexPosition(1, 0), true);
+    /// map.set(HexPosition(0, 1), true);
+    ///
+    /// let start = HexPosition(0, 0);
+    /// let goal = HexPosition(0, 2);
+    ///
+    /// let path = map.pathfinding(start, goal);
+    ///
+    /// for pos in &path {
+    ///     println!("Position: {:?}", pos);
+    /// }
+    /// assert_eq!(path, vec![HexPosition(0, 0), HexPosition(-1, 0), HexPosition(-2, 1), HexPosition(-2, 2), HexPosition(-1, 2), HexPosition(0, 2)]);
+    ///
+    /// // On a symmetric map, the same path is always chosen among the shortest ones,
+    /// // whatever the order the positions were inserted in.
+    /// let expected = vec![
+    ///     HexPosition(-2, 0),
+    ///     HexPosition(-1, 0),
+    ///     HexPosition(-1, 1),
+    ///     HexPosition(0, 1),
+    ///     HexPosition(1, 0),
+    ///     HexPosition(2, 0),
+    /// ];
+    /// let mut positions: Vec<_> = HexPosition(0, 0).spiral(3).collect();
+    /// for _ in 0..10 {
+    ///     positions.rotate_left(7);
+    ///     let symmetric: HexLayout<bool, isize> = positions
+    ///         .iter()
+    ///         .map(|pos| (*pos, *pos == HexPosition(0, 0)))
+    ///         .collect();
+    ///     assert_eq!(symmetric.pathfinding(HexPosition(-2, 0), HexPosition(2, 0)), expected);
+    /// }
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// This implementation uses the A* algorithm to guarantee finding the shortest path.
+    /// The heuristic used is tailored to hexagonal grids, where the axial distance is used to estimate the cost to the destination.
+    pub fn pathfinding(&self, from: HexPosition<S>, to: HexPosition<S>) -> HexPath<S> {
+        if from == to {
+            return HexPath(vec![from]);
+        }
+
+        if !self.0.contains_key(&from) || !self.0.contains_key(&to) {
+            panic!("Position not in layout");
+        }
+
+        HexPath(
+            algorithms::astar(
+                from,
+                to,
+                |pos| self.neighbors_unblocked(pos),
+                |_, _| 1,
+                |pos| pos.distance(to).to_isize() as u32,
+            )
+            .unwrap_or_else(|| vec![to]),
+        )
+    }
+
+    /// Same as [HexLayout::pathfinding], with [SearchOptions] to weight the heuristic or limit the search,
+    /// and statistics about the search (see [algorithms::astar_with_options]).
+    ///
+    /// Returns `None` as the path if `to` can't be reached, if the search was aborted, or if `from` or `to`
+    /// is not in the layout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{algorithms::SearchOptions, layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(8, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(0, -7), HexPosition(0, 4), true);
+    /// let (from, to) = (HexPosition(-5, 0), HexPosition(5, 0));
+    ///
+    /// let (path, stats) = map.pathfinding_with_options(from, to, SearchOptions::default());
+    /// assert_eq!(path, Some(map.pathfinding(from, to)));
+    ///
+    /// let fast = SearchOptions { heuristic_weight: 2.0, ..Default::default() };
+    /// let (_, fast_stats) = map.pathfinding_with_options(from, to, fast);
+    /// assert!(fast_stats.expanded < stats.expanded);
+    ///
+    /// let limited = SearchOptions { max_expansions: Some(5), ..Default::default() };
+    /// assert_eq!(map.pathfinding_with_options(from, to, limited).0, None);
+    /// ```
+    pub fn pathfinding_with_options(
+        &self,
+        from: HexPosition<S>,
+        to: HexPosition<S>,
+        options: SearchOptions,
+    ) -> (Option<HexPath<S>>, SearchStats) {
+        if !self.0.contains_key(&from) || !self.0.contains_key(&to) {
+            return (None, SearchStats::default());
+        }
+
+        let (path, stats) = algorithms::astar_with_options(
+            from,
+            to,
+            |pos| self.neighbors_unblocked(pos),
+            |_, _| 1,
+            |pos| pos.distance(to).to_isize() as u32,
+            options,
+        );
+        (path.map(HexPath), stats)
+    }
+
+    /// Finds the shortest path from `from` to any of the `goals`, with a single A* search.
+    ///
+    /// Returns `None` if no goal can be reached, for example if they are all blocked or not in the layout.
+    /// If `from` is one of the goals, the path only contains `from`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(-1, -1), HexPosition(-1, 2), true);
+    ///
+    /// // The closest exit as the crow flies is behind a wall.
+    /// let exits = HashSet::from([HexPosition(-2, 0), HexPosition(3, 0)]);
+    /// let path = map.pathfinding_to_any(HexPosition(0, 0), &exits).unwrap();
+    /// assert_eq!(path.last(), Some(&HexPosition(3, 0)));
+    /// assert_eq!(path.len(), 4);
+    ///
+    /// assert_eq!(map.pathfinding_to_any(HexPosition(0, 0), &HashSet::new()), None);
+    /// ```
+    pub fn pathfinding_to_any(
+        &self,
+        from: HexPosition<S>,
+        goals: &HashSet<HexPosition<S>>,
+    ) -> Option<HexPath<S>> {
+        if self.get(from).is_none() || goals.is_empty() {
+            return None;
+        }
+
+        algorithms::astar_to_any(
+            from,
+            |pos| goals.contains(&pos),
+            |pos| self.neighbors_unblocked(pos),
+            |_, _| 1,
+            |pos| {
+                goals
+                    .iter()
+                    .map(|goal| pos.distance(*goal).to_isize() as u32)
+                    .min()
+                    .unwrap_or_default()
+            },
+        )
+        .map(HexPath)
+    }
+
+    /// Finds the shortest path from `from` to any unblocked neighbor of `to`, to reach a blocked or occupied position.
+    ///
+    /// This is a single A* search to all the neighbors of `to`. If `from` is already next to `to`,
+    /// the path only contains `from`. Returns `None` if no neighbor of `to` can be reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// let enemy = HexPosition(2, 0);
+    /// map.set(enemy, true);
+    /// map.fill_line(HexPosition(1, -3), HexPosition(1, 1), true);
+    ///
+    /// let start = HexPosition(-1, 0);
+    /// let path = map.pathfinding_adjacent(start, enemy).unwrap();
+    /// let end = *path.last().unwrap();
+    /// assert_eq!(end.distance(enemy), 1);
+    ///
+    /// // The reached neighbor is the cheapest one.
+    /// let cheapest = map
+    ///     .neighbors_unblocked(enemy)
+    ///     .filter_map(|pos| map.path_cost(start, pos))
+    ///     .min();
+    /// assert_eq!(Some(path.cost() as u32), cheapest);
+    ///
+    /// let path = map.pathfinding_adjacent(HexPosition(2, 1), enemy).unwrap();
+    /// assert_eq!(path, vec![HexPosition(2, 1)]);
+    ///
+    /// // A surrounded enemy can't be reached.
+    /// map.fill_ring(enemy, 1, true);
+    /// assert_eq!(map.pathfinding_adjacent(start, enemy), None);
+    /// ```
+    pub fn pathfinding_adjacent(
+        &self,
+        from: HexPosition<S>,
+        to: HexPosition<S>,
+    ) -> Option<HexPath<S>> {
+        self.get(from)?;
+
+        let goals: HashSet<_> = self.neighbors_unblocked(to).collect();
+        algorithms::astar_to_any(
+            from,
+            |pos| goals.contains(&pos),
+            |pos| self.neighbors_unblocked(pos),
+            |_, _| 1,
+            |pos| (pos.distance(to).to_isize() as u32).saturating_sub(1),
+        )
+        .map(HexPath)
+    }
+
+    /// Returns the length of the shortest path between two positions, without building the path.
+    ///
+    /// Where a path exists, this is `self.pathfinding(from, to).len() - 1`.
+    /// Returns `None` if `to` can't be reached, or if `from` or `to` is not in the layout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(1, -3), HexPosition(1, 1), true);
+    ///
+    /// let (from, to) = (HexPosition(0, 0), HexPosition(3, -1));
+    /// assert_eq!(map.path_cost(from, to), Some(map.pathfinding(from, to).len() as u32 - 1));
+    ///
+    /// map.set(HexPosition(1, 2), true);
+    /// assert_eq!(map.path_cost(from, to), None);
+    /// ```
+    pub fn path_cost(&self, from: HexPosition<S>, to: HexPosition<S>) -> Option<u32> {
+        self.path_cost_weighted(from, to, |_, _| 1)
+    }
+
+    /// Returns the cost of the cheapest path between two positions, where `cost` gives the cost of a move
+    /// between two adjacent unblocked positions. Every move must cost at least 1.
+    ///
+    /// Returns `None` if `to` can't be reached, or if `from` or `to` is not in the layout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    ///
+    /// // Moving into the column q = 1 costs 5.
+    /// let cost = |_, to: HexPosition<isize>| if to.0 == 1 { 5 } else { 1 };
+    /// assert_eq!(map.path_cost_weighted(HexPosition(0, 0), HexPosition(2, 0), cost), Some(6));
+    /// ```
+    pub fn path_cost_weighted(
+        &self,
+        from: HexPosition<S>,
+        to: HexPosition<S>,
+        cost: impl Fn(HexPosition<S>, HexPosition<S>) -> u32,
+    ) -> Option<u32> {
+        if !self.0.contains_key(&from) || !self.0.contains_key(&to) {
+            return None;
+        }
+
+        algorithms::astar_cost(
+            from,
+            to,
+            |pos| self.neighbors_unblocked(pos),
+            cost,
+            |pos| pos.distance(to).to_isize() as u32,
+        )
+    }
+
+    /// Returns the lengths of the shortest paths from `from` to each of the `targets`, with a single search,
+    /// see [algorithms::dijkstra_costs].
+    ///
+    /// The element `i` of the returned vector is the length of the path to `targets[i]`,
+    /// or `None` if it can't be reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{HexPosition, layout::HexLayout};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(1, -4), HexPosition(1, 2), true);
+    /// map.set(HexPosition(-3, 0), true);
+    ///
+    /// let targets = [HexPosition(2, 0), HexPosition(-2, 0), HexPosition(-3, 0), HexPosition(0, 0)];
+    /// let costs = map.path_costs_to_many(HexPosition(0, 0), &targets);
+    ///
+    /// for (target, cost) in targets.iter().zip(&costs) {
+    ///     assert_eq!(*cost, map.path_cost(HexPosition(0, 0), *target));
+    /// }
+    /// assert_eq!(costs, vec![Some(7), Some(2), None, Some(0)]);
+    /// ```
+    pub fn path_costs_to_many(
+        &self,
+        from: HexPosition<S>,
+        targets: &[HexPosition<S>],
+    ) -> Vec<Option<u32>> {
+        if !self.0.contains_key(&from) {
+            return vec![None; targets.len()];
+        }
+        algorithms::dijkstra_costs(from, targets, |pos| self.neighbors_unblocked(pos), |_, _| 1)
+    }
+
+    /// Finds the shortest path between two positions for a unit covering several hexagons.
+    ///
+    /// The unit is described by a [HexShape] anchored on the positions of the path: a position can only be
+    /// used if every hexagon covered by the shape there is in the layout and not blocked.
+    /// The goal is reachable when the whole shape fits there.
+    ///
+    /// Returns `None` if the shape doesn't fit at `from` or `to`, or if `to` can't be reached.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayout, shape::HexShape, HexPosition};
+    ///
+    /// // A corridor, two hexagons wide, in the middle of walls.
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(10, HexPosition(0, 0));
+    /// for data in map.data_mut() {
+    ///     *data = true;
+    /// }
+    /// for q in -5..=5 {
+    ///     map.set(HexPosition(q, 0), false);
+    ///     map.set(HexPosition(q, 1), false);
+    /// }
+    ///
+    /// let (start, goal) = (HexPosition(-5, 0), HexPosition(5, 0));
+    ///
+    /// let path = map.pathfinding_shaped(start, goal, &HexShape::single()).unwrap();
+    /// assert_eq!(path.len(), 11);
+    /// assert_eq!(map.pathfinding_shaped(start, goal, &HexShape::hexagon(1)), None);
+    ///
+    /// // A line of three hexagons only fits along the corridor.
+    /// let line = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0)]);
+    /// let goal = HexPosition(3, 0);
+    /// assert!(map.pathfinding_shaped(start, goal, &line).is_some());
+    /// assert_eq!(map.pathfinding_shaped(start, goal, &line.rotated(1)), None);
+    /// ```
+    pub fn pathfinding_shaped(
+        &self,
+        from: HexPosition<S>,
+        to: HexPosition<S>,
+        shape: &HexShape<S>,
+    ) -> Option<HexPath<S>> {
+        let fits = |anchor: HexPosition<S>| {
+            shape
+                .translated_to(anchor)
+                .all(|pos| self.get(pos) == Some(&false))
+        };
+
+        if !fits(from) || !fits(to) {
+            return None;
+        }
+
+        algorithms::astar(
+            from,
+            to,
+            |pos| neighbors(pos).into_iter().filter(|next| fits(*next)),
+            |_, _| 1,
+            |pos| pos.distance(to).to_isize() as u32,
+        )
+        .map(HexPath)
+    }
+
+    /// Returns `true` if every position of the line from `from` to `to` (both included) is in the layout and not blocked.
+    ///
+    /// This is the same visibility rule as [HexLayout::field_of_view].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), true);
+    ///
+    /// assert!(map.has_line_of_sight(HexPosition(0, 0), HexPosition(0, 3)));
+    /// assert!(!map.has_line_of_sight(HexPosition(0, 0), HexPosition(3, 0)));
+    /// ```
+    pub fn has_line_of_sight(&self, from: HexPosition<S>, to: HexPosition<S>) -> bool {
+        from.line_to(to).all(|pos| self.get(pos) == Some(&false))
+    }
+
+    /// Removes the unnecessary waypoints of a path, so that it can be followed in straight lines.
+    ///
+    /// A waypoint is removed whenever there is a line of sight (see [HexLayout::has_line_of_sight])
+    /// between the previous kept waypoint and the next one. The first and last positions are always kept,
+    /// and there is a line of sight between every two consecutive waypoints of the result,
+    /// as long as there was one between every two consecutive positions of `path`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// let (start, goal) = (HexPosition(-3, 0), HexPosition(3, 0));
+    ///
+    /// // On an empty map, only the start and the goal are kept.
+    /// let path = map.pathfinding(start, goal);
+    /// assert_eq!(map.smooth_path(&path), vec![start, goal]);
+    ///
+    /// // With a wall in the way, a corner is kept to go around it.
+    /// map.set(HexPosition(0, 0), true);
+    /// let path = map.smooth_path(&map.pathfinding(start, goal));
+    /// assert_eq!(path.len(), 3);
+    /// assert!(path.windows(2).all(|pair| map.has_line_of_sight(pair[0], pair[1])));
+    /// ```
+    pub fn smooth_path(&self, path: &[HexPosition<S>]) -> Vec<HexPosition<S>> {
+        let (Some(first), Some(last)) = (path.first(), path.last()) else {
+            return Vec::new();
+        };
+        if path.len() == 1 {
+            return vec![*first];
+        }
+
+        let mut result = vec![*first];
+        for pair in path.windows(2).skip(1) {
+            let anchor = result[result.len() - 1];
+            if !self.has_line_of_sight(anchor, pair[1]) {
+                result.push(pair[0]);
+            }
+        }
+        result.push(*last);
+        result
+    }
+
+    /// Same as [HexLayout::pathfinding], with the path smoothed by [HexLayout::smooth_path].
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if either `from` or `to` are not present in the hexagonal grid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    ///
+    /// let path = map.pathfinding_smoothed(HexPosition(-2, -1), HexPosition(3, 1));
+    /// assert_eq!(path, vec![HexPosition(-2, -1), HexPosition(3, 1)]);
+    /// ```
+    pub fn pathfinding_smoothed(&self, from: HexPosition<S>, to: HexPosition<S>) -> HexPath<S> {
+        HexPath(self.smooth_path(&self.pathfinding(from, to)))
+    }
+
+    /// Calculates the positions visible from a given position on a hexagonal map.
+    ///
+    /// This function returns a set of positions that are visible from the `center` position.
+    /// Visibility is determined based on distance and obstacles. Obstacles are defined as positions
+    /// with an associated value of `true`.
+    ///
+    /// # Parameters
+    ///
+    /// - `center`: The starting position from which visibility is calculated.
+    /// - `range`: An optional parameter specifying the maximum visibility range. If `None`, there is no
+    ///   range limit.
+    ///
+    /// # Return Value
+    ///
+    /// Returns a `HashSet` containing the positions visible from `center`. A position is considered
+    /// visible if it is within the specified range (if a radius is provided) and if there are no obstacles
+    /// blocking the line of sight between `center` and the position.
+    ///
+    /// # Edge cases
+    ///
+    /// - With a range of `Some(0)`, the result is exactly `center` if it is visible, and is empty otherwise.
+    /// - The center itself is visible only if it is in the layout and not blocked. Otherwise nothing is visible,
+    ///   since every line of sight starts at the center: the result is empty, whatever the range.
+    /// - With a range of `None`, the range covers the whole layout, so a layout with a single open position
+    ///   only sees that position.
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// assert_eq!(map.field_of_view(HexPosition(0, 0), Some(0)), HashSet::from([HexPosition(0, 0)]));
+    ///
+    /// // A center outside of the layout sees nothing.
+    /// assert!(map.field_of_view(HexPosition(5, 0), None).is_empty());
+    /// assert!(map.field_of_view(HexPosition(5, 0), Some(0)).is_empty());
+    /// assert!(map.field_of_view(HexPosition(3, 0), Some(10)).is_empty());
+    ///
+    /// // Neither does a blocked center.
+    /// map.set(HexPosition(0, 0), true);
+    /// assert!(map.field_of_view(HexPosition(0, 0), Some(0)).is_empty());
+    /// assert!(map.field_of_view(HexPosition(0, 0), None).is_empty());
+    ///
+    /// // A layout with a single position.
+    /// let single: HexLayout<bool, isize> = HexLayout::new_from_range(1, HexPosition(2, 2));
+    /// assert_eq!(single.field_of_view(HexPosition(2, 2), None), HashSet::from([HexPosition(2, 2)]));
+    /// assert_eq!(single.field_of_view(HexPosition(2, 2), Some(5)), HashSet::from([HexPosition(2, 2)]));
+    /// assert!(single.field_of_view(HexPosition(0, 0), None).is_empty());
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map = HexLayout::new_from_range(6, HexPosition(0, 0));
+    ///
+    /// let start_pos = HexPosition::new(0, 0);
+    ///
+    /// map.set(HexPosition(0, 1), true);
+    /// map.set(HexPosition(1, 0), true);
+    /// map.set(HexPosition(-2, 0), true);
+    /// map.set(HexPosition(-2, 1), true);
+    /// map.set(HexPosition(0, -2), true);
+    ///
+    /// let reachable_positions = map.field_of_view(start_pos, None);
+    ///
+    /// let range = 2;
+    /// let reachable_positions_with_range = map.field_of_view(start_pos, Some(range));
+    ///
+    /// for pos in reachable_positions {
+    ///    println!("{}", pos);
+    /// }
+    ///
+    /// for pos in reachable_positions_with_range {
+    ///     println!("{}", pos);
+    /// }
+    /// ```
+    pub fn field_of_view(
+        &self,
+        center: HexPosition<S>,
+        range: Option<usize>,
+    ) -> HashSet<HexPosition<S>> {
+        self.field_of_view_where(
+            center,
+            range,
+            |pos| self.get(pos) == Some(&false),
+            |_, _| false,
+        )
+    }
+
+    /// Computes a field of view where `is_open` tells which positions of the layout can be seen through,
+    /// and `blocks_edge` which steps between two neighbors stop the line of sight.
+    ///
+    /// Like [HexLayout::field_of_view], a center that isn't open sees nothing.
+    fn field_of_view_where(
+        &self,
+        center: HexPosition<S>,
+        range: Option<usize>,
+        is_open: impl Fn(HexPosition<S>) -> bool,
+        blocks_edge: impl Fn(HexPosition<S>, HexPosition<S>) -> bool,
+    ) -> HashSet<HexPosition<S>> {
+        if !is_open(center) {
+            return HashSet::new();
+        }
+        let range = range.unwrap_or_else(|| self.covering_radius(center));
+
+        algorithms::fov_with_edges(
+            center,
+            range,
+            |pos| !is_open(pos),
+            |pos| self.0.contains_key(&pos),
+            blocks_edge,
+        )
+    }
+
+    /// Computes the set of positions reachable from a given starting position within a specified range.
+    ///
+    /// This function performs a breadth-first search to determine all the positions that can be reached from the
+    /// starting position `pos` within a movement range of `range` steps. It accounts for obstacles or blocked positions
+    /// using the `neighbors_unblocked` method, which provides a list of neighboring positions that are not blocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos` - The starting position from which to compute the field of move. It is of type `HexPosition<S>`.
+    /// * `range` - The maximum number of steps that can be taken from the starting position. It is of type `usize`.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `HashSet<HexPosition<S>>` containing all the positions reachable within the specified range, including
+    /// the starting position itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///
+    /// use hexing::HexPosition;
+    /// use hexing::layout::HexLayout;
+    ///
+    /// let mut map = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// let start_pos = HexPosition::new(0, 0);
+    /// let range = 2;
+    ///
+    /// let reachable_positions = map.field_of_move(start_pos, range);
+    /// assert_eq!(reachable_positions.len(), 19);
+    ///
+    /// map.set(HexPosition(0, 1), true);
+    /// map.set(HexPosition(1, 0), true);
+    /// map.set(HexPosition(0, -2), true);
+    ///
+    /// let reachable_positions = map.field_of_move(start_pos, range);
+    /// assert_eq!(reachable_positions.len(), 13);
+    /// ```
+    ///
+    /// # Notes
+    ///
+    /// The function uses a breadth-first approach, iterating level by level up to the specified range. It maintains
+    /// a set of visited positions to avoid reprocessing and ensure that each position is added only once. The number
+    /// of positions added at each level is managed to ensure the function scales efficiently with the size of the range.
+    ///
+    /// # Complexity
+    ///
+    /// The time complexity is O(n), where n is the number of positions within the specified range, assuming neighbor
+    /// checks are constant-time operations.
+    pub fn field_of_move(&self, pos: HexPosition<S>, range: usize) -> HashSet<HexPosition<S>> {
+        self.move_fringes(pos, range)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Computes the positions reachable from a given starting position, grouped by their exact number of steps.
+    ///
+    /// The element `k` of the returned vector contains the positions that can be reached in exactly `k` steps
+    /// (and not less), so the first element only contains `pos`. This is the breadth-first search behind
+    /// [HexLayout::field_of_move], keeping the grouping by distance.
+    ///
+    /// The search stops as soon as no new position can be reached, so the returned vector is truncated after
+    /// the last non-empty fringe and can be shorter than `range + 1`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::HexPosition;
+    /// use hexing::layout::HexLayout;
+    ///
+    /// let mut map = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// map.set(HexPosition(0, 1), true);
+    /// map.set(HexPosition(1, 0), true);
+    /// map.set(HexPosition(0, -2), true);
+    ///
+    /// let fringes = map.move_fringes(HexPosition(0, 0), 2);
+    /// assert_eq!(fringes[0], vec![HexPosition(0, 0)]);
+    /// assert_eq!(fringes[1].len(), 4);
+    ///
+    /// let total: usize = fringes.iter().map(Vec::len).sum();
+    /// assert_eq!(total, map.field_of_move(HexPosition(0, 0), 2).len());
+    ///
+    /// // The search stops early once the whole map has been explored.
+    /// assert_eq!(map.move_fringes(HexPosition(0, 0), 100).len(), 5);
+    /// ```
+    pub fn move_fringes(&self, pos: HexPosition<S>, range: usize) -> Vec<Vec<HexPosition<S>>> {
+        algorithms::bfs_fringes(pos, range, |pos| self.neighbors_unblocked(pos))
+    }
+
+    /// Returns the positions reachable from `start` in exactly `k` steps, and not less (see [HexLayout::move_fringes]).
+    ///
+    /// Because of the obstacles, a position can be in this set even if it is closer than `k` to `start`.
+    /// With `k = 0`, this is only `start`. Returns an empty set if `start` is not in the layout.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(1, -3), HexPosition(1, 2), true);
+    /// let start = HexPosition(0, 0);
+    ///
+    /// assert_eq!(map.reachable_at_exact(start, 0), HashSet::from([start]));
+    /// assert_eq!(map.reachable_at_exact(start, 1).len(), 4);
+    ///
+    /// // (2, 0) is 2 positions away, but the wall must be walked around.
+    /// assert_eq!(map.min_path_distance(start, HexPosition(2, 0)), Some(7));
+    /// assert!(map.reachable_at_exact(start, 7).contains(&HexPosition(2, 0)));
+    /// assert!(!map.reachable_at_exact(start, 2).contains(&HexPosition(2, 0)));
+    ///
+    /// for k in 0..10 {
+    ///     for pos in map.reachable_at_exact(start, k) {
+    ///         assert_eq!(map.min_path_distance(start, pos), Some(k as u32));
+    ///     }
+    /// }
+    /// assert!(map.reachable_at_exact(start, 20).is_empty());
+    /// assert!(map.reachable_at_exact(HexPosition(9, 0), 0).is_empty());
+    /// ```
+    pub fn reachable_at_exact(&self, start: HexPosition<S>, k: usize) -> HashSet<HexPosition<S>> {
+        if !self.0.contains_key(&start) {
+            return HashSet::new();
+        }
+        self.move_fringes(start, k)
+            .into_iter()
+            .nth(k)
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the number of steps of the shortest path from `start` to `pos`, like [HexLayout::path_cost].
+    ///
+    /// `pos` is in [HexLayout::reachable_at_exact] for `k` steps if and only if this is `Some(k)`.
+    /// Returns `None` if `pos` can't be reached, or if `start` or `pos` is not in the layout.
+    pub fn min_path_distance(&self, start: HexPosition<S>, pos: HexPosition<S>) -> Option<u32> {
+        self.path_cost(start, pos)
+    }
+
+    /// Same as [HexLayout::pathfinding], with temporary obstacles that don't modify the layout.
+    ///
+    /// Positions in `extra_blocked` are considered blocked and positions in `extra_open` are considered
+    /// not blocked, whatever their data in the layout (if a position is in both, it is blocked).
+    /// The overlays can't add positions to the layout: missing positions stay unreachable.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if either `from` or `to` are not present in the hexagonal grid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// let (start, goal) = (HexPosition(-2, 0), HexPosition(2, 0));
+    ///
+    /// // An enemy stands in the way.
+    /// let enemies = HashSet::from([HexPosition(0, 0)]);
+    /// let path = map.pathfinding_with_overlay(start, goal, &enemies, &HashSet::new());
+    /// assert_eq!(path.len(), 6);
+    /// assert!(!path.contains(&HexPosition(0, 0)));
+    ///
+    /// // Without the overlay, the path goes straight.
+    /// let path = map.pathfinding_with_overlay(start, goal, &HashSet::new(), &HashSet::new());
+    /// assert_eq!(path.len(), 5);
+    /// assert!(!map.get(HexPosition(0, 0)).unwrap());
+    /// ```
+    pub fn pathfinding_with_overlay(
+        &self,
+        from: HexPosition<S>,
+        to: HexPosition<S>,
+        extra_blocked: &HashSet<HexPosition<S>>,
+        extra_open: &HashSet<HexPosition<S>>,
+    ) -> HexPath<S> {
+        if from == to {
+            return HexPath(vec![from]);
+        }
+
+        if !self.0.contains_key(&from) || !self.0.contains_key(&to) {
+            panic!("Position not in layout");
+        }
+
+        algorithms::astar(
+            from,
+            to,
+            |pos| {
+                neighbors(pos)
+                    .into_iter()
+                    .filter(|next| self.is_open_with_overlay(*next, extra_blocked, extra_open))
+            },
+            |_, _| 1,
+            |pos| pos.distance(to).to_isize() as u32,
+        )
+        .map_or_else(|| HexPath(vec![to]), HexPath)
+    }
+
+    /// Same as [HexLayout::field_of_view], with temporary obstacles that don't modify the layout.
+    ///
+    /// See [HexLayout::pathfinding_with_overlay] for how the overlays are applied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), true);
+    ///
+    /// let smoke = HashSet::from([HexPosition(-1, 0)]);
+    /// let open_door = HashSet::from([HexPosition(1, 0)]);
+    /// let visible = map.field_of_view_with_overlay(HexPosition(0, 0), None, &smoke, &open_door);
+    ///
+    /// assert!(visible.contains(&HexPosition(2, 0)));
+    /// assert!(!visible.contains(&HexPosition(-2, 0)));
+    ///
+    /// // Like with `field_of_view`, a blocked center sees nothing, and the overlays decide whether it is blocked.
+    /// let at_center = HashSet::from([HexPosition(0, 0)]);
+    /// assert!(map.field_of_view_with_overlay(HexPosition(0, 0), None, &at_center, &HashSet::new()).is_empty());
+    /// assert!(map.field_of_view_with_overlay(HexPosition(0, 0), None, &at_center, &at_center).is_empty());
+    /// assert!(!map.field_of_view_with_overlay(HexPosition(1, 0), None, &HashSet::new(), &open_door).is_empty());
+    /// assert!(map.field_of_view_with_overlay(HexPosition(1, 0), None, &HashSet::new(), &HashSet::new()).is_empty());
+    /// ```
+    pub fn field_of_view_with_overlay(
+        &self,
+        center: HexPosition<S>,
+        range: Option<usize>,
+        extra_blocked: &HashSet<HexPosition<S>>,
+        extra_open: &HashSet<HexPosition<S>>,
+    ) -> HashSet<HexPosition<S>> {
+        self.field_of_view_where(
+            center,
+            range,
+            |pos| self.is_open_with_overlay(pos, extra_blocked, extra_open),
+            |_, _| false,
+        )
+    }
+
+    /// Same as [HexLayout::field_of_move], with temporary obstacles that don't modify the layout.
+    ///
+    /// See [HexLayout::pathfinding_with_overlay] for how the overlays are applied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    /// let enemies = HashSet::from([HexPosition(0, 1), HexPosition(1, 0), HexPosition(0, -2)]);
+    ///
+    /// let reachable = map.field_of_move_with_overlay(HexPosition(0, 0), 2, &enemies, &HashSet::new());
+    /// assert_eq!(reachable.len(), 13);
+    /// ```
+    pub fn field_of_move_with_overlay(
+        &self,
+        pos: HexPosition<S>,
+        range: usize,
+        extra_blocked: &HashSet<HexPosition<S>>,
+        extra_open: &HashSet<HexPosition<S>>,
+    ) -> HashSet<HexPosition<S>> {
+        algorithms::bfs_fringes(pos, range, |pos| {
+            neighbors(pos)
+                .into_iter()
+                .filter(|next| self.is_open_with_overlay(*next, extra_blocked, extra_open))
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Same as [HexLayout::pathfinding], but a step between two neighbors is also impossible
+    /// if there is a wall on their shared edge in `walls`.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if either `from` or `to` are not present in the hexagonal grid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{edge::{HexEdge, WallLayout}, layout::HexLayout, HexDirection, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(6, HexPosition(0, 0));
+    /// let center = HexPosition(0, 0);
+    ///
+    /// // A room of radius 1, enclosed by walls except for a doorway on its left.
+    /// let doorway = HexEdge::new(HexPosition(-1, 0), HexDirection::Left);
+    /// let mut walls = WallLayout::new();
+    /// for pos in center.spiral(1) {
+    ///     for direction in HexDirection::iter() {
+    ///         if (pos + direction.to_vector()).distance(center) == 2 {
+    ///             walls.set_wall(HexEdge::new(pos, direction), true);
+    ///         }
+    ///     }
+    /// }
+    /// walls.set_wall(doorway, false);
+    ///
+    /// let path = map.pathfinding_with_walls(HexPosition(4, 0), center, &walls);
+    /// assert!(path.windows(2).any(|step| step == [HexPosition(-2, 0), HexPosition(-1, 0)]));
+    /// assert!(path.windows(2).all(|step| !walls.blocks(step[0], step[1])));
+    ///
+    /// walls.set_wall(doorway, true);
+    /// assert_eq!(map.pathfinding_with_walls(HexPosition(4, 0), center, &walls), vec![center]);
+    /// ```
+    pub fn pathfinding_with_walls(
+        &self,
+        from: HexPosition<S>,
+        to: HexPosition<S>,
+        walls: &WallLayout<S>,
+    ) -> HexPath<S> {
+        if from == to {
+            return HexPath(vec![from]);
+        }
+
+        if !self.0.contains_key(&from) || !self.0.contains_key(&to) {
+            panic!("Position not in layout");
+        }
+
+        algorithms::astar(
+            from,
+            to,
+            |pos| self.neighbors_unwalled(pos, walls),
+            |_, _| 1,
+            |pos| pos.distance(to).to_isize() as u32,
+        )
+        .map_or_else(|| HexPath(vec![to]), HexPath)
+    }
+
+    /// Same as [HexLayout::field_of_move], but a step between two neighbors is also impossible
+    /// if there is a wall on their shared edge in `walls`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{edge::{HexEdge, WallLayout}, layout::HexLayout, HexDirection, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// let mut walls = WallLayout::new();
+    ///
+    /// // A blocked position and a wall, each closing one way.
+    /// map.set(HexPosition(1, 0), true);
+    /// walls.set_wall(HexEdge::new(HexPosition(0, 0), HexDirection::Left), true);
+    ///
+    /// let reachable = map.field_of_move_with_walls(HexPosition(0, 0), 1, &walls);
+    /// assert_eq!(reachable.len(), 5);
+    /// assert!(!reachable.contains(&HexPosition(1, 0)));
+    /// assert!(!reachable.contains(&HexPosition(-1, 0)));
+    /// ```
+    pub fn field_of_move_with_walls(
+        &self,
+        pos: HexPosition<S>,
+        range: usize,
+        walls: &WallLayout<S>,
+    ) -> HashSet<HexPosition<S>> {
+        algorithms::bfs_fringes(pos, range, |pos| self.neighbors_unwalled(pos, walls))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Same as [HexLayout::field_of_view], but the line of sight also stops at the walls of `walls`:
+    /// a position is hidden if the line from `center` to it crosses an edge with a wall.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{edge::{HexEdge, WallLayout}, layout::HexLayout, HexDirection, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// let mut walls = WallLayout::new();
+    /// walls.set_wall(HexEdge::new(HexPosition(0, 0), HexDirection::Right), true);
+    ///
+    /// let visible = map.field_of_view_with_walls(HexPosition(0, 0), None, &walls);
+    ///
+    /// // Both positions are open, but the wall between them hides the other side.
+    /// assert_eq!(map.get(HexPosition(1, 0)), Some(&false));
+    /// assert!(!visible.contains(&HexPosition(1, 0)));
+    /// assert!(!visible.contains(&HexPosition(2, 0)));
+    /// assert!(visible.contains(&HexPosition(-2, 0)));
+    /// assert!(visible.contains(&HexPosition(1, -1)));
+    ///
+    /// // Without walls, it sees the same positions as `field_of_view`, and nothing from a blocked or missing center.
+    /// let mut map = map;
+    /// map.set(HexPosition(-1, 1), true);
+    /// for center in [HexPosition(0, 0), HexPosition(2, -1), HexPosition(-1, 1), HexPosition(9, 0)] {
+    ///     assert_eq!(map.field_of_view_with_walls(center, None, &WallLayout::new()), map.field_of_view(center, None));
+    /// }
+    /// assert!(map.field_of_view_with_walls(HexPosition(-1, 1), None, &walls).is_empty());
+    /// ```
+    pub fn field_of_view_with_walls(
+        &self,
+        center: HexPosition<S>,
+        range: Option<usize>,
+        walls: &WallLayout<S>,
+    ) -> HashSet<HexPosition<S>> {
+        self.field_of_view_where(
+            center,
+            range,
+            |pos| self.get(pos) == Some(&false),
+            |from, to| walls.blocks(from, to),
+        )
+    }
+
+    /// Returns the neighbors that are not blocked and not separated from `pos` by a wall.
+    fn neighbors_unwalled(&self, pos: HexPosition<S>, walls: &WallLayout<S>) -> HexNeighbors<S> {
+        let mut result_neighbors = HexNeighbors::default();
+        for next in self.neighbors_unblocked(pos) {
+            if !walls.blocks(pos, next) {
+                result_neighbors.push(next);
+            }
+        }
+        result_neighbors
+    }
+
+    /// Returns `true` if the position is in the layout and not blocked, taking the overlays into account.
+    fn is_open_with_overlay(
+        &self,
+        pos: HexPosition<S>,
+        extra_blocked: &HashSet<HexPosition<S>>,
+        extra_open: &HashSet<HexPosition<S>>,
+    ) -> bool {
+        match self.get(pos) {
+            None => false,
+            Some(_) if extra_blocked.contains(&pos) => false,
+            Some(_) if extra_open.contains(&pos) => true,
+            Some(blocked) => !blocked,
+        }
+    }
+
+    /// Returns an iterator over all the neighbors that are not blocked.
+    ///
+    /// The neighbors are stored inline in the returned [HexNeighbors], so this doesn't allocate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+    ///
+    /// let neighbors = map.neighbors_unblocked(HexPosition(0, 0));
+    /// assert_eq!(neighbors.len(), 6);
+    ///
+    /// map.delete(HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), true);
+    ///
+    /// let neighbors = map.neighbors_unblocked(HexPosition(1, 1));
+    /// assert_eq!(neighbors.len(), 3); // edge of the map
+    /// assert!(!neighbors.as_slice().contains(&HexPosition(1, 0)));
+    /// ```
+    pub fn neighbors_unblocked(&self, pos: HexPosition<S>) -> HexNeighbors<S> {
+        self.neighbors_passable(pos, |blocked| *blocked)
+    }
+
+    /// Returns the `k` unblocked positions nearest to `from` by path length that match `predicate`, with their number of steps.
+    ///
+    /// The positions are explored with [HexLayout::bfs_iter], which stops once the `k` matches are found.
+    /// The result is sorted by number of steps, then by `q` and `r`.
+    /// It has fewer than `k` elements if there aren't enough reachable matches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(1, -3), HexPosition(1, 2), true);
+    ///
+    /// // (2, 0) is close, but behind a wall.
+    /// let targets = [HexPosition(2, 0), HexPosition(-2, 0), HexPosition(0, 3)];
+    /// let nearest = map.nearest_reachable_where(HexPosition(0, 0), 2, |pos| targets.contains(&pos));
+    /// assert_eq!(nearest, vec![(HexPosition(-2, 0), 2), (HexPosition(0, 3), 3)]);
+    /// ```
+    pub fn nearest_reachable_where(
+        &self,
+        from: HexPosition<S>,
+        k: usize,
+        predicate: impl Fn(HexPosition<S>) -> bool,
+    ) -> Vec<(HexPosition<S>, u32)> {
+        let mut matches: Vec<(HexPosition<S>, u32)> = Vec::new();
+        if k == 0 {
+            return matches;
+        }
+
+        for (pos, depth) in self.bfs_iter(from) {
+            // Keep the matches at the same depth as the k-th one, for the tie-breaking.
+            if matches.len() >= k && depth > matches[k - 1].1 {
+                break;
+            }
+            if predicate(pos) {
+                matches.push((pos, depth));
+            }
+        }
+
+        matches.sort_by_key(|(pos, depth)| (*depth, pos.0.to_isize(), pos.1.to_isize()));
+        matches.truncate(k);
+        matches
+    }
+
+    /// Returns a breadth-first iterator over the unblocked positions reachable from `start`.
+    ///
+    /// Each position is yielded once, with its number of steps from `start`, in nondecreasing order of steps.
+    /// The iterator is lazy: the search only goes as far as the iterator is consumed.
+    /// If `start` is blocked or not in the layout, the iterator is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    ///
+    /// // On an open map, the depth is the distance.
+    /// for (pos, depth) in map.bfs_iter(HexPosition(0, 0)) {
+    ///     assert_eq!(depth as isize, pos.distance(HexPosition(0, 0)));
+    /// }
+    /// assert_eq!(map.bfs_iter(HexPosition(0, 0)).count(), map.len());
+    ///
+    /// let mut blocked = map;
+    /// blocked.set(HexPosition(0, 0), true);
+    /// assert_eq!(blocked.bfs_iter(HexPosition(0, 0)).count(), 0);
+    /// ```
+    pub fn bfs_iter(&self, start: HexPosition<S>) -> HexBfsIter<'_, S, H> {
+        let mut queue = VecDeque::new();
+        let mut visited = HashSet::new();
+        if self.get(start) == Some(&false) {
+            queue.push_back((start, 0));
+            visited.insert(start);
+        }
+
+        HexBfsIter {
+            layout: self,
+            queue,
+            visited,
+        }
+    }
+
+    /// Returns a lazy iterator over the unblocked positions reachable from `start`, with their distance from `start`
+    /// in number of moves, in nondecreasing order of distance.
+    ///
+    /// This is [HexLayout::bfs_iter] with `usize` distances: unlike [HexLayout::field_of_move],
+    /// the search can be stopped early. If `start` is blocked or not in the layout, the iterator is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// map.fill_line(HexPosition(1, -3), HexPosition(1, 2), true);
+    ///
+    /// let mut bfs = map.bfs(HexPosition(0, 0));
+    /// assert_eq!(bfs.next(), Some((HexPosition(0, 0), 0)));
+    ///
+    /// let distances: Vec<usize> = bfs.map(|(_, distance)| distance).collect();
+    /// assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+    ///
+    /// // Stop at the first position on the other side of the wall.
+    /// let (pos, distance) = map.bfs(HexPosition(0, 0)).find(|(pos, _)| pos.0 > 1).unwrap();
+    /// assert_eq!(map.path_cost(HexPosition(0, 0), pos), Some(distance as u32));
+    /// ```
+    pub fn bfs(&self, start: HexPosition<S>) -> impl Iterator<Item = (HexPosition<S>, usize)> + '_ {
+        self.bfs_iter(start)
+            .map(|(pos, depth)| (pos, depth as usize))
+    }
+
+    /// Returns `true` if all the unblocked positions form a single connected region.
+    ///
+    /// A layout without unblocked positions is considered connected. This is useful to validate generated maps.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// assert!(map.is_connected());
+    ///
+    /// // A wall with a gap.
+    /// map.fill_line(HexPosition(1, -4), HexPosition(1, 2), true);
+    /// assert!(map.is_connected());
+    ///
+    /// // A full wall splits the map in two.
+    /// map.set(HexPosition(1, 3), true);
+    /// assert!(!map.is_connected());
+    ///
+    /// map.fill_where(|_, _| true, true);
+    /// assert!(map.is_connected());
+    /// ```
+    pub fn is_connected(&self) -> bool {
+        let Some(start) = self
+            .0
+            .iter()
+            .find(|(_, blocked)| !**blocked)
+            .map(|(pos, _)| *pos)
+        else {
+            return true;
+        };
+        self.bfs_iter(start).count() == self.open_count()
+    }
+
+    /// Returns a depth-first iterator over the unblocked positions reachable from `start`.
+    ///
+    /// Each position is yielded once, with its depth in the depth-first search tree (which is not the shortest number of steps).
+    /// The iterator is lazy: the search only goes as far as the iterator is consumed.
+    /// If `start` is blocked or not in the layout, the iterator is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(1, 0), true);
+    /// map.set(HexPosition(1, -1), true);
+    ///
+    /// let visited: Vec<_> = map.dfs_iter(HexPosition(0, 0)).map(|(pos, _)| pos).collect();
+    /// let unique: HashSet<_> = visited.iter().copied().collect();
+    ///
+    /// assert_eq!(visited[0], HexPosition(0, 0));
+    /// assert_eq!(visited.len(), unique.len());
+    /// assert_eq!(unique, map.field_of_move(HexPosition(0, 0), 10));
+    /// ```
+    pub fn dfs_iter(&self, start: HexPosition<S>) -> HexDfsIter<'_, S, H> {
+        let mut stack = Vec::new();
+        if self.get(start) == Some(&false) {
+            stack.push((start, 0));
+        }
+
+        HexDfsIter {
+            layout: self,
+            stack,
+            visited: HashSet::new(),
+        }
+    }
+
+    /// Returns the unblocked positions whose blocking would split the unblocked area they belong to in several parts
+    /// (the doorways and corridors of the map).
+    ///
+    /// The positions are found with the [Tarjan algorithm](https://en.wikipedia.org/wiki/Biconnected_component),
+    /// written without recursion so that it works on large maps.
+    /// Note that a dead-end position (with a single unblocked neighbor) is never an articulation point,
+    /// but its neighbor is, unless the dead end and its neighbor are the whole area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{layout::HexLayout, utils::HexBound, HexPosition};
+    ///
+    /// // Two rooms joined by a corridor.
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(9, HexPosition(0, 0));
+    /// map.fill_where(|_, _| true, true);
+    /// map.fill_bound(&HexBound::new(HexPosition(-5, 0), 2), false);
+    /// map.fill_bound(&HexBound::new(HexPosition(5, 0), 2), false);
+    /// map.fill_line(HexPosition(-2, 0), HexPosition(2, 0), false);
+    ///
+    /// // The corridor, and the two tiles of the rooms leading to it.
+    /// let expected: HashSet<_> = (-3..=3).map(|q| HexPosition(q, 0)).collect();
+    /// assert_eq!(map.articulation_points(), expected);
+    ///
+    /// let open: HexLayout<bool, isize> = HexLayout::new_from_range(6, HexPosition(0, 0));
+    /// assert!(open.articulation_points().is_empty());
+    /// ```
+    pub fn articulation_points(&self) -> HashSet<HexPosition<S>> {
+        self.biconnectivity().0
+    }
+
+    /// Returns the pairs of adjacent unblocked positions whose link is the only way between two parts of the unblocked area.
+    ///
+    /// Each pair is sorted by `q`, then by `r`, and so are the pairs themselves. See [HexLayout::articulation_points].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.fill_where(|pos, _| pos.1 != 0, true);
+    ///
+    /// let bridges = map.bridges();
+    /// assert_eq!(bridges.len(), 6);
+    /// assert_eq!(bridges[0], (HexPosition(-3, 0), HexPosition(-2, 0)));
+    ///
+    /// let open: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// assert!(open.bridges().is_empty());
+    /// ```
+    pub fn bridges(&self) -> Vec<(HexPosition<S>, HexPosition<S>)> {
+        let mut bridges = self.biconnectivity().1;
+        let key = |pos: HexPosition<S>| (pos.0.to_isize(), pos.1.to_isize());
+        for (a, b) in &mut bridges {
+            if key(*b) < key(*a) {
+                std::mem::swap(a, b);
+            }
+        }
+        bridges.sort_by_key(|(a, b)| (key(*a), key(*b)));
+        bridges
+    }
+
+    /// Computes the articulation points and the bridges of the unblocked positions, with an iterative depth-first search.
+    fn biconnectivity(&self) -> (HashSet<HexPosition<S>>, Vec<AdjacentPair<S>>) {
+        /// A position being explored by the depth-first search.
+        struct Frame<S: Number> {
+            /// The explored position.
+            pos: HexPosition<S>,
+
+            /// The position it was discovered from, `None` for the root of the search.
+            parent: Option<HexPosition<S>>,
+
+            /// The neighbors not checked yet.
+            neighbors: HexNeighbors<S>,
+
+            /// The number of positions discovered from it.
+            children: usize,
+        }
+
+        let mut discovery: HashMap<HexPosition<S>, u32, HexBuildHasher> = HashMap::default();
+        let mut low: HashMap<HexPosition<S>, u32, HexBuildHasher> = HashMap::default();
+        let mut points = HashSet::new();
+        let mut bridges = Vec::new();
+        let mut time = 0;
+
+        for (root, blocked) in &self.0 {
+            if *blocked || discovery.contains_key(root) {
+                continue;
+            }
+
+            discovery.insert(*root, time);
+            low.insert(*root, time);
+            time += 1;
+            let mut stack = vec![Frame {
+                pos: *root,
+                parent: None,
+                neighbors: self.neighbors_unblocked(*root),
+                children: 0,
+            }];
+
+            while let Some(frame) = stack.last_mut() {
+                let pos = frame.pos;
+                if let Some(next) = frame.neighbors.next() {
+                    if Some(next) == frame.parent {
+                        continue;
+                    }
+                    if let Some(next_discovery) = discovery.get(&next).copied() {
+                        let low_pos = low.entry(pos).or_default();
+                        *low_pos = std::cmp::min(*low_pos, next_discovery);
+                    } else {
+                        frame.children += 1;
+                        discovery.insert(next, time);
+                        low.insert(next, time);
+                        time += 1;
+                        stack.push(Frame {
+                            pos: next,
+                            parent: Some(pos),
+                            neighbors: self.neighbors_unblocked(next),
+                            children: 0,
+                        });
+                    }
+                    continue;
+                }
+
+                let children = frame.children;
+                stack.pop();
+                let low_pos = low.get(&pos).copied().unwrap_or_default();
+                match stack.last() {
+                    Some(parent) => {
+                        let parent_discovery =
+                            discovery.get(&parent.pos).copied().unwrap_or_default();
+                        let low_parent = low.entry(parent.pos).or_default();
+                        *low_parent = std::cmp::min(*low_parent, low_pos);
+
+                        if low_pos > parent_discovery {
+                            bridges.push((parent.pos, pos));
+                        }
+                        if parent.parent.is_some() && low_pos >= parent_discovery {
+                            points.insert(parent.pos);
+                        }
+                    }
+                    None if children >= 2 => {
+                        points.insert(pos);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        (points, bridges)
+    }
+
+    /// Returns the unblocked position farthest from `start` by number of moves, with its number of moves.
+    ///
+    /// All the positions reachable from `start` are explored with [HexLayout::bfs]. Among the farthest positions,
+    /// the one with the smallest `q`, then `r`, is returned. Returns `None` if `start` is blocked or not in the layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// // An L-shaped cave in the rock.
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(8, HexPosition(0, 0));
+    /// map.fill_where(|_, _| true, true);
+    /// map.fill_line(HexPosition(0, 0), HexPosition(4, 0), false);
+    /// map.fill_line(HexPosition(4, 0), HexPosition(4, 3), false);
+    ///
+    /// assert_eq!(map.farthest_from(HexPosition(0, 0)), Some((HexPosition(4, 3), 7)));
+    /// assert_eq!(map.farthest_from(HexPosition(4, 0)), Some((HexPosition(0, 0), 4)));
+    /// assert_eq!(map.farthest_from(HexPosition(1, 1)), None);
+    /// ```
+    pub fn farthest_from(&self, start: HexPosition<S>) -> Option<(HexPosition<S>, u32)> {
+        self.bfs(start)
+            .min_by_key(|(pos, distance)| (Reverse(*distance), pos.0.to_isize(), pos.1.to_isize()))
+            .map(|(pos, distance)| (pos, distance as u32))
+    }
+
+    /// Returns two unblocked positions far apart by number of moves, with their number of moves,
+    /// to estimate the diameter of the walkable regions.
+    ///
+    /// In each region of unblocked positions, a first search finds the farthest position from an arbitrary one,
+    /// then a second search finds the farthest position from it (see [HexLayout::farthest_from]).
+    /// The result is exact when the regions have no loop (like corridors or trees), and otherwise can be shorter
+    /// than the real diameter. The farthest pair among all the regions is returned, or `None` if no position is unblocked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(8, HexPosition(0, 0));
+    /// map.fill_where(|_, _| true, true);
+    /// assert_eq!(map.approx_diameter(), None);
+    ///
+    /// // A straight corridor: its diameter is its length.
+    /// map.fill_line(HexPosition(-5, 0), HexPosition(5, 0), false);
+    /// assert_eq!(map.approx_diameter(), Some((HexPosition(5, 0), HexPosition(-5, 0), 10)));
+    ///
+    /// // An L-shaped cave, separated from the corridor.
+    /// map.fill_line(HexPosition(-2, -3), HexPosition(4, -3), false);
+    /// map.fill_line(HexPosition(4, -3), HexPosition(4, -7), false);
+    /// let (a, b, length) = map.approx_diameter().unwrap();
+    /// assert_eq!(length, 10);
+    /// assert_eq!(map.path_cost(a, b), Some(10));
+    ///
+    /// map.fill_line(HexPosition(4, -6), HexPosition(4, -7), true);
+    /// assert_eq!(map.approx_diameter(), Some((HexPosition(5, 0), HexPosition(-5, 0), 10)));
+    /// map.fill_line(HexPosition(-5, 0), HexPosition(5, 0), true);
+    /// assert_eq!(map.approx_diameter(), Some((HexPosition(4, -5), HexPosition(-2, -3), 7)));
+    /// ```
+    pub fn approx_diameter(&self) -> Option<(HexPosition<S>, HexPosition<S>, u32)> {
+        let mut regions: Vec<HexPosition<S>> = self
+            .clusters_among(|blocked| !blocked, |_, _| true)
+            .into_iter()
+            .filter_map(|region| {
+                region
+                    .into_iter()
+                    .min_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()))
+            })
+            .collect();
+        regions.sort_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()));
+
+        regions
+            .into_iter()
+            .filter_map(|start| {
+                let (a, _) = self.farthest_from(start)?;
+                let (b, length) = self.farthest_from(a)?;
+                Some((a, b, length))
+            })
+            .rev()
+            .max_by_key(|(_, _, length)| *length)
+    }
+
+    /// Unblocks the positions along straight corridors connecting all the `rooms`, with the shortest network of corridors
+    /// (see [utils::minimum_spanning_tree_with_loops]). `extra_loops` is the proportion of the other possible corridors
+    /// that are also carved, to make loops.
+    ///
+    /// Only the positions already in the layout are unblocked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(10, HexPosition(0, 0));
+    /// map.fill_where(|_, _| true, true);
+    ///
+    /// let rooms = [HexPosition(-6, 0), HexPosition(0, 0), HexPosition(4, 3), HexPosition(2, -7)];
+    /// map.carve_corridors(&rooms, 0.0);
+    ///
+    /// for room in &rooms[1..] {
+    ///     assert!(map.path_cost(rooms[0], *room).is_some());
+    /// }
+    /// assert_eq!(map.open_count(), 21);
+    /// ```
+    pub fn carve_corridors(&mut self, rooms: &[HexPosition<S>], extra_loops: f32) {
+        for (a, b) in utils::minimum_spanning_tree_with_loops(rooms, extra_loops) {
+            for pos in rooms[a].line_to(rooms[b]) {
+                if let Some(blocked) = self.0.get_mut(&pos) {
+                    *blocked = false;
+                }
+            }
+        }
+    }
+
+    /// Dilates the `true` positions of the layout: every position next to a `true` position becomes `true`.
+    ///
+    /// Only positions already in the layout are changed, the layout never grows in size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// map.set(HexPosition(0, 0), true);
+    ///
+    /// map.grow();
+    /// assert_eq!(map.iter().filter(|(_, blocked)| **blocked).count(), 7);
+    ///
+    /// map.grow();
+    /// assert_eq!(map.iter().filter(|(_, blocked)| **blocked).count(), 19);
+    /// ```
+    pub fn grow(&mut self) {
+        for (pos, count) in self.neighbor_counts(|blocked| *blocked) {
+            if count > 0 {
+                self.0.insert(pos, true);
+            }
+        }
+    }
+
+    /// Erodes the `true` positions of the layout: every `true` position next to a position that isn't `true` becomes `false`.
+    ///
+    /// Positions outside the layout are not `true`, so the `true` positions on the border of the layout are cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// for pos in HexPosition(0, 0).spiral(1) {
+    ///     map.set(pos, true);
+    /// }
+    /// map.set(HexPosition(2, 0), true);
+    ///
+    /// map.shrink();
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&true));
+    /// assert_eq!(map.iter().filter(|(_, blocked)| **blocked).count(), 1);
+    ///
+    /// map.shrink();
+    /// assert_eq!(map.iter().filter(|(_, blocked)| **blocked).count(), 0);
+    /// ```
+    pub fn shrink(&mut self) {
+        for (pos, count) in self.neighbor_counts(|blocked| *blocked) {
+            if count < 6 {
+                self.0.insert(pos, false);
+            }
+        }
+    }
+}
+
+/// Two layouts are equal if they have the same positions, with equal data at each position.
+///
+/// ## Examples
+///
+/// ```rust
+/// use hexing::{layout::HexLayout, HexPosition};
+///
+/// let mut a: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+/// let mut b: HexLayout<u8, isize> = HexLayout::new_from_range(2, HexPosition(0, 0));
+/// a.set(HexPosition(1, 0), 3);
+/// b.set(HexPosition(1, 0), 3);
+/// assert_eq!(a, b);
+///
+/// b.set(HexPosition(0, 1), 3);
+/// assert_ne!(a, b);
+///
+/// // The order of insertion doesn't matter.
+/// let mut c: HexLayout<u8, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
+/// let mut d: HexLayout<u8, isize> = HexLayout::new_from_range(1, HexPosition(0, 0));
+/// for pos in HexPosition(0, 0).spiral(3) {
+///     c.set(pos, 1);
+/// }
+/// for pos in HexPosition(0, 0).spiral(3).collect::<Vec<_>>().into_iter().rev() {
+///     d.set(pos, 1);
+/// }
+/// assert_eq!(c, d);
+/// ```
+impl<D: PartialEq + Default, S: Number, H: BuildHasher> PartialEq for HexLayout<D, S, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<D: Eq + Default, S: Number, H: BuildHasher> Eq for HexLayout<D, S, H> {}
+
+impl<D: std::fmt::Debug + Default, S: Number, H> std::fmt::Debug for HexLayout<D, S, H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("HexLayout").field(&self.0).finish()
+    }
+}
+
+/// Creates a layout from positions and their data. If a position is given several times, the last data is kept.
+///
+/// ## Examples
+///
+/// ```rust
+/// use hexing::{layout::HexLayout, HexPosition};
+///
+/// let map: HexLayout<isize, i32> = HexPosition(0, 0).spiral(2).map(|pos| (pos, pos.distance(HexPosition(0, 0)) as isize)).collect();
+/// assert_eq!(map.len(), 19);
+/// assert_eq!(map.get(HexPosition(2, -1)), Some(&2));
+/// ```
+impl<D: Default, S: Number, H: BuildHasher + Default> FromIterator<(HexPosition<S>, D)>
+    for HexLayout<D, S, H>
+{
+    fn from_iter<I: IntoIterator<Item = (HexPosition<S>, D)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<D: Default, S: Number, H, G: BuildHasher + Default> From<HashMap<HexPosition<S>, D, H>>
+    for HexLayout<D, S, G>
+{
+    fn from(map: HashMap<HexPosition<S>, D, H>) -> Self {
+        Self(map.into_iter().collect())
+    }
+}
+
+/// The changes between two [HexLayout]s, computed by [HexLayout::diff] and applied by [HexLayout::apply_patch].
+///
+/// Only the positions that changed are stored, with their new data, so a patch is much smaller
+/// than the whole layout when sent over the network. The positions are sorted by `q`, then by `r`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HexLayoutPatch<D, S: Number> {
+    /// The positions added or changed, with their new data.
+    changed: Vec<(HexPosition<S>, D)>,
+
+    /// The positions removed.
+    removed: Vec<HexPosition<S>>,
+}
+
+impl<D, S: Number> HexLayoutPatch<D, S> {
+    /// Returns the positions added or changed by the patch, with their new data.
+    pub fn changed(&self) -> &[(HexPosition<S>, D)] {
+        &self.changed
+    }
+
+    /// Returns the positions removed by the patch.
+    pub fn removed(&self) -> &[HexPosition<S>] {
+        &self.removed
+    }
+
+    /// Returns `true` if the patch doesn't change anything.
+    pub const fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Two adjacent positions.
+type AdjacentPair<S> = (HexPosition<S>, HexPosition<S>);
+
+/// An iterator over the data of the neighbors of a position that are in a [HexLayout], see [HexLayout::step_ca].
+#[derive(Debug, Clone)]
+pub struct NeighborIter<'a, D: Default, S: Number, H = HexBuildHasher> {
+    /// The layout of the neighbors.
+    layout: &'a HexLayout<D, S, H>,
+
+    /// The position whose neighbors are yielded.
+    center: HexPosition<S>,
+
+    /// The index, in [HexDirection::iter], of the next direction to look at.
+    index: usize,
+}
+
+impl<'a, D: Default, S: Number, H: BuildHasher + Default> Iterator for NeighborIter<'a, D, S, H> {
+    type Item = &'a D;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(direction) = HexDirection::iter().get(self.index) {
+            self.index += 1;
+            if let Some(data) = self.layout.get(self.center + direction.to_vector()) {
+                return Some(data);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(6 - self.index))
+    }
+}
+
+/// A breadth-first iterator over the unblocked positions of a [HexLayout], see [HexLayout::bfs_iter].
+pub struct HexBfsIter<'a, S: Number, H = HexBuildHasher> {
+    /// The layout being explored.
+    layout: &'a HexLayout<bool, S, H>,
+
+    /// The positions waiting to be yielded, with their depth.
+    queue: VecDeque<(HexPosition<S>, u32)>,
+
+    /// The positions already discovered.
+    visited: HashSet<HexPosition<S>>,
+}
+
+impl<S: Number, H: BuildHasher + Default + Clone> Iterator for HexBfsIter<'_, S, H> {
+    type Item = (HexPosition<S>, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (current, depth) = self.queue.pop_front()?;
+        for neighbor in self.layout.neighbors_unblocked(current) {
+            if self.visited.insert(neighbor) {
+                self.queue.push_back((neighbor, depth + 1));
+            }
+        }
+        Some((current, depth))
+    }
+}
+
+/// A depth-first iterator over the unblocked positions of a [HexLayout], see [HexLayout::dfs_iter].
+pub struct HexDfsIter<'a, S: Number, H = HexBuildHasher> {
+    /// The layout being explored.
+    layout: &'a HexLayout<bool, S, H>,
+
+    /// The positions waiting to be explored, with their depth.
+    stack: Vec<(HexPosition<S>, u32)>,
+
+    /// The positions already yielded.
+    visited: HashSet<HexPosition<S>>,
+}
+
+impl<S: Number, H: BuildHasher + Default + Clone> Iterator for HexDfsIter<'_, S, H> {
+    type Item = (HexPosition<S>, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((current, depth)) = self.stack.pop() {
+            if !self.visited.insert(current) {
+                continue;
+            }
+            for neighbor in self.layout.neighbors_unblocked(current) {
+                if !self.visited.contains(&neighbor) {
+                    self.stack.push((neighbor, depth + 1));
+                }
+            }
+            return Some((current, depth));
+        }
+        None
+    }
+}
+
+/// A list of up to six neighbors, stored inline to avoid allocating, see [HexLayout::neighbors_unblocked].
+///
+/// It is an iterator over the neighbors; [HexNeighbors::as_slice] gives access to the remaining ones.
+#[derive(Debug, Clone)]
+pub struct HexNeighbors<S: Number> {
+    /// The neighbors, only the `len` first ones are set.
+    positions: [HexPosition<S>; 6],
+
+    /// The number of neighbors.
+    len: usize,
+
+    /// The index of the next neighbor to yield.
+    index: usize,
+}
+
+impl<S: Number> Default for HexNeighbors<S> {
+    fn default() -> Self {
+        Self {
+            positions: [HexPosition::ORIGIN; 6],
+            len: 0,
+            index: 0,
+        }
+    }
+}
+
+impl<S: Number> HexNeighbors<S> {
+    /// Adds a neighbor at the end of the list.
+    pub(crate) const fn push(&mut self, pos: HexPosition<S>) {
+        self.positions[self.len] = pos;
+        self.len += 1;
+    }
+
+    /// Returns the neighbors that haven't been yielded yet.
+    pub fn as_slice(&self) -> &[HexPosition<S>] {
+        &self.positions[self.index..self.len]
+    }
+}
+
+impl<S: Number> Iterator for HexNeighbors<S> {
+    type Item = HexPosition<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        self.index += 1;
+        Some(self.positions[self.index - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<S: Number> ExactSizeIterator for HexNeighbors<S> {}
+
+/// A path on the grid, as returned by [HexLayout::pathfinding] and the other pathfinding functions.
+///
+/// Each position is a neighbor of the previous one, except in the paths of [HexLayout::pathfinding_smoothed],
+/// where each position is a waypoint in line of sight of the previous one.
+/// It dereferences to a slice of positions, and compares equal to a vector of the same positions.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{layout::HexLayout, HexDirection, HexPosition};
+///
+/// let map: HexLayout<bool, isize> = HexLayout::new_from_range(4, HexPosition(0, 0));
+/// let mut path = map.pathfinding(HexPosition(0, 0), HexPosition(3, 0));
+///
+/// assert_eq!(path.len(), 4);
+/// assert_eq!(path.cost(), 3);
+/// assert!(path.contains(&HexPosition(2, 0)));
+/// assert_eq!(path.directions(), vec![HexDirection::Right; 3]);
+///
+/// // Only one move is allowed this turn.
+/// path.truncate_to(1);
+/// assert_eq!(path, vec![HexPosition(0, 0), HexPosition(1, 0)]);
+/// assert_eq!(path.last(), Some(&HexPosition(1, 0)));
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexPath<S: Number>(pub(crate) Vec<HexPosition<S>>);
+
+impl<S: Number> HexPath<S> {
+    /// Returns the number of positions of the path, including its start.
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the path has no position.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of moves of the path: the sum of the distances between its consecutive positions.
+    ///
+    /// This is one less than its number of positions when they are neighbors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{layout::HexLayout, HexPosition};
+    ///
+    /// let map: HexLayout<bool, isize> = HexLayout::new_from_range(5, HexPosition(0, 0));
+    /// let path = map.pathfinding_smoothed(HexPosition(-2, -1), HexPosition(3, 1));
+    ///
+    /// assert_eq!(path.len(), 2);
+    /// assert_eq!(path.cost(), map.pathfinding(HexPosition(-2, -1), HexPosition(3, 1)).cost());
+    /// ```
+    pub fn cost(&self) -> usize {
+        self.0
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]).to_isize() as usize)
+            .sum()
+    }
+
+    /// Returns the direction of each move of the path,
+    /// or an empty vector if two consecutive positions are not neighbors.
+    pub fn directions(&self) -> Vec<HexDirection> {
+        utils::path_directions(&self.0).unwrap_or_default()
+    }
+
+    /// Shortens the path to its first `steps` moves, for example to follow it with a limited move budget.
+    /// Nothing changes if the path is already shorter.
+    pub fn truncate_to(&mut self, steps: usize) {
+        self.0.truncate(steps + 1);
+    }
+
+    /// Returns the positions of the path.
+    pub fn into_inner(self) -> Vec<HexPosition<S>> {
+        self.0
+    }
+}
+
+impl<S: Number> std::ops::Deref for HexPath<S> {
+    type Target = [HexPosition<S>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S: Number> PartialEq<Vec<HexPosition<S>>> for HexPath<S> {
+    fn eq(&self, other: &Vec<HexPosition<S>>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<S: Number> From<HexPath<S>> for Vec<HexPosition<S>> {
+    fn from(path: HexPath<S>) -> Self {
+        path.0
+    }
+}
+
+impl<S: Number> IntoIterator for HexPath<S> {
+    type Item = HexPosition<S>;
+    type IntoIter = std::vec::IntoIter<HexPosition<S>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, S: Number> IntoIterator for &'a HexPath<S> {
+    type Item = &'a HexPosition<S>;
+    type IntoIter = std::slice::Iter<'a, HexPosition<S>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+/// A pathfinder that keeps its search state between calls, to find paths quickly on a changing [HexLayout].
+///
+/// It implements the [Lifelong Planning A*](https://en.wikipedia.org/wiki/Lifelong_Planning_A*) algorithm:
+/// when a few positions are blocked or unblocked with [DynamicPathfinder::update_blocked],
+/// [DynamicPathfinder::replan] only repairs the part of the search affected by the changes
+/// instead of starting over. The returned paths always have the same length as the ones found by a new search.
+///
+/// # Example
+///
+/// ```rust
+/// use hexing::{algorithms::astar, layout::{DynamicPathfinder, HexLayout}, HexPosition};
+///
+/// let map: HexLayout<bool, isize> = HexLayout::new_from_range(6, HexPosition(0, 0));
+/// let (start, goal) = (HexPosition(-5, 0), HexPosition(5, 0));
+///
+/// let mut pathfinder = DynamicPathfinder::new(map, start, goal);
+/// assert_eq!(pathfinder.replan().unwrap().len(), 11);
+///
+/// // Randomly block and unblock positions all over the map, and compare with a new search each time.
+/// let mut positions: Vec<_> = pathfinder.layout().positions().copied().collect();
+/// positions.sort_by_key(|pos| (pos.0, pos.1));
+/// let mut seed: u64 = 42;
+/// for _ in 0..200 {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     let pos = positions[(seed >> 33) as usize % positions.len()];
+///     let blocked = (seed >> 20) % 3 == 0;
+///     pathfinder.update_blocked(pos, blocked);
+///
+///     let path = pathfinder.replan();
+///     let layout = pathfinder.layout();
+///     let expected = astar(
+///         start,
+///         goal,
+///         |pos| layout.neighbors_unblocked(pos),
+///         |_, _| 1,
+///         |pos| pos.distance(goal) as u32,
+///     );
+///
+///     assert_eq!(path.as_ref().map(|path| path.len()), expected.map(|path| path.len()));
+///     if let Some(path) = path {
+///         assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+///         assert!(path[1..].iter().all(|pos| layout.get(*pos) == Some(&false)));
+///     }
+/// }
+/// ```
+pub struct DynamicPathfinder<S: Number> {
+    /// The layout the paths are searched on.
+    layout: HexLayout<bool, S>,
+
+    /// The position the paths start from.
+    start: HexPosition<S>,
+
+    /// The position the paths go to.
+    goal: HexPosition<S>,
+
+    /// The current cost estimations from `start`, missing positions have an infinite cost.
+    g: HashMap<HexPosition<S>, u32, HexBuildHasher>,
+
+    /// The one-step lookahead costs from `start`, missing positions have an infinite cost.
+    rhs: HashMap<HexPosition<S>, u32, HexBuildHasher>,
+
+    /// The positions whose `g` and `rhs` costs differ, by ascending key.
+    queue: PriorityQueue<HexPosition<S>, Reverse<(u32, u32)>, HexBuildHasher>,
+}
+
+impl<S: Number> DynamicPathfinder<S> {
+    /// Creates a new pathfinder on `layout`, searching paths from `start` to `goal`.
+    ///
+    /// The first call to [DynamicPathfinder::replan] does a complete search.
+    pub fn new(layout: HexLayout<bool, S>, start: HexPosition<S>, goal: HexPosition<S>) -> Self {
+        let mut pathfinder = Self {
+            layout,
+            start,
+            goal,
+            g: HashMap::default(),
+            rhs: HashMap::default(),
+            queue: PriorityQueue::with_default_hasher(),
+        };
+        pathfinder.rhs.insert(start, 0);
+        let key = pathfinder.key(start);
+        pathfinder.queue.push(start, Reverse(key));
+        pathfinder
+    }
+
+    /// Returns the layout the paths are searched on.
+    pub const fn layout(&self) -> &HexLayout<bool, S> {
+        &self.layout
+    }
+
+    /// Sets whether the position is blocked. The position is added to the layout if it wasn't in it.
+    ///
+    /// The change is taken into account by the next call to [DynamicPathfinder::replan].
+    pub fn update_blocked(&mut self, pos: HexPosition<S>, blocked: bool) {
+        if self.layout.set(pos, blocked) == Some(blocked) {
+            return;
+        }
+
+        self.update_vertex(pos);
+        for neighbor in neighbors(pos) {
+            self.update_vertex(neighbor);
+        }
+    }
+
+    /// Returns the shortest path from the start to the goal (both included), or `None` if the goal can't be reached.
+    ///
+    /// Like [HexLayout::pathfinding], the path goes through unblocked positions of the layout only,
+    /// except for the start position.
+    pub fn replan(&mut self) -> Option<HexPath<S>> {
+        if self.start == self.goal {
+            return Some(HexPath(vec![self.start]));
+        }
+
+        self.compute_shortest_path();
+
+        let mut current = self.goal;
+        let mut cost = self.g(current);
+        if cost == u32::MAX {
+            return None;
+        }
+
+        let mut path = vec![current];
+        while current != self.start {
+            current = neighbors(current)
+                .into_iter()
+                .find(|neighbor| self.g(*neighbor) == cost - 1)?;
+            cost -= 1;
+            path.push(current);
+        }
+        path.reverse();
+        Some(HexPath(path))
+    }
+
+    /// Processes the inconsistent positions until the cost of the goal is known.
+    fn compute_shortest_path(&mut self) {
+        while let Some((current, Reverse(key))) = self.queue.peek().map(|(pos, key)| (*pos, *key)) {
+            if key >= self.key(self.goal) && self.rhs(self.goal) == self.g(self.goal) {
+                break;
+            }
+            self.queue.pop();
+
+            if self.g(current) > self.rhs(current) {
+                self.g.insert(current, self.rhs(current));
+            } else {
+                self.g.remove(&current);
+                self.update_vertex(current);
+            }
+
+            for neighbor in neighbors(current) {
+                self.update_vertex(neighbor);
+            }
+        }
+    }
+
+    /// Recomputes the lookahead cost of a position, and queues it if it is inconsistent.
+    fn update_vertex(&mut self, pos: HexPosition<S>) {
+        if pos != self.start {
+            let rhs = if self.layout.get(pos) == Some(&false) {
+                neighbors(pos)
+                    .into_iter()
+                    .map(|neighbor| self.g(neighbor).saturating_add(1))
+                    .min()
+                    .unwrap_or(u32::MAX)
+            } else {
+                u32::MAX
+            };
+            self.rhs.insert(pos, rhs);
+        }
+
+        self.queue.remove(&pos);
+        if self.g(pos) != self.rhs(pos) {
+            let key = self.key(pos);
+            self.queue.push(pos, Reverse(key));
+        }
+    }
+
+    /// Returns the priority key of a position.
+    fn key(&self, pos: HexPosition<S>) -> (u32, u32) {
+        let cost = std::cmp::min(self.g(pos), self.rhs(pos));
+        (
+            cost.saturating_add(pos.distance(self.goal).to_isize() as u32),
+            cost,
+        )
+    }
+
+    /// Returns the current cost estimation of a position.
+    fn g(&self, pos: HexPosition<S>) -> u32 {
+        self.g.get(&pos).copied().unwrap_or(u32::MAX)
+    }
+
+    /// Returns the lookahead cost of a position.
+    fn rhs(&self, pos: HexPosition<S>) -> u32 {
+        self.rhs.get(&pos).copied().unwrap_or(u32::MAX)
+    }
+}
+
+/// A cache of the field of view of a [HexLayout], to update it quickly when a few positions change.
+///
+/// The cache remembers, for each position, which visible positions are behind it. So when a single position
+/// is blocked or unblocked with [FovCache::update_blocked], only the positions behind it are checked again,
+/// instead of the whole field of view. The result is always the same as [HexLayout::field_of_view].
+///
+/// # Example
+///
+/// ```rust
+/// use hexing::{layout::{FovCache, HexLayout}, HexPosition};
+///
+/// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(8, HexPosition(0, 0));
+/// let center = HexPosition(1, -1);
+///
+/// let mut cache = FovCache::new(center, Some(6));
+/// assert_eq!(cache.visible(&map), &map.field_of_view(center, Some(6)));
+///
+/// // Randomly toggle positions, and compare with a new field of view each time.
+/// let mut seed: u64 = 7;
+/// for _ in 0..200 {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     let pos = HexPosition((seed >> 33) as isize % 8, (seed >> 45) as isize % 8 - 4);
+///     let blocked = (seed >> 20) % 3 != 0;
+///
+///     let visible = cache.update_blocked(&mut map, pos, blocked).clone();
+///     assert_eq!(visible, map.field_of_view(center, Some(6)));
+/// }
+///
+/// // After changing the layout directly, the cache must be invalidated.
+/// map.set(HexPosition(2, -1), true);
+/// cache.invalidate();
+/// assert_eq!(cache.visible(&map), &map.field_of_view(center, Some(6)));
+/// ```
+pub struct FovCache<S: Number> {
+    /// The position the field of view is computed from.
+    center: HexPosition<S>,
+
+    /// The range of the field of view, `None` for no limit.
+    range: Option<usize>,
+
+    /// The positions visible from `center`.
+    visible: HashSet<HexPosition<S>>,
+
+    /// For each position, the positions whose line from `center` goes through it.
+    dependents: HashMap<HexPosition<S>, Vec<HexPosition<S>>, HexBuildHasher>,
+
+    /// Whether the whole field of view must be computed again.
+    dirty: bool,
+}
+
+impl<S: Number> FovCache<S> {
+    /// Creates a new cache of the field of view from `center` within `range` (see [HexLayout::field_of_view]).
+    ///
+    /// The field of view is computed on the first call to [FovCache::visible] or [FovCache::update_blocked].
+    pub fn new(center: HexPosition<S>, range: Option<usize>) -> Self {
+        Self {
+            center,
+            range,
+            visible: HashSet::new(),
+            dependents: HashMap::default(),
+            dirty: true,
+        }
+    }
+
+    /// Returns the positions visible from the center, computing them again if the cache was invalidated.
+    pub fn visible(&mut self, layout: &HexLayout<bool, S>) -> &HashSet<HexPosition<S>> {
+        if self.dirty {
+            self.recompute(layout);
+        }
+        &self.visible
+    }
+
+    /// Returns the positions visible from `center` within `range`, computing them again only if the center or the range
+    /// changed since the last call, or if the cache was invalidated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::{FovCache, HexLayout}, HexPosition};
+    ///
+    /// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(6, HexPosition(0, 0));
+    /// let mut cache = FovCache::new(HexPosition(0, 0), None);
+    /// let visible = cache.get(&map, HexPosition(0, 0), Some(3)).clone();
+    /// assert_eq!(visible, map.field_of_view(HexPosition(0, 0), Some(3)));
+    ///
+    /// // Without invalidation, a cache hit returns the same set without computing it again,
+    /// // even though the layout changed.
+    /// map.set(HexPosition(1, 0), true);
+    /// assert_eq!(cache.get(&map, HexPosition(0, 0), Some(3)), &visible);
+    ///
+    /// cache.invalidate();
+    /// assert_eq!(cache.get(&map, HexPosition(0, 0), Some(3)), &map.field_of_view(HexPosition(0, 0), Some(3)));
+    ///
+    /// // A new center or range is always computed.
+    /// assert_eq!(cache.get(&map, HexPosition(-1, 0), Some(3)), &map.field_of_view(HexPosition(-1, 0), Some(3)));
+    /// assert_eq!(cache.get(&map, HexPosition(-1, 0), None), &map.field_of_view(HexPosition(-1, 0), None));
+    /// ```
+    pub fn get(
+        &mut self,
+        layout: &HexLayout<bool, S>,
+        center: HexPosition<S>,
+        range: Option<usize>,
+    ) -> &HashSet<HexPosition<S>> {
+        if self.center != center || self.range != range {
+            self.center = center;
+            self.range = range;
+            self.dirty = true;
+        }
+        self.visible(layout)
+    }
+
+    /// Sets whether a position of the layout is blocked, and updates the positions visible from the center.
+    ///
+    /// Only the positions behind `pos` are checked again. If `pos` wasn't in the layout,
+    /// the size of the layout changes, so the whole field of view is computed again.
+    pub fn update_blocked(
+        &mut self,
+        layout: &mut HexLayout<bool, S>,
+        pos: HexPosition<S>,
+        blocked: bool,
+    ) -> &HashSet<HexPosition<S>> {
+        match layout.set(pos, blocked) {
+            None => self.dirty = true,
+            Some(previous) if previous == blocked || self.dirty => {}
+            Some(_) => {
+                for target in self.dependents.get(&pos).into_iter().flatten() {
+                    if layout.has_line_of_sight(self.center, *target) {
+                        self.visible.insert(*target);
+                    } else {
+                        self.visible.remove(target);
+                    }
+                }
+            }
+        }
+        self.visible(layout)
+    }
+
+    /// Marks the cache as outdated, so that the whole field of view is computed again on the next access.
+    ///
+    /// This must be called after changing the layout without [FovCache::update_blocked].
+    pub const fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Computes the whole field of view, and the positions behind each position.
+    fn recompute(&mut self, layout: &HexLayout<bool, S>) {
+        let range = self
+            .range
+            .unwrap_or_else(|| layout.covering_radius(self.center));
+
+        self.visible = layout.field_of_view(self.center, Some(range));
+        self.dependents.clear();
+        for target in self.center.spiral(range) {
+            for between in self.center.line_to(target) {
+                self.dependents.entry(between).or_default().push(target);
+            }
+        }
+        self.dirty = false;
+    }
+}
+
+/// A [HexLayout] that records the positions modified since the last call to [TrackedHexLayout::take_dirty].
+///
+/// The positions are marked when [TrackedHexLayout::set] or [TrackedHexLayout::delete] change them,
+/// and as soon as they are borrowed mutably (with [TrackedHexLayout::get_mut], [TrackedHexLayout::data_mut]
+/// or [TrackedHexLayout::iter_mut]), even if the data is finally left unchanged.
+/// This is useful to only render or send the tiles that changed since the last frame.
+///
+/// # Example
+///
+/// ```rust
+/// use std::collections::HashSet;
+///
+/// use hexing::{layout::{HexLayout, TrackedHexLayout}, utils::neighbors, HexPosition};
+///
+/// let mut map: HexLayout<bool, isize> = HexLayout::new_from_range(3, HexPosition(0, 0));
+/// map.set(HexPosition(0, 0), true);
+/// map.set(HexPosition(1, 0), true);
+///
+/// let mut map = TrackedHexLayout::new(map);
+/// assert_eq!(map.take_dirty().len(), 0);
+///
+/// // A generation of the game of life of the `game-of-life` example, setting every tile.
+/// let previous = map.layout().clone();
+/// for (pos, alive) in previous.iter() {
+///     let alive_neighbors = neighbors(*pos)
+///         .iter()
+///         .filter(|&&neighbor| *previous.get(neighbor).unwrap_or(&false))
+///         .count();
+///     let next_state = match (*alive, alive_neighbors) {
+///         (true, x) if x < 2 || x > 2 => false,
+///         (false, 2) => true,
+///         _ => *alive,
+///     };
+///     map.set(*pos, next_state);
+/// }
+///
+/// // Only the tiles whose value changed are reported.
+/// let expected: HashSet<_> = [
+///     HexPosition(0, 0),
+///     HexPosition(1, 0),
+///     HexPosition(1, -1),
+///     HexPosition(0, 1),
+/// ]
+/// .into();
+/// let changed: HashSet<_> = previous
+///     .iter()
+///     .filter(|(pos, alive)| map.layout().get(**pos) != Some(*alive))
+///     .map(|(pos, _)| *pos)
+///     .collect();
+/// assert_eq!(changed, expected);
+/// assert_eq!(map.take_dirty(), expected);
+/// assert!(map.take_dirty().is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrackedHexLayout<D: Default, S: Number> {
+    /// The tracked layout.
+    layout: HexLayout<D, S>,
+
+    /// The positions modified since the last call to [TrackedHexLayout::take_dirty].
+    dirty: HashSet<HexPosition<S>>,
+}
+
+impl<D: Default, S: Number> TrackedHexLayout<D, S> {
+    /// Starts tracking the changes of `layout`. No position is marked as modified.
+    pub fn new(layout: HexLayout<D, S>) -> Self {
+        Self {
+            layout,
+            dirty: HashSet::new(),
+        }
+    }
+
+    /// Returns the tracked layout, for read-only access.
+    pub const fn layout(&self) -> &HexLayout<D, S> {
+        &self.layout
+    }
+
+    /// Stops tracking the changes and returns the layout.
+    pub fn into_inner(self) -> HexLayout<D, S> {
+        self.layout
+    }
+
+    /// Returns the positions modified since the last call, and clears them.
+    pub fn take_dirty(&mut self) -> HashSet<HexPosition<S>> {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Like [HexLayout::get_mut]. The position is marked as modified if it exists.
+    pub fn get_mut(&mut self, pos: HexPosition<S>) -> Option<&mut D> {
+        let data = self.layout.get_mut(pos);
+        if data.is_some() {
+            self.dirty.insert(pos);
+        }
+        data
+    }
+
+    /// Like [HexLayout::delete]. The position is marked as modified if it existed.
+    pub fn delete(&mut self, pos: HexPosition<S>) -> Option<D> {
+        let data = self.layout.delete(pos);
+        if data.is_some() {
+            self.dirty.insert(pos);
+        }
+        data
+    }
+
+    /// Like [HexLayout::data_mut]. Every position of the layout is marked as modified.
+    pub fn data_mut(&mut self) -> impl Iterator<Item = &mut D> {
+        self.dirty.extend(self.layout.positions().copied());
+        self.layout.data_mut()
+    }
+
+    /// Like [HexLayout::iter_mut]. Every position of the layout is marked as modified.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&HexPosition<S>, &mut D)> {
+        self.dirty.extend(self.layout.positions().copied());
+        self.layout.iter_mut()
+    }
+}
+
+impl<D: Default + PartialEq, S: Number> TrackedHexLayout<D, S> {
+    /// Like [HexLayout::set]. The position is marked as modified if it is added, or if its data changes.
+    pub fn set(&mut self, pos: HexPosition<S>, data: D) -> Option<D> {
+        if self.layout.get(pos) != Some(&data) {
+            self.dirty.insert(pos);
+        }
+        self.layout.set(pos, data)
+    }
+}
+
+/// The options of the noise stage of a [HexLayoutBuilder], see [HexLayoutBuilder::noise].
+#[cfg(feature = "noise")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NoiseOptions {
+    /// The factor applied to the pixel coordinates of the positions before sampling the noise.
+    pub scale: f64,
+
+    /// The offset added to the scaled pixel coordinates, to sample another part of the noise.
+    pub offset: (f64, f64),
+}
+
+#[cfg(feature = "noise")]
+impl Default for NoiseOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: (0.0, 0.0),
+        }
+    }
+}
+
+/// A builder creating a [HexLayout] in a single expression: its shape, a height from a noise function,
+/// a blocked threshold on that height, and the data of each position.
+///
+/// Every stage is optional:
+/// - the layout is a hexagon of radius 0 (only the center) around [HexPosition::ORIGIN] by default,
+/// - the height of every position is `0.0` without noise,
+/// - without [HexLayoutBuilder::init], the data of each position is its height.
+///
+/// # Example
+///
+/// ```rust
+/// use hexing::{layout::HexLayoutBuilder, HexPosition};
+///
+/// #[derive(Default)]
+/// struct Tile {
+///     distance: isize,
+///     height: f64,
+/// }
+///
+/// let center = HexPosition(2, -1);
+/// let map = HexLayoutBuilder::new()
+///     .radius(5)
+///     .center(center)
+///     .init(move |pos, height, _| Tile { distance: pos.distance(center), height })
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(map.len(), 91);
+/// assert_eq!(map.get(center).unwrap().distance, 0);
+/// assert_eq!(map.get(HexPosition(7, -1)).unwrap().distance, 5);
+/// assert!(map.data().all(|tile| tile.height == 0.0));
+/// ```
+///
+/// See [HexLayoutBuilder::noise] for a layout with heights and blocked positions.
+pub struct HexLayoutBuilder<D: Default> {
+    /// The radius of the layout.
+    radius: usize,
+
+    /// The center of the layout.
+    center: HexPosition<isize>,
+
+    /// The function giving the height of each position, `None` for a flat layout.
+    noise: Option<Box<dyn Fn(HexPosition<isize>) -> f64>>,
+
+    /// The height above which the positions are blocked.
+    blocked_above: Option<f64>,
+
+    /// The function giving the data of each position from its height and whether it is blocked.
+    init: Box<dyn Fn(HexPosition<isize>, f64, bool) -> D>,
+
+    /// Whether `init` was given by the user.
+    custom_init: bool,
+}
+
+impl HexLayoutBuilder<f64> {
+    /// Creates a new builder with every stage disabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayoutBuilder, HexPosition};
+    ///
+    /// let map = HexLayoutBuilder::new().build().unwrap();
+    /// assert_eq!(map.len(), 1);
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&0.0));
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            radius: 0,
+            center: HexPosition::ORIGIN,
+            noise: None,
+            blocked_above: None,
+            init: Box::new(|_, height, _| height),
+            custom_init: false,
+        }
+    }
+}
+
+impl Default for HexLayoutBuilder<f64> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Default> HexLayoutBuilder<D> {
+    /// Sets the radius of the layout, which covers all the positions within `radius` of its center.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayoutBuilder, HexPosition};
+    ///
+    /// let map = HexLayoutBuilder::new().radius(2).center(HexPosition(5, 5)).build().unwrap();
+    /// assert_eq!(map.len(), 19);
+    /// assert!(map.get(HexPosition(7, 3)).is_some());
+    /// assert!(map.get(HexPosition(0, 0)).is_none());
+    /// ```
+    pub const fn radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the center of the layout.
+    pub const fn center(mut self, center: HexPosition<isize>) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Sets the noise function giving the height of each position, sampled at its pixel coordinates
+    /// like [HexLayout::init_noise].
+    ///
+    /// Note: You must include the `noise` crate in your project to use this function.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::{HexLayout, HexLayoutBuilder, NoiseOptions}, HexPosition};
+    /// use noise::Perlin;
+    ///
+    /// let map = HexLayoutBuilder::new()
+    ///     .radius(3)
+    ///     .noise(Perlin::new(1), NoiseOptions::default())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let mut expected = HexLayout::new_from_range(4, HexPosition(0, 0));
+    /// expected.init_noise(Perlin::new(1));
+    /// assert_eq!(map, expected);
+    /// ```
+    ///
+    /// The height can block the positions above a threshold:
+    ///
+    /// ```rust
+    /// use hexing::{layout::{HexLayoutBuilder, NoiseOptions}, HexPosition};
+    /// use noise::{Fbm, MultiFractal, Perlin};
+    ///
+    /// #[derive(Default)]
+    /// struct Tile {
+    ///     height: f64,
+    ///     blocked: bool,
+    /// }
+    ///
+    /// let fbm = Fbm::<Perlin>::new(12345)
+    ///     .set_octaves(2)
+    ///     .set_frequency(0.2)
+    ///     .set_lacunarity(0.4)
+    ///     .set_persistence(0.5);
+    ///
+    /// let map = HexLayoutBuilder::new()
+    ///     .radius(5)
+    ///     .center(HexPosition(2, -1))
+    ///     .noise(fbm, NoiseOptions::default())
+    ///     .blocked_above(0.1)
+    ///     .init(|_, height, blocked| Tile { height, blocked })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(map.len(), 91);
+    /// for tile in map.data() {
+    ///     assert!(tile.height > -1.0 && tile.height < 1.0);
+    ///     assert_eq!(tile.blocked, tile.height > 0.1);
+    /// }
+    /// ```
+    #[cfg(feature = "noise")]
+    pub fn noise<N: NoiseFn<f64, 2> + 'static>(mut self, noise: N, options: NoiseOptions) -> Self {
+        self.noise = Some(Box::new(move |pos| {
+            let pixel = pos.to_pixel_coordinates();
+            noise.get([
+                (pixel.0 as f64).mul_add(options.scale, options.offset.0),
+                (pixel.1 as f64).mul_add(options.scale, options.offset.1),
+            ])
+        }));
+        self
+    }
+
+    /// Blocks the positions whose height is strictly above `threshold`.
+    ///
+    /// This requires a noise function and a custom [HexLayoutBuilder::init] using the blocked flag,
+    /// otherwise [HexLayoutBuilder::build] returns an error.
+    pub const fn blocked_above(mut self, threshold: f64) -> Self {
+        self.blocked_above = Some(threshold);
+        self
+    }
+
+    /// Sets the function creating the data of each position from the position, its height, and whether it is blocked.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayoutBuilder, HexPosition};
+    ///
+    /// let map = HexLayoutBuilder::new()
+    ///     .radius(1)
+    ///     .init(|pos, _, _| pos.distance(HexPosition(0, 0)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(map.get(HexPosition(0, 0)), Some(&0));
+    /// assert_eq!(map.get(HexPosition(1, -1)), Some(&1));
+    /// ```
+    pub fn init<E: Default>(
+        self,
+        init: impl Fn(HexPosition<isize>, f64, bool) -> E + 'static,
+    ) -> HexLayoutBuilder<E> {
+        HexLayoutBuilder {
+            radius: self.radius,
+            center: self.center,
+            noise: self.noise,
+            blocked_above: self.blocked_above,
+            init: Box::new(init),
+            custom_init: true,
+        }
+    }
+
+    /// Creates the layout.
+    ///
+    /// Returns [HexError::IncompatibleOptions] if [HexLayoutBuilder::blocked_above] is used without noise
+    /// (every position would have the same height) or without [HexLayoutBuilder::init] (the blocked flag would be lost).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use hexing::{layout::HexLayoutBuilder, HexError};
+    ///
+    /// let result = HexLayoutBuilder::new()
+    ///     .radius(3)
+    ///     .blocked_above(0.5)
+    ///     .init(|_, _, blocked| blocked)
+    ///     .build();
+    ///
+    /// assert!(matches!(result, Err(HexError::IncompatibleOptions(_))));
+    /// ```
+    pub fn build(self) -> Result<HexLayout<D, isize>, HexError> {
+        if self.blocked_above.is_some() && self.noise.is_none() {
+            return Err(HexError::IncompatibleOptions(
+                "a blocked threshold needs a noise function",
+            ));
+        }
+        if self.blocked_above.is_some() && !self.custom_init {
+            return Err(HexError::IncompatibleOptions(
+                "a blocked threshold needs an init function",
+            ));
+        }
+
+        let mut layout = HashMap::default();
+        for pos in self.center.spiral(self.radius) {
+            let height = self.noise.as_ref().map_or(0.0, |noise| noise(pos));
+            let blocked = self
+                .blocked_above
+                .is_some_and(|threshold| height > threshold);
+            layout.insert(pos, (self.init)(pos, height, blocked));
+        }
+        Ok(HexLayout(layout))
     }
 }
@@ -63,9 +63,17 @@
 //!
 //! This example demonstrates basic usage of the `hexing` library, including creating hexagonal positions, converting to pixel coordinates, calculating distances, and iterating over hexagonal rings and spirals.
 
+pub mod algorithms;
+pub mod bitlayout;
+pub mod chunk;
+pub mod edge;
 pub mod layout;
+pub mod occupancy;
+pub mod pixel;
+pub mod shape;
+pub mod storage;
 pub mod utils;
-use utils::{axial_round, hexagonal_lerp};
+use utils::{HexBound, axial_round, axial_round_f64, hexagonal_lerp_f64};
 
 use std::{
     fmt::Display,
@@ -238,6 +246,28 @@ impl HexDirection {
         }
     }
 
+    /// Returns the opposite direction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexDirection, HexPosition};
+    ///
+    /// for direction in HexDirection::iter() {
+    ///     assert_eq!(direction.to_vector::<i32>() + direction.opposite().to_vector(), HexPosition(0, 0));
+    /// }
+    /// ```
+    pub const fn opposite(self) -> Self {
+        match self {
+            Self::Right => Self::Left,
+            Self::UpRight => Self::DownLeft,
+            Self::UpLeft => Self::DownRight,
+            Self::Left => Self::Right,
+            Self::DownLeft => Self::UpRight,
+            Self::DownRight => Self::UpLeft,
+        }
+    }
+
     ///  Returns a iterator of all directions.
     ///
     /// # Example
@@ -261,6 +291,98 @@ impl HexDirection {
     }
 }
 
+/// Displays the name of the direction, like `UpRight`.
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexDirection;
+///
+/// for direction in HexDirection::iter() {
+///     assert_eq!(direction.to_string().parse::<HexDirection>(), Ok(direction));
+/// }
+/// assert_eq!(HexDirection::UpRight.to_string(), "UpRight");
+/// ```
+impl Display for HexDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Parses the name of a direction, ignoring the case.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{HexDirection, HexError};
+///
+/// assert_eq!("downleft".parse(), Ok(HexDirection::DownLeft));
+/// assert_eq!("RIGHT".parse(), Ok(HexDirection::Right));
+/// assert_eq!(
+///     "up".parse::<HexDirection>(),
+///     Err(HexError::InvalidDirection("up".to_string()))
+/// );
+/// ```
+impl std::str::FromStr for HexDirection {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .into_iter()
+            .find(|direction| direction.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| HexError::InvalidDirection(s.to_string()))
+    }
+}
+
+/// The orientation of the hexagons when they are drawn.
+/// For more information, see the [documentation](https://www.redblobgames.com/grids/hexagons/#basics).
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum HexOrientation {
+    /// Hexagons with a pointy top, as used by [HexPosition::to_pixel_coordinates].
+    #[default]
+    PointyTop,
+
+    /// Hexagons with a flat top.
+    FlatTop,
+}
+
+/// The errors returned by the fallible functions of the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HexError {
+    /// A [HexLayoutBuilder](layout::HexLayoutBuilder) was given options that can't be used together.
+    IncompatibleOptions(&'static str),
+
+    /// A string couldn't be parsed as a [HexDirection].
+    InvalidDirection(String),
+
+    /// A string couldn't be parsed as a position key, see [HexPosition::from_string_key].
+    InvalidKey(String),
+
+    /// A character of a text map is between two positions, see [HexLayout::from_ascii](layout::HexLayout::from_ascii).
+    /// Contains its line and its column, both starting at 0.
+    MisalignedChar(usize, usize),
+}
+
+impl Display for HexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompatibleOptions(reason) => write!(f, "incompatible options: {}", reason),
+            Self::InvalidDirection(input) => write!(f, "invalid direction: {:?}", input),
+            Self::InvalidKey(input) => write!(f, "invalid position key: {:?}", input),
+            Self::MisalignedChar(line, column) => {
+                write!(
+                    f,
+                    "misaligned character at line {}, column {}",
+                    line, column
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
 /// A hexagonal ring iterator.
 /// This this the rust implementation of the [documentation](https://www.redblobgames.com/grids/hexagons/#rings).
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -315,6 +437,20 @@ impl<T: Number> Iterator for HexRing<T> {
 
 /// A hexagonal spiral iterator.
 /// This this the rust implementation of the [documentation](https://www.redblobgames.com/grids/hexagons/#rings) spiral.
+///
+/// The iterator can be cloned to resume the spiral from the same position later.
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexPosition;
+///
+/// let mut spiral = HexPosition(0, 0).spiral(3);
+/// spiral.nth(4);
+///
+/// let saved = spiral.clone();
+/// assert!(saved.eq(spiral));
+/// ```
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct HexSpiral<T: Number> {
@@ -329,6 +465,26 @@ pub struct HexSpiral<T: Number> {
 
     /// The index of the current ring in the spiral.
     index: usize,
+
+    /// The number of rotations (see [HexPosition::rotation]) moving the first position of the default rings,
+    /// in the [HexDirection::DownLeft] direction, to the start direction of the spiral.
+    turns: i32,
+
+    /// The order of the positions in each ring.
+    winding: HexWinding,
+}
+
+impl<T: Number> HexSpiral<T> {
+    /// Moves a position of a default ring (see [HexPosition::ring]) to its place with the spiral's
+    /// start direction and winding.
+    fn orient(&self, pos: HexPosition<T>) -> HexPosition<T> {
+        let mut offset = pos - self.origin;
+        if self.winding == HexWinding::Clockwise {
+            // The reflection across the axis of `HexDirection::DownLeft`, which reverses the rings.
+            offset = HexPosition(-offset.1, -offset.0);
+        }
+        self.origin + offset.rotation(self.turns)
+    }
 }
 
 impl<T: Number> Iterator for HexSpiral<T> {
@@ -349,7 +505,229 @@ impl<T: Number> Iterator for HexSpiral<T> {
             self.current = self.origin.ring(self.index);
             result = self.current.next();
         }
-        result
+        if self.turns == 0 && self.winding == HexWinding::CounterClockwise {
+            return result;
+        }
+        result.map(|pos| self.orient(pos))
+    }
+}
+
+/// The order of the positions in the rings of a spiral built with a [SpiralBuilder].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum HexWinding {
+    /// The order of [HexDirection::iter], from [HexDirection::Right] to [HexDirection::UpRight],
+    /// as used by [HexPosition::ring] and [HexPosition::spiral].
+    #[default]
+    CounterClockwise,
+
+    /// The reverse order, from [HexDirection::UpRight] to [HexDirection::Right].
+    Clockwise,
+}
+
+/// A symmetry of the hexagonal grid around a center, used to build and check fair maps.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{HexDirection, HexPosition, HexSymmetry};
+///
+/// let center = HexPosition(1, 1);
+/// let pos = HexPosition(3, 1);
+///
+/// assert_eq!(HexSymmetry::Rotation2(center).orbit(pos), vec![HexPosition(-1, 1), pos]);
+/// assert_eq!(HexSymmetry::Rotation3(center).orbit(pos).len(), 3);
+/// assert_eq!(HexSymmetry::Rotation6(center).orbit(pos).len(), 6);
+/// assert_eq!(HexSymmetry::Rotation6(center).orbit(center), vec![center]);
+///
+/// // The positions on the axis are their own mirror image.
+/// assert_eq!(HexSymmetry::Mirror(center, HexDirection::Left).orbit(pos), vec![pos]);
+/// assert_eq!(
+///     HexSymmetry::Mirror(center, HexDirection::Right).orbit(HexPosition(2, 0)),
+///     vec![HexPosition(1, 2), HexPosition(2, 0)]
+/// );
+///
+/// // The axis between two directions swaps the neighbors in these directions.
+/// let right = center + HexDirection::Right.to_vector();
+/// let up_right = center + HexDirection::UpRight.to_vector();
+/// assert_eq!(HexSymmetry::MirrorBetween(center, HexDirection::Right).orbit(right), vec![up_right, right]);
+/// assert_eq!(HexSymmetry::MirrorBetween(center, HexDirection::Right).orbit(right + up_right - center), vec![right + up_right - center]);
+///
+/// // With the six mirror axes, a position has six mirror images, which are not among its rotations.
+/// let pos = center + HexPosition(3, -1);
+/// let rotations = HexSymmetry::Rotation6(center).orbit(pos);
+/// let mut images: Vec<_> = HexDirection::iter()
+///     .into_iter()
+///     .flat_map(|direction| [HexSymmetry::Mirror(center, direction), HexSymmetry::MirrorBetween(center, direction)])
+///     .flat_map(|symmetry| symmetry.orbit(pos))
+///     .filter(|image| !rotations.contains(image))
+///     .collect();
+/// images.sort_by_key(|image| (image.0, image.1));
+/// images.dedup();
+/// assert_eq!(images.len(), 6);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HexSymmetry<T: Number> {
+    /// Invariance under the rotation of 180 degrees around the center.
+    Rotation2(HexPosition<T>),
+
+    /// Invariance under the rotations of 120 degrees around the center.
+    Rotation3(HexPosition<T>),
+
+    /// Invariance under the rotations of 60 degrees around the center.
+    Rotation6(HexPosition<T>),
+
+    /// Invariance under the reflection across the axis going through the center in the given direction
+    /// (and the opposite one). These axes go through the centers of the neighbors of the center,
+    /// crossing the edges of the central hexagon at their midpoints.
+    Mirror(HexPosition<T>, HexDirection),
+
+    /// Invariance under the reflection across the axis going through the center, halfway between the given direction
+    /// and the next one counterclockwise (in the order of [HexDirection::iter]). These axes go through the corners
+    /// of the central hexagon, and together with [HexSymmetry::Mirror] they are the six mirror axes of the grid.
+    MirrorBetween(HexPosition<T>, HexDirection),
+}
+
+impl<T: Number> HexSymmetry<T> {
+    /// Returns the orbit of a position: the position and all its images by the symmetry,
+    /// each only once, sorted by `q`, then by `r`.
+    pub fn orbit(self, pos: HexPosition<T>) -> Vec<HexPosition<T>> {
+        let mut orbit = match self {
+            Self::Rotation2(center) => vec![pos, pos.rotate(center, 3, HexWinding::Clockwise)],
+            Self::Rotation3(center) => (0..3)
+                .map(|i| pos.rotate(center, 2 * i, HexWinding::Clockwise))
+                .collect(),
+            Self::Rotation6(center) => (0..6)
+                .map(|i| pos.rotate(center, i, HexWinding::Clockwise))
+                .collect(),
+            Self::Mirror(center, direction) | Self::MirrorBetween(center, direction) => {
+                let turns = HexDirection::iter()
+                    .iter()
+                    .position(|other| *other == direction)
+                    .unwrap_or_default() as i32;
+                // Rotate `direction` onto `HexDirection::Right`, reflect across the axis, and rotate back.
+                let offset = (pos - center).rotation(turns);
+                let reflected = if matches!(self, Self::Mirror(..)) {
+                    HexPosition(offset.0 + offset.1, -offset.1)
+                } else {
+                    // The axis between `HexDirection::Right` and `HexDirection::UpRight` swaps them.
+                    HexPosition(offset.0, -offset.0 - offset.1)
+                };
+                vec![pos, center + reflected.rotation((6 - turns) % 6)]
+            }
+        };
+        orbit.sort_by_key(|pos| (pos.0.to_isize(), pos.1.to_isize()));
+        orbit.dedup();
+        orbit
+    }
+}
+
+/// A builder creating a [HexSpiral] with a custom start and order for its rings.
+///
+/// Each ring of the spiral starts at the corner in `start_direction` from the center, and goes around
+/// the center in the order given by its [HexWinding]. The default configuration, starting at [HexDirection::DownLeft]
+/// and going counterclockwise, gives the same spiral as [HexPosition::spiral].
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use hexing::{HexDirection, HexPosition, HexWinding, SpiralBuilder};
+///
+/// let center = HexPosition(1, -2);
+/// let default: Vec<_> = SpiralBuilder::new(center).radius(2).build().collect();
+/// assert_eq!(default, center.spiral(2).collect::<Vec<_>>());
+///
+/// let custom: Vec<_> = SpiralBuilder::new(center)
+///     .radius(2)
+///     .start_direction(HexDirection::UpRight)
+///     .winding(HexWinding::Clockwise)
+///     .build()
+///     .collect();
+/// assert_eq!(custom[1], center + HexDirection::UpRight.to_vector());
+/// assert_eq!(custom[2], center + HexDirection::Right.to_vector());
+///
+/// // The same positions, in a different order.
+/// assert_ne!(custom, default);
+/// assert_eq!(custom.iter().collect::<HashSet<_>>(), default.iter().collect::<HashSet<_>>());
+///
+/// for start_direction in HexDirection::iter() {
+///     for winding in [HexWinding::Clockwise, HexWinding::CounterClockwise] {
+///         let spiral: Vec<_> = SpiralBuilder::new(center)
+///             .radius(3)
+///             .start_direction(start_direction)
+///             .winding(winding)
+///             .build()
+///             .collect();
+///         assert_eq!(spiral.len(), 37);
+///         // The second ring starts two positions away in the start direction.
+///         let vector = start_direction.to_vector();
+///         assert_eq!(spiral[7], center + vector + vector);
+///         assert_eq!(spiral.into_iter().collect::<HashSet<_>>(), center.spiral(3).collect::<HashSet<_>>());
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SpiralBuilder<T: Number> {
+    /// The center of the spiral.
+    center: HexPosition<T>,
+
+    /// The radius of the spiral.
+    radius: usize,
+
+    /// The direction of the first position of each ring from the center.
+    start_direction: HexDirection,
+
+    /// The order of the positions in each ring.
+    winding: HexWinding,
+}
+
+impl<T: Number> SpiralBuilder<T> {
+    /// Creates a new [SpiralBuilder] around `center`, with a radius of `0` and the default configuration.
+    pub const fn new(center: HexPosition<T>) -> Self {
+        Self {
+            center,
+            radius: 0,
+            start_direction: HexDirection::DownLeft,
+            winding: HexWinding::CounterClockwise,
+        }
+    }
+
+    /// Sets the radius of the spiral.
+    pub const fn radius(mut self, radius: usize) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets the direction, from the center, of the first position of each ring.
+    pub const fn start_direction(mut self, direction: HexDirection) -> Self {
+        self.start_direction = direction;
+        self
+    }
+
+    /// Sets the order of the positions in each ring.
+    pub const fn winding(mut self, winding: HexWinding) -> Self {
+        self.winding = winding;
+        self
+    }
+
+    /// Creates the [HexSpiral].
+    pub fn build(self) -> HexSpiral<T> {
+        let start = HexDirection::iter()
+            .iter()
+            .position(|direction| *direction == self.start_direction)
+            .unwrap_or_default();
+        HexSpiral {
+            origin: self.center,
+            current: self.center.ring(1),
+            radius: self.radius,
+            index: 0,
+            // A rotation moves each direction to the previous one, and the default rings start at `HexDirection::DownLeft`.
+            turns: (10 - start as i32) % 6,
+            winding: self.winding,
+        }
     }
 }
 
@@ -365,10 +743,10 @@ pub struct HexLine<T: Number> {
     end: HexPosition<T>,
 
     /// The length of the line.
-    max_index: u32,
+    max_index: u64,
 
     /// The index of the current position in the line.
-    current_index: u32,
+    current_index: u64,
 }
 
 impl<T: Number> Iterator for HexLine<T> {
@@ -386,16 +764,47 @@ impl<T: Number> Iterator for HexLine<T> {
             return Some(self.start);
         }
 
-        // Calculate the next position.
-        let t = self.current_index as f32 / self.max_index as f32;
-        let result = axial_round(hexagonal_lerp(self.start, self.end, t));
+        // Calculate the next position, in double precision to stay exact far from the origin.
+        let t = self.current_index as f64 / self.max_index as f64;
+        let result = axial_round_f64(hexagonal_lerp_f64(self.start, self.end, t));
 
         self.current_index += 1;
         Some(HexPosition(
-            T::from_f32(result.0 as f32),
-            T::from_f32(result.1 as f32),
+            T::from_isize(result.0 as isize),
+            T::from_isize(result.1 as isize),
         ))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.max_index + 1 - self.current_index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Number> HexLine<T> {
+    /// Returns an iterator over the remaining positions of the line that are within `bound`, in the order of the line.
+    ///
+    /// The positions outside of the bound are skipped, so if the line leaves the bound and comes back,
+    /// the in-bound runs are yielded one after the other. A line fully outside of the bound yields nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// let bound = HexBound::new(HexPosition(0, 0), 2);
+    ///
+    /// // Both ends are outside, the middle is inside.
+    /// let clipped: Vec<_> = HexPosition(-4, 0).line_to(HexPosition(4, 0)).clip_to_bound(&bound).collect();
+    /// assert_eq!(clipped, (-2..=2).map(|q| HexPosition(q, 0)).collect::<Vec<_>>());
+    ///
+    /// assert_eq!(HexPosition(3, 0).line_to(HexPosition(0, 3)).clip_to_bound(&bound).count(), 0);
+    /// assert_eq!(HexPosition(1, 0).line_to(HexPosition(5, 0)).clip_to_bound(&bound).count(), 2);
+    /// ```
+    pub fn clip_to_bound(self, bound: &HexBound<T>) -> impl Iterator<Item = HexPosition<T>> {
+        let bound = *bound;
+        self.filter(move |pos| bound.contains(*pos))
+    }
 }
 
 impl<T: Number> HexPosition<T> {
@@ -463,6 +872,180 @@ impl<T: Number> HexPosition<T> {
         Self(T::from_f32(result.0 as f32), T::from_f32(result.1 as f32))
     }
 
+    /// Converts the current [HexPosition] into a pixel coordinate, for hexagons drawn with the given orientation.
+    ///
+    /// With [HexOrientation::PointyTop], this is the same as [HexPosition::to_pixel_coordinates].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexOrientation, HexPosition};
+    ///
+    /// let position = HexPosition(1, 0);
+    /// assert_eq!(
+    ///     position.to_oriented_pixel_coordinates(HexOrientation::PointyTop),
+    ///     position.to_pixel_coordinates()
+    /// );
+    /// assert_eq!(
+    ///     position.to_oriented_pixel_coordinates(HexOrientation::FlatTop),
+    ///     (1.5, 3f32.sqrt() / 2.0)
+    /// );
+    /// ```
+    pub fn to_oriented_pixel_coordinates(&self, orientation: HexOrientation) -> (f32, f32) {
+        match orientation {
+            HexOrientation::PointyTop => self.to_pixel_coordinates(),
+            HexOrientation::FlatTop => (
+                3.0 / 2.0 * T::to_f32(self.0),
+                3f32.sqrt()
+                    .mul_add(T::to_f32(self.1), 3f32.sqrt() / 2.0 * T::to_f32(self.0)),
+            ),
+        }
+    }
+
+    /// Returns the euclidean distance between the centers of two [HexPosition]s, in pixels.
+    ///
+    /// This is not the same as [HexPosition::distance], which counts the number of steps in the grid.
+    /// Note that the distance doesn't depend on the orientation, which only rotates the grid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexOrientation, HexPosition};
+    ///
+    /// let a = HexPosition(0, 0);
+    /// let b = HexPosition(1, 0);
+    ///
+    /// let distance = a.pixel_distance(b, HexOrientation::PointyTop);
+    /// assert!((distance - 3f32.sqrt()).abs() < 1e-6);
+    /// ```
+    pub fn pixel_distance(self, other: Self, orientation: HexOrientation) -> f32 {
+        let a = self.to_oriented_pixel_coordinates(orientation);
+        let b = other.to_oriented_pixel_coordinates(orientation);
+        (a.0 - b.0).hypot(a.1 - b.1)
+    }
+
+    /// Returns the pixel coordinates of the center of the [HexPosition], rotated by `radians` around `center_pixel`.
+    ///
+    /// Unlike [HexPosition::rotation], the angle can be any value, so the result is usually not the center
+    /// of a hexagon: this is meant for rendering (e.g. animating a rotation), not for the grid logic.
+    /// Like [HexPosition::rotation], positive angles are counterclockwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::f32::consts::PI;
+    ///
+    /// use hexing::{HexOrientation, HexPosition};
+    ///
+    /// let position = HexPosition(2, -1);
+    /// let orientation = HexOrientation::PointyTop;
+    ///
+    /// let rotated = position.rotate_pixel((0.0, 0.0), PI, orientation);
+    /// let expected = position.reflect().to_oriented_pixel_coordinates(orientation);
+    /// assert!((rotated.0 - expected.0).abs() < 1e-5 && (rotated.1 - expected.1).abs() < 1e-5);
+    ///
+    /// let rotated = position.rotate_pixel((0.0, 0.0), PI / 3.0, orientation);
+    /// let expected = position.rotation(1).to_oriented_pixel_coordinates(orientation);
+    /// assert!((rotated.0 - expected.0).abs() < 1e-5 && (rotated.1 - expected.1).abs() < 1e-5);
+    /// ```
+    pub fn rotate_pixel(
+        self,
+        center_pixel: (f32, f32),
+        radians: f32,
+        orientation: HexOrientation,
+    ) -> (f32, f32) {
+        let (x, y) = self.to_oriented_pixel_coordinates(orientation);
+        let (dx, dy) = (x - center_pixel.0, y - center_pixel.1);
+        let (sin, cos) = radians.sin_cos();
+        (
+            dx.mul_add(cos, -dy * sin) + center_pixel.0,
+            dx.mul_add(sin, dy * cos) + center_pixel.1,
+        )
+    }
+
+    /// Returns the position at `distance` steps from `center` in the given direction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexDirection, HexPosition};
+    ///
+    /// let center = HexPosition(1, 1);
+    ///
+    /// assert_eq!(HexPosition::from_polar(center, HexDirection::UpRight, 3), HexPosition(4, -2));
+    /// assert_eq!(HexPosition::from_polar(center, HexDirection::Left, 2), HexPosition(-1, 1));
+    /// assert_eq!(HexPosition::from_polar(center, HexDirection::Left, 0), center);
+    /// ```
+    pub fn from_polar(center: Self, direction: HexDirection, distance: usize) -> Self {
+        center + direction.to_vector() * T::from_usize(distance)
+    }
+
+    /// Returns the direction and the distance of the current [HexPosition] from `center`,
+    /// if it lies straight along one of the six directions from `center`. This is the inverse of [HexPosition::from_polar].
+    ///
+    /// Returns `None` if the position is off these axes, or if it is `center` itself.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexDirection, HexPosition};
+    ///
+    /// let center = HexPosition(1, 1);
+    ///
+    /// assert_eq!(HexPosition(4, -2).to_polar(center), Some((HexDirection::UpRight, 3)));
+    /// assert_eq!(HexPosition(-1, 1).to_polar(center), Some((HexDirection::Left, 2)));
+    /// assert_eq!(HexPosition(3, 0).to_polar(center), None);
+    /// assert_eq!(center.to_polar(center), None);
+    /// ```
+    pub fn to_polar(self, center: Self) -> Option<(HexDirection, usize)> {
+        let distance = self.distance(center).to_isize() as usize;
+        if distance == 0 {
+            return None;
+        }
+        HexDirection::iter()
+            .into_iter()
+            .find(|direction| Self::from_polar(center, *direction, distance) == self)
+            .map(|direction| (direction, distance))
+    }
+
+    /// Returns the reflection of the current [HexPosition] across the line going through the centers of `a` and `b`.
+    ///
+    /// The reflection is done in pixel space, then rounded to the nearest hexagon, so `a` and `b` are their own reflection.
+    /// If `a` and `b` are the same position, the reflection is done through that position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let (a, b) = (HexPosition(0, 0), HexPosition(2, 0));
+    ///
+    /// assert_eq!(HexPosition(1, -1).mirror_over(a, b), HexPosition(0, 1));
+    /// assert_eq!(HexPosition(0, 2).mirror_over(a, b), HexPosition(2, -2));
+    /// assert_eq!(a.mirror_over(a, b), a);
+    /// assert_eq!(b.mirror_over(a, b), b);
+    ///
+    /// assert_eq!(HexPosition(1, 0).mirror_over(a, a), HexPosition(-1, 0));
+    /// ```
+    pub fn mirror_over(self, a: Self, b: Self) -> HexPosition<i32> {
+        let (x, y) = self.to_pixel_coordinates();
+        let (ax, ay) = a.to_pixel_coordinates();
+        let (bx, by) = b.to_pixel_coordinates();
+        let (dx, dy) = (bx - ax, by - ay);
+
+        let length = dx.mul_add(dx, dy * dy);
+        let t = if length == 0.0 {
+            0.0
+        } else {
+            (x - ax).mul_add(dx, (y - ay) * dy) / length
+        };
+        let projected = (t.mul_add(dx, ax), t.mul_add(dy, ay));
+        HexPosition::from_pixel_coordinates((
+            2.0f32.mul_add(projected.0, -x),
+            2.0f32.mul_add(projected.1, -y),
+        ))
+    }
+
     /// Returns the distance between two [HexPosition]s.
     ///
     /// # How it works
@@ -491,6 +1074,55 @@ impl<T: Number> HexPosition<T> {
             .max((-self.0 - self.1 - (-other.0 - other.1)).abs())
     }
 
+    /// Returns the distance to `center` as a fraction of `radius`, clamped to `[0, 1]`.
+    ///
+    /// This is the usual input of a falloff curve, for example to lower the terrain near the border of an island.
+    /// With a radius of `0`, the center is at `0.0` and every other position is at `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let center = HexPosition(2, -1);
+    ///
+    /// assert_eq!(center.distance_normalized(center, 4), 0.0);
+    /// assert_eq!(HexPosition(2, 1).distance_normalized(center, 4), 0.5);
+    /// assert_eq!(HexPosition(6, -1).distance_normalized(center, 4), 1.0);
+    /// assert_eq!(HexPosition(9, -1).distance_normalized(center, 4), 1.0);
+    /// assert_eq!(HexPosition(3, -1).distance_normalized(center, 0), 1.0);
+    /// ```
+    pub fn distance_normalized(self, center: Self, radius: usize) -> f32 {
+        let distance = self.distance(center).to_f32();
+        if radius == 0 {
+            return if distance == 0.0 { 0.0 } else { 1.0 };
+        }
+        (distance / radius as f32).clamp(0.0, 1.0)
+    }
+
+    /// Returns the distance to the nearest position of the line from `a` to `b` (see [HexPosition::line_to]).
+    ///
+    /// This is useful for influence maps around roads or rivers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let (a, b) = (HexPosition(-3, 0), HexPosition(3, 0));
+    ///
+    /// assert_eq!(HexPosition(0, 0).distance_to_line(a, b), 0);
+    /// assert_eq!(HexPosition(1, -1).distance_to_line(a, b), 1);
+    /// assert_eq!(HexPosition(0, 3).distance_to_line(a, b), 3);
+    /// assert_eq!(HexPosition(6, 0).distance_to_line(a, b), 3);
+    /// assert_eq!(HexPosition(6, 0).distance_to_line(a, a), HexPosition(6, 0).distance(a));
+    /// ```
+    pub fn distance_to_line(self, a: Self, b: Self) -> T {
+        a.line_to(b)
+            .map(|pos| self.distance(pos))
+            .fold(self.distance(a), |min, distance| min.min(distance))
+    }
+
     /// Returns the hexagonal ring of the given radius.
     /// If you want to learn more about hexagonal grids, check the
     /// [documentation](https://www.redblobgames.com/grids/hexagons/#rings)
@@ -516,10 +1148,83 @@ impl<T: Number> HexPosition<T> {
         }
     }
 
+    /// Returns the part of the hexagonal ring of the given radius going from the corner in the `from` direction
+    /// to the corner in the `to` direction, both included.
+    ///
+    /// The positions are yielded in the same order as [HexPosition::ring], going past its first position if needed.
+    /// If `from` and `to` are the same direction, only the corner is yielded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexDirection, HexPosition};
+    ///
+    /// let center = HexPosition(0, 0);
+    ///
+    /// let half: Vec<_> = center.ring_arc(2, HexDirection::DownLeft, HexDirection::UpRight).collect();
+    /// assert_eq!(half.len(), 12 / 2 + 1);
+    /// assert_eq!(half[0], HexPosition(-2, 2));
+    /// assert_eq!(half[6], HexPosition(2, -2));
+    /// assert!(half.iter().all(|pos| pos.is_on_ring(center, 2)));
+    ///
+    /// // The arc can go past the start of the ring.
+    /// let arc: Vec<_> = center.ring_arc(1, HexDirection::Left, HexDirection::DownRight).collect();
+    /// assert_eq!(arc, vec![HexPosition(-1, 0), HexPosition(-1, 1), HexPosition(0, 1)]);
+    /// ```
+    pub fn ring_arc(
+        self,
+        radius: usize,
+        from: HexDirection,
+        to: HexDirection,
+    ) -> impl Iterator<Item = Self> {
+        // The index of the corner in each direction, in the order of the ring.
+        let corner = |direction| match direction {
+            HexDirection::DownLeft => 0,
+            HexDirection::DownRight => radius,
+            HexDirection::Right => radius * 2,
+            HexDirection::UpRight => radius * 3,
+            HexDirection::UpLeft => radius * 4,
+            HexDirection::Left => radius * 5,
+        };
+        let len = radius * 6;
+        let count = if len == 0 {
+            1
+        } else {
+            (corner(to) + len - corner(from)) % len + 1
+        };
+
+        self.ring(radius)
+            .take(std::cmp::max(len, 1))
+            .cycle()
+            .skip(corner(from))
+            .take(count)
+    }
+
+    /// Returns `true` if the current [HexPosition] is exactly on the ring of the given radius around `center`.
+    /// This is equivalent to checking that `self.distance(center) == radius`, without building the ring.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let center = HexPosition(1, -1);
+    ///
+    /// for ring_position in center.ring(2) {
+    ///     assert!(ring_position.is_on_ring(center, 2));
+    /// }
+    /// assert!(!center.is_on_ring(center, 2));
+    /// ```
+    pub fn is_on_ring(self, center: Self, radius: usize) -> bool {
+        self.distance(center).to_isize() == radius as isize
+    }
+
     /// Returns the hexagonal spiral of the given radius.
     /// If you want to learn more about hexagonal grids, check the
     /// [documentation](https://www.redblobgames.com/grids/hexagons/#rings-spiral)
     ///
+    /// See [SpiralBuilder] to change where the rings of the spiral start and their order.
+    ///
     /// # Example
     ///
     /// ```
@@ -533,12 +1238,34 @@ impl<T: Number> HexPosition<T> {
     /// }
     /// ```
     pub fn spiral(self, radius: usize) -> HexSpiral<T> {
-        HexSpiral {
-            origin: self,
-            current: self.ring(1),
-            radius,
-            index: 0,
-        }
+        SpiralBuilder::new(self).radius(radius).build()
+    }
+
+    /// Returns the hexagonal spiral going from the ring of radius `inner` to the ring of radius `outer`, both included.
+    ///
+    /// Rings are yielded in order of increasing radius, each one in the same order as [HexPosition::ring],
+    /// so `spiral_range(0, radius)` yields the same positions in the same order as [HexPosition::spiral].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let position = HexPosition(0, 0);
+    ///
+    /// let first = position.spiral_range(2, 4).next().unwrap();
+    /// assert!(first.is_on_ring(position, 2));
+    /// assert_eq!(position.spiral_range(2, 4).count(), 12 + 18 + 24);
+    ///
+    /// assert!(position.spiral_range(0, 3).eq(position.spiral(3)));
+    /// ```
+    pub fn spiral_range(self, inner: usize, outer: usize) -> impl Iterator<Item = Self> {
+        (inner..=outer).flat_map(move |radius| {
+            // The ring of radius 0 is the position itself.
+            std::iter::once(self)
+                .take(usize::from(radius == 0))
+                .chain(self.ring(radius).take(radius * 6))
+        })
     }
 
     /// Returns the line between two [HexPosition]s as a iterator.
@@ -560,26 +1287,44 @@ impl<T: Number> HexPosition<T> {
     ///
     /// let c = HexPosition(3, -2);
     /// assert_eq!(c.line_to(c).count(), 1);
+    ///
+    /// // Positions far from the origin are kept exact.
+    /// let far = HexPosition(1_i64 << 40, 7);
+    /// let line: Vec<_> = far.line_to(far + HexPosition(3, -3)).collect();
+    /// let expected: Vec<_> = (0..=3).map(|k| far + HexPosition(k, -k)).collect();
+    /// assert_eq!(line, expected);
+    ///
+    /// // Long lines have the exact number of positions.
+    /// let end = HexPosition((1_i64 << 24) + 1, 0);
+    /// assert_eq!(HexPosition(0, 0).distance(end), (1 << 24) + 1);
+    /// assert_eq!(HexPosition(0, 0).line_to(end).size_hint().0, (1 << 24) + 2);
+    ///
+    /// // Even when they are longer than `u32::MAX`.
+    /// let end = HexPosition(1_i64 << 40, 0);
+    /// assert_eq!(HexPosition(0, 0).line_to(end).size_hint().0, (1 << 40) + 1);
     /// ```
     pub fn line_to(self, other: Self) -> HexLine<T> {
         HexLine {
             start: self,
             end: other,
-            max_index: self.distance(other).to_f32() as u32,
+            max_index: self.distance(other).to_isize().unsigned_abs() as u64,
             current_index: 0,
         }
     }
 
     /// Returns the rotation of the current [HexPosition] by 60 degrees n times.
-    /// Note that the rotation is counterclockwise.
+    /// Note that the rotation is counterclockwise with the `r` axis pointing up, so it turns [HexDirection::Right]
+    /// into [HexDirection::DownRight]: this is `self.rotate(HexPosition::ORIGIN, n, HexWinding::Clockwise)`,
+    /// see [HexPosition::rotate].
     ///
     /// # Example
     ///
     /// ```
-    /// use hexing::HexPosition;
+    /// use hexing::{HexPosition, HexWinding};
     ///
     /// let position = HexPosition(-3, 1);
     /// assert_eq!(position.rotation(2), HexPosition(2, -3));
+    /// assert_eq!(position.rotation(2), position.rotate(HexPosition::ORIGIN, 2, HexWinding::Clockwise));
     /// ```
     pub fn rotation(self, n: i32) -> Self {
         if n == 0 {
@@ -590,6 +1335,126 @@ impl<T: Number> HexPosition<T> {
         }
     }
 
+    /// Returns the rotation of the current [HexPosition] around `pivot` by 60 degrees `turns` times.
+    ///
+    /// The `winding` is the same as for the rings of a [SpiralBuilder]: [HexWinding::CounterClockwise] follows
+    /// the order of [HexDirection::iter], turning [HexDirection::Right] into [HexDirection::UpRight].
+    /// A negative number of turns rotates the other way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexDirection, HexPosition, HexWinding};
+    ///
+    /// let pivot = HexPosition(1, 1);
+    /// let right = pivot + HexDirection::Right.to_vector();
+    /// assert_eq!(right.rotate(pivot, 1, HexWinding::CounterClockwise), pivot + HexDirection::UpRight.to_vector());
+    /// assert_eq!(right.rotate(pivot, 1, HexWinding::Clockwise), pivot + HexDirection::DownRight.to_vector());
+    ///
+    /// // The same number of turns in both windings cancel out.
+    /// let position = HexPosition(4, -1);
+    /// for turns in -7..=7 {
+    ///     let turned = position.rotate(pivot, turns, HexWinding::Clockwise);
+    ///     assert_eq!(turned.distance(pivot), position.distance(pivot));
+    ///     assert_eq!(turned.rotate(pivot, turns, HexWinding::CounterClockwise), position);
+    ///     assert_eq!(turned, position.rotate(pivot, -turns, HexWinding::CounterClockwise));
+    /// }
+    /// ```
+    pub fn rotate(self, pivot: Self, turns: i32, winding: HexWinding) -> Self {
+        let turns = match winding {
+            HexWinding::Clockwise => turns,
+            HexWinding::CounterClockwise => -turns,
+        };
+        pivot + (self - pivot).rotation(turns.rem_euclid(6))
+    }
+
+    /// Returns the six rotations of the current [HexPosition] around the origin,
+    /// where the element `i` is the position rotated `i` times like [HexPosition::rotation].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let position = HexPosition(2, -1);
+    /// let rotations = position.rotations();
+    ///
+    /// assert_eq!(rotations.len(), 6);
+    /// assert_eq!(rotations[0], position);
+    /// assert_eq!(rotations[1], position.rotation(1));
+    /// assert_eq!(rotations[5].rotation(1), position);
+    /// ```
+    pub fn rotations(self) -> [Self; 6] {
+        let mut rotations = [self; 6];
+        for i in 1..6 {
+            rotations[i] = Self(-rotations[i - 1].1, rotations[i - 1].0 + rotations[i - 1].1);
+        }
+        rotations
+    }
+
+    /// Checks if the current [HexPosition] is within the wedge (cone) starting at `center` and facing `facing`.
+    ///
+    /// The wedge spreads over `half_width` steps of 60 degrees on each side of the `facing` direction:
+    /// with `half_width = 0` it only contains the positions straight in the `facing` direction,
+    /// and with `half_width >= 3` it contains every position. The angles are measured in cube coordinates,
+    /// so the borders of the wedge follow the lines of the grid. The `center` itself is never in the wedge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{HexDirection, HexPosition};
+    ///
+    /// let center = HexPosition(0, 0);
+    /// let in_cone = |pos: HexPosition<i32>| pos.in_wedge(center, HexDirection::Right, 1);
+    ///
+    /// // In front of the center.
+    /// assert!(in_cone(HexPosition(1, 0)));
+    /// assert!(in_cone(HexPosition(3, 0)));
+    /// assert!(in_cone(HexPosition(2, -1)));
+    /// assert!(in_cone(HexPosition(1, -1)));
+    /// assert!(in_cone(HexPosition(0, 2)));
+    ///
+    /// // Behind the center.
+    /// assert!(!in_cone(HexPosition(-1, 0)));
+    /// assert!(!in_cone(HexPosition(0, -1)));
+    /// assert!(!in_cone(HexPosition(-2, 1)));
+    /// assert!(!in_cone(center));
+    /// ```
+    pub fn in_wedge(self, center: Self, facing: HexDirection, half_width: usize) -> bool {
+        let offset = self - center;
+        let (q, r) = (offset.0.to_isize(), offset.1.to_isize());
+        if q == 0 && r == 0 {
+            return false;
+        }
+
+        let directions = HexDirection::iter();
+        let facing = directions
+            .iter()
+            .position(|direction| *direction == facing)
+            .unwrap_or_default() as isize;
+
+        for k in 0..6 {
+            let first: HexPosition<isize> = directions[k].to_vector();
+            let second: HexPosition<isize> = directions[(k + 1) % 6].to_vector();
+
+            // Decomposes the offset as `a * first + b * second`, the determinant being 1 or -1.
+            let det = first.0 * second.1 - second.0 * first.1;
+            let a = (q * second.1 - second.0 * r) * det;
+            let b = (first.0 * r - q * first.1) * det;
+            if a < 0 || b < 0 {
+                continue;
+            }
+
+            // The angle of the offset from `facing` is `angle / (a + b)` steps of 60 degrees.
+            let steps = a + b;
+            let angle = (k as isize - facing).rem_euclid(6) * steps + b;
+            let angle = std::cmp::min(angle, 6 * steps - angle);
+            return angle <= half_width as isize * steps;
+        }
+
+        false
+    }
+
     /// Returns the reflection of the current [HexPosition].
     /// The reflection is the position with the same distance from the origin but in the opposite direction.
     /// (like a central symmetry)
@@ -605,6 +1470,25 @@ impl<T: Number> HexPosition<T> {
     pub fn reflect(self) -> Self {
         Self::new(-self.0, -self.1)
     }
+
+    /// Returns the reflection of the current [HexPosition] about `center`.
+    /// This is the position at the same distance from `center` but in the opposite direction,
+    /// so reflecting about [HexPosition::ORIGIN] is the same as [HexPosition::reflect].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::HexPosition;
+    ///
+    /// let position = HexPosition(3, -1);
+    /// let center = HexPosition(1, 0);
+    /// assert_eq!(position.reflect_about(center), HexPosition(-1, 1));
+    /// assert_eq!(position.reflect_about(center).distance(center), position.distance(center));
+    /// assert_eq!(position.reflect_about(HexPosition::ORIGIN), position.reflect());
+    /// ```
+    pub fn reflect_about(self, center: Self) -> Self {
+        center + center - self
+    }
 }
 
 /// Implementation of the arithmetic operators for hexagonal positions.
@@ -642,6 +1526,42 @@ macro_rules! impl_ops {
     )*}};
 }
 
+/// A trait for the types that can be converted into a [HexPosition].
+///
+/// Functions can take an `impl ToHexPosition<T>` to accept positions given as tuples or arrays as well.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{HexPosition, ToHexPosition};
+///
+/// assert_eq!((1, -2).to_hex(), HexPosition(1, -2));
+/// assert_eq!([1, -2].to_hex(), HexPosition(1, -2));
+/// assert_eq!(HexPosition(1, -2).to_hex(), HexPosition(1, -2));
+/// ```
+pub trait ToHexPosition<T: Number> {
+    /// Converts the value into a [HexPosition].
+    fn to_hex(self) -> HexPosition<T>;
+}
+
+impl<T: Number> ToHexPosition<T> for HexPosition<T> {
+    fn to_hex(self) -> Self {
+        self
+    }
+}
+
+impl<T: Number> ToHexPosition<T> for (T, T) {
+    fn to_hex(self) -> HexPosition<T> {
+        HexPosition(self.0, self.1)
+    }
+}
+
+impl<T: Number> ToHexPosition<T> for [T; 2] {
+    fn to_hex(self) -> HexPosition<T> {
+        HexPosition(self[0], self[1])
+    }
+}
+
 impl<T: Number> Display for HexPosition<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({:?}, {:?})", self.0, self.1)
@@ -655,3 +1575,98 @@ impl_ops! {
     (Div, div),
     (Rem, rem),
 }
+
+/// Implementation of the packed `u64` keys for the hexagonal positions with coordinates of at most 32 bits.
+macro_rules! impl_key {
+    ($($t:ty,)*) => {$(
+        impl HexPosition<$t> {
+            /// Packs the position into an `u64`, for storage, networking or compact serialization.
+            ///
+            /// The format is stable: the high 32 bits store `q` and the low 32 bits store `r`, each one
+            /// converted to an `i32` and offset by `2^31` (its sign bit is flipped) so that negative coordinates pack correctly.
+            /// Smaller coordinate types are widened first, so the same position always has the same key, whatever its type.
+            /// The keys are ordered like the positions sorted by `q`, then by `r`.
+            ///
+            /// # Example
+            ///
+            /// ```
+            /// use hexing::HexPosition;
+            ///
+            /// let extremes = [i32::MIN, -1, 0, 1, i32::MAX];
+            /// for q in extremes {
+            ///     for r in extremes {
+            ///         let pos = HexPosition(q, r);
+            ///         assert_eq!(HexPosition::<i32>::from_key(pos.to_key()), pos);
+            ///     }
+            /// }
+            ///
+            /// let mut seed: u64 = 42;
+            /// for _ in 0..1000 {
+            ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ///     let pos = HexPosition((seed >> 32) as i32, seed as i32);
+            ///     assert_eq!(HexPosition::<i32>::from_key(pos.to_key()), pos);
+            /// }
+            ///
+            /// assert_eq!(HexPosition(0_i32, 0).to_key(), 0x8000_0000_8000_0000);
+            /// assert_eq!(HexPosition(-3_i8, 7).to_key(), HexPosition(-3_i32, 7).to_key());
+            /// assert!(HexPosition(-1_i32, 5).to_key() < HexPosition(0_i32, -5).to_key());
+            /// ```
+            pub const fn to_key(self) -> u64 {
+                let q = (self.0 as i32 as u32) ^ (1 << 31);
+                let r = (self.1 as i32 as u32) ^ (1 << 31);
+                ((q as u64) << 32) | r as u64
+            }
+
+            /// Unpacks a position packed by [HexPosition::to_key].
+            ///
+            /// If the key was created from a position with a larger coordinate type, the coordinates are truncated.
+            pub const fn from_key(key: u64) -> Self {
+                let q = ((key >> 32) as u32 ^ (1 << 31)) as i32;
+                let r = (key as u32 ^ (1 << 31)) as i32;
+                Self(q as $t, r as $t)
+            }
+
+            /// Returns the position as a string key like `"1,-2"` (`q`, then `r`), for example to use positions
+            /// as the keys of a JSON object.
+            ///
+            /// # Example
+            ///
+            /// ```
+            /// use hexing::{HexError, HexPosition};
+            ///
+            /// assert_eq!(HexPosition(1_i32, -2).to_string_key(), "1,-2");
+            ///
+            /// for pos in [HexPosition(0_i32, 0), HexPosition(-7, 3), HexPosition(i32::MIN, i32::MAX)] {
+            ///     assert_eq!(HexPosition::<i32>::from_string_key(&pos.to_string_key()), Ok(pos));
+            /// }
+            ///
+            /// assert_eq!(HexPosition::<i32>::from_string_key(" -4 , 5 "), Ok(HexPosition(-4, 5)));
+            /// assert_eq!(
+            ///     HexPosition::<i32>::from_string_key("1;2"),
+            ///     Err(HexError::InvalidKey("1;2".to_string()))
+            /// );
+            /// assert!(HexPosition::<i8>::from_string_key("300,0").is_err());
+            /// ```
+            pub fn to_string_key(self) -> String {
+                format!("{},{}", self.0, self.1)
+            }
+
+            /// Parses a string key created by [HexPosition::to_string_key].
+            /// Spaces around the coordinates are ignored.
+            ///
+            /// Returns [HexError::InvalidKey] if the string isn't two integers separated by a comma,
+            /// or if a coordinate doesn't fit in the coordinate type.
+            pub fn from_string_key(key: &str) -> Result<Self, HexError> {
+                let invalid = || HexError::InvalidKey(key.to_string());
+                let (q, r) = key.split_once(',').ok_or_else(invalid)?;
+                let q = q.trim().parse::<$t>().map_err(|_| invalid())?;
+                let r = r.trim().parse::<$t>().map_err(|_| invalid())?;
+                Ok(Self(q, r))
+            }
+        }
+    )*};
+}
+
+impl_key! {
+    i8, i16, i32,
+}
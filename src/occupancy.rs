@@ -0,0 +1,240 @@
+//! # Positions of entities on the grid.
+//!
+//! An [OccupancyLayout] maps each tile to the entities standing on it, like a `HexLayout<Vec<Id>, _>`,
+//! and keeps a reverse index from each entity to its position, so both lookups are cheap.
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use layout::HexLayout;
+
+use crate::*;
+
+/// A layout of the entities standing on each tile, with any number of entities per tile.
+///
+/// An entity is at most at one position. The entities of a tile are kept in the order they arrived on it,
+/// and tiles without entities are not stored.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{occupancy::OccupancyLayout, HexPosition};
+///
+/// let mut entities: OccupancyLayout<&str, i32> = OccupancyLayout::new();
+/// assert!(entities.add(HexPosition(0, 0), "knight"));
+/// assert!(entities.add(HexPosition(0, 0), "sword"));
+/// assert!(entities.add(HexPosition(2, -1), "archer"));
+///
+/// // An entity can't be added twice, even on another tile.
+/// assert!(!entities.add(HexPosition(0, 0), "knight"));
+/// assert!(!entities.add(HexPosition(1, 0), "knight"));
+/// assert_eq!(entities.entities_at(HexPosition(0, 0)), ["knight", "sword"]);
+///
+/// assert!(entities.move_entity(&"knight", HexPosition(0, 0), HexPosition(1, 0)));
+/// assert_eq!(entities.position_of(&"knight"), Some(HexPosition(1, 0)));
+/// assert_eq!(entities.entities_at(HexPosition(0, 0)), ["sword"]);
+///
+/// // Removing an entity from a tile it isn't on does nothing.
+/// assert!(!entities.remove(HexPosition(0, 0), &"knight"));
+/// assert!(!entities.remove(HexPosition(0, 0), &"dragon"));
+/// assert!(entities.remove(HexPosition(0, 0), &"sword"));
+/// assert_eq!(entities.position_of(&"sword"), None);
+/// assert!(entities.entities_at(HexPosition(0, 0)).is_empty());
+/// assert_eq!(entities.len(), 2);
+///
+/// entities.clear();
+/// assert_eq!(entities.position_of(&"archer"), None);
+/// assert!(entities.add(HexPosition(3, 3), "archer"));
+/// ```
+#[derive(Clone)]
+pub struct OccupancyLayout<Id, T: Number> {
+    /// The entities of each occupied position.
+    tiles: HexLayout<Vec<Id>, T>,
+
+    /// The position of each entity, always in sync with `tiles`.
+    positions: HashMap<Id, HexPosition<T>>,
+}
+
+impl<Id: Eq + Hash + Clone, T: Number> Default for OccupancyLayout<Id, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id: Eq + Hash + Clone, T: Number> OccupancyLayout<Id, T> {
+    /// Creates a new layout without any entity.
+    pub fn new() -> Self {
+        Self {
+            tiles: std::iter::empty().collect(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Adds an entity at the end of the entities of `pos`.
+    ///
+    /// Returns `false` and does nothing if the entity is already in the layout, at any position.
+    /// Use [OccupancyLayout::move_entity] to change its position.
+    pub fn add(&mut self, pos: HexPosition<T>, id: Id) -> bool {
+        if self.positions.contains_key(&id) {
+            return false;
+        }
+        self.positions.insert(id.clone(), pos);
+        match self.tiles.get_mut(pos) {
+            Some(ids) => ids.push(id),
+            None => {
+                self.tiles.set(pos, vec![id]);
+            }
+        }
+        true
+    }
+
+    /// Removes an entity from `pos`. Returns `false` and does nothing if the entity isn't at `pos`.
+    pub fn remove(&mut self, pos: HexPosition<T>, id: &Id) -> bool {
+        if self.positions.get(id) != Some(&pos) {
+            return false;
+        }
+        self.positions.remove(id);
+        self.remove_from_tile(pos, id);
+        true
+    }
+
+    /// Removes an entity from the layout, wherever it is, and returns its position.
+    pub fn remove_entity(&mut self, id: &Id) -> Option<HexPosition<T>> {
+        let pos = self.positions.remove(id)?;
+        self.remove_from_tile(pos, id);
+        Some(pos)
+    }
+
+    /// Moves an entity from `from` to the end of the entities of `to`.
+    /// Returns `false` and does nothing if the entity isn't at `from`.
+    pub fn move_entity(&mut self, id: &Id, from: HexPosition<T>, to: HexPosition<T>) -> bool {
+        if !self.remove(from, id) {
+            return false;
+        }
+        self.add(to, id.clone())
+    }
+
+    /// Returns the entities at `pos`, in the order they arrived on it.
+    pub fn entities_at(&self, pos: HexPosition<T>) -> &[Id] {
+        self.tiles.get(pos).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the position of an entity, or `None` if it isn't in the layout.
+    pub fn position_of(&self, id: &Id) -> Option<HexPosition<T>> {
+        self.positions.get(id).copied()
+    }
+
+    /// Returns an iterator over the entities within `radius` of `pos` (`pos` included), with their position.
+    /// The order of the positions is unspecified, see [HexLayout::region].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{occupancy::OccupancyLayout, HexPosition};
+    ///
+    /// let mut entities: OccupancyLayout<u32, i32> = OccupancyLayout::new();
+    /// entities.add(HexPosition(0, 0), 1);
+    /// entities.add(HexPosition(1, 1), 2);
+    /// entities.add(HexPosition(1, 1), 3);
+    /// entities.add(HexPosition(5, 0), 4);
+    ///
+    /// let near: HashSet<_> = entities.entities_within(HexPosition(1, 0), 1).collect();
+    /// assert_eq!(near, HashSet::from([(HexPosition(0, 0), &1), (HexPosition(1, 1), &2), (HexPosition(1, 1), &3)]));
+    /// assert_eq!(entities.entities_within(HexPosition(5, 0), 0).count(), 1);
+    /// ```
+    pub fn entities_within(
+        &self,
+        pos: HexPosition<T>,
+        radius: usize,
+    ) -> impl Iterator<Item = (HexPosition<T>, &Id)> {
+        self.tiles
+            .within_range(pos, radius)
+            .flat_map(|(pos, ids)| ids.iter().map(move |id| (pos, id)))
+    }
+
+    /// Returns an iterator over the occupied positions and their entities.
+    pub fn iter(&self) -> impl Iterator<Item = (&HexPosition<T>, &[Id])> {
+        self.tiles.iter().map(|(pos, ids)| (pos, ids.as_slice()))
+    }
+
+    /// Returns the number of entities in the layout.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns `true` if there is no entity in the layout.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Removes all the entities from the layout.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{occupancy::OccupancyLayout, HexPosition};
+    ///
+    /// // Many random moves keep both indices consistent.
+    /// let mut entities: OccupancyLayout<u32, i32> = OccupancyLayout::new();
+    /// let mut seed = 3u64;
+    /// let mut random = |n: u64| {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     (seed >> 33) % n
+    /// };
+    /// for id in 0..50 {
+    ///     entities.add(HexPosition(random(5) as i32, random(5) as i32), id);
+    /// }
+    /// for _ in 0..1000 {
+    ///     let id = random(60) as u32;
+    ///     let to = HexPosition(random(5) as i32, random(5) as i32);
+    ///     match entities.position_of(&id) {
+    ///         Some(from) if random(10) == 0 => assert!(entities.remove(from, &id)),
+    ///         Some(from) => assert!(entities.move_entity(&id, from, to)),
+    ///         None => assert!(entities.add(to, id)),
+    ///     }
+    /// }
+    ///
+    /// let mut count = 0;
+    /// for (pos, ids) in entities.iter() {
+    ///     assert!(!ids.is_empty());
+    ///     assert!(ids.iter().all(|id| entities.position_of(id) == Some(*pos)));
+    ///     count += ids.len();
+    /// }
+    /// assert_eq!(count, entities.len());
+    ///
+    /// entities.clear();
+    /// assert!(entities.is_empty());
+    /// assert_eq!(entities.iter().count(), 0);
+    /// assert!((0..60).all(|id| entities.position_of(&id).is_none()));
+    /// ```
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+        self.positions.clear();
+    }
+
+    /// Removes an entity from the entities of `pos`, and removes the tile if it is now empty.
+    fn remove_from_tile(&mut self, pos: HexPosition<T>, id: &Id) {
+        if let Some(ids) = self.tiles.get_mut(pos) {
+            ids.retain(|other| other != id);
+            if ids.is_empty() {
+                self.tiles.delete(pos);
+            }
+        }
+    }
+}
@@ -0,0 +1,714 @@
+//! # Conversions between hexagonal positions and pixels.
+//!
+//! A [PixelLayout] describes how the hexagonal grid is drawn on the screen: the orientation of the hexagons,
+//! their size and the pixel position of the origin. For more information, see the
+//! [documentation](https://www.redblobgames.com/grids/hexagons/#hex-to-pixel).
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+
+use crate::*;
+
+/// The tolerance, in hexagon units, used to detect segments going through corners or along edges.
+const EPSILON: f64 = 1e-4;
+
+/// The tolerance, in pixels, used to detect points on the border of a polygon.
+const PIXEL_EPSILON: f32 = 1e-4;
+
+/// Describes how a hexagonal grid is drawn in pixel space.
+///
+/// - `orientation`: the orientation of the hexagons.
+/// - `size`: the distance from the center of a hexagon to its corners, horizontally and vertically
+///   (the two values are equal for regular hexagons).
+/// - `origin`: the pixel position of the center of the hexagon `(0, 0)`.
+///
+/// The default layout has pointy-top hexagons of size 1 centered on the origin, which matches
+/// [HexPosition::to_pixel_coordinates].
+///
+/// # Example
+///
+/// ```
+/// use hexing::{pixel::PixelLayout, HexOrientation, HexPosition};
+///
+/// let layout = PixelLayout::new(HexOrientation::FlatTop, (10.0, 10.0), (100.0, 50.0));
+///
+/// assert_eq!(layout.hex_to_pixel(HexPosition(0, 0)), (100.0, 50.0));
+/// assert_eq!(layout.hex_to_pixel(HexPosition(2, 0)), (130.0, 50.0 + 10.0 * 3f32.sqrt()));
+/// assert_eq!(layout.pixel_to_hex::<i32>((131.0, 66.0)), HexPosition(2, 0));
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PixelLayout {
+    /// The orientation of the hexagons.
+    pub orientation: HexOrientation,
+
+    /// The distance from the center of a hexagon to its corners, horizontally and vertically.
+    pub size: (f32, f32),
+
+    /// The pixel position of the center of the hexagon `(0, 0)`.
+    pub origin: (f32, f32),
+}
+
+impl Default for PixelLayout {
+    fn default() -> Self {
+        Self::new(HexOrientation::PointyTop, (1.0, 1.0), (0.0, 0.0))
+    }
+}
+
+impl PixelLayout {
+    /// Creates a new [PixelLayout].
+    pub const fn new(orientation: HexOrientation, size: (f32, f32), origin: (f32, f32)) -> Self {
+        Self {
+            orientation,
+            size,
+            origin,
+        }
+    }
+
+    /// Returns the pixel position of the center of the given [HexPosition].
+    pub fn hex_to_pixel<T: Number>(&self, pos: HexPosition<T>) -> (f32, f32) {
+        let (x, y) = pos.to_oriented_pixel_coordinates(self.orientation);
+        (
+            x.mul_add(self.size.0, self.origin.0),
+            y.mul_add(self.size.1, self.origin.1),
+        )
+    }
+
+    /// Returns the fractional axial coordinates `(q, r)` of a pixel position.
+    ///
+    /// This is the exact inverse of [PixelLayout::hex_to_pixel], before rounding to the nearest hexagon.
+    pub fn pixel_to_fractional_hex(&self, point: (f32, f32)) -> (f32, f32) {
+        let x = (point.0 - self.origin.0) / self.size.0;
+        let y = (point.1 - self.origin.1) / self.size.1;
+        match self.orientation {
+            HexOrientation::PointyTop => ((3f32.sqrt() / 3.0).mul_add(x, -y / 3.0), 2.0 / 3.0 * y),
+            HexOrientation::FlatTop => (2.0 / 3.0 * x, (3f32.sqrt() / 3.0).mul_add(y, -x / 3.0)),
+        }
+    }
+
+    /// Returns the [HexPosition] of the hexagon containing the given pixel position.
+    pub fn pixel_to_hex<T: Number>(&self, point: (f32, f32)) -> HexPosition<T> {
+        let rounded = axial_round(self.pixel_to_fractional_hex(point));
+        HexPosition(
+            T::from_isize(rounded.0 as isize),
+            T::from_isize(rounded.1 as isize),
+        )
+    }
+
+    /// Returns the pixel positions of the six corners of the given [HexPosition], counterclockwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{pixel::PixelLayout, HexOrientation, HexPosition};
+    ///
+    /// let layout = PixelLayout::new(HexOrientation::FlatTop, (2.0, 2.0), (0.0, 0.0));
+    /// let corners = layout.hex_corners(HexPosition(0, 0));
+    ///
+    /// assert_eq!(corners[0], (2.0, 0.0));
+    /// assert!(corners.iter().all(|(x, y)| (x.hypot(*y) - 2.0).abs() < 1e-5));
+    /// ```
+    pub fn hex_corners<T: Number>(&self, pos: HexPosition<T>) -> [(f32, f32); 6] {
+        let center = self.hex_to_pixel(pos);
+        let offset = match self.orientation {
+            HexOrientation::PointyTop => -30f32,
+            HexOrientation::FlatTop => 0f32,
+        };
+        std::array::from_fn(|i| {
+            let (sin, cos) = 60f32.mul_add(i as f32, offset).to_radians().sin_cos();
+            (
+                cos.mul_add(self.size.0, center.0),
+                sin.mul_add(self.size.1, center.1),
+            )
+        })
+    }
+
+    /// Returns the pixel position of the given [HexVertex](crate::edge::HexVertex),
+    /// which is one of the [PixelLayout::hex_corners] of its three hexagons.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{edge::HexVertex, pixel::PixelLayout, HexDirection, HexOrientation, HexPosition};
+    ///
+    /// let layout = PixelLayout::new(HexOrientation::FlatTop, (10.0, 10.0), (5.0, 5.0));
+    /// let vertex = HexVertex::new(HexPosition(1, 2), HexDirection::UpLeft);
+    /// let (x, y) = layout.vertex_to_pixel(vertex);
+    ///
+    /// for hex in vertex.adjacent_hexes() {
+    ///     assert!(layout.hex_corners(hex).iter().any(|c| (c.0 - x).hypot(c.1 - y) < 1e-3));
+    /// }
+    /// ```
+    pub fn vertex_to_pixel<T: Number>(&self, vertex: edge::HexVertex<T>) -> (f32, f32) {
+        let (x, y) = vertex.adjacent_hexes().iter().fold((0.0, 0.0), |sum, hex| {
+            let (x, y) = self.hex_to_pixel_f64(hex.0.to_isize(), hex.1.to_isize());
+            (sum.0 + x, sum.1 + y)
+        });
+        ((x / 3.0) as f32, (y / 3.0) as f32)
+    }
+
+    /// Returns the pixel positions of the two ends of the given [HexEdge](crate::edge::HexEdge),
+    /// in the same order as [HexEdge::adjacent_vertices](crate::edge::HexEdge::adjacent_vertices).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{edge::HexEdge, pixel::PixelLayout, HexDirection, HexPosition};
+    ///
+    /// let layout = PixelLayout::default();
+    /// let [a, b] = layout.edge_to_pixel(HexEdge::new(HexPosition(0, 0), HexDirection::Right));
+    ///
+    /// // The right side of a pointy-top hexagon is vertical.
+    /// assert!((a.0 - 3f32.sqrt() / 2.0).abs() < 1e-5 && (b.0 - a.0).abs() < 1e-5);
+    /// assert!(((a.1 - b.1).abs() - 1.0).abs() < 1e-5);
+    /// ```
+    pub fn edge_to_pixel<T: Number>(&self, edge: edge::HexEdge<T>) -> [(f32, f32); 2] {
+        edge.adjacent_vertices()
+            .map(|vertex| self.vertex_to_pixel(vertex))
+    }
+
+    /// Returns the pixel position of the center of the given [HexPosition], as integers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{pixel::{PixelLayout, RoundingMode}, HexOrientation, HexPosition};
+    ///
+    /// let layout = PixelLayout::new(HexOrientation::PointyTop, (10.0, 10.0), (0.0, 0.0));
+    ///
+    /// assert_eq!(layout.hex_to_pixel_i32(HexPosition(1, 0), RoundingMode::Round), (17, 0));
+    /// assert_eq!(layout.hex_to_pixel_i32(HexPosition(-1, 0), RoundingMode::Floor), (-18, 0));
+    /// ```
+    pub fn hex_to_pixel_i32<T: Number>(
+        &self,
+        pos: HexPosition<T>,
+        rounding: RoundingMode,
+    ) -> (i32, i32) {
+        let (x, y) = self.hex_to_pixel_f64(pos.0.to_isize(), pos.1.to_isize());
+        (rounding.apply(x), rounding.apply(y))
+    }
+
+    /// Returns the pixel positions of the six corners of the given [HexPosition] as integers,
+    /// in the same order as [PixelLayout::hex_corners].
+    ///
+    /// Each corner is computed in the same way for the three hexagons sharing it,
+    /// so adjacent hexagons always have exactly the same corners and tile without seams.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{pixel::{PixelLayout, RoundingMode}, HexOrientation, HexPosition};
+    ///
+    /// for orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop] {
+    ///     for size in [7.0, 10.0, 13.3, 24.7] {
+    ///         let layout = PixelLayout::new(orientation, (size, size), (0.5, 0.25));
+    ///         for rounding in [RoundingMode::Floor, RoundingMode::Round] {
+    ///             // The corners already seen, with their exact position.
+    ///             let mut seen: Vec<((f32, f32), (i32, i32))> = Vec::new();
+    ///
+    ///             for q in 0..10 {
+    ///                 for r in 0..10 {
+    ///                     let pos = HexPosition(q, r);
+    ///                     let corners = layout.hex_polygon_i32(pos, rounding);
+    ///                     for (exact, corner) in layout.hex_corners(pos).into_iter().zip(corners) {
+    ///                         let shared = seen
+    ///                             .iter()
+    ///                             .find(|(other, _)| (other.0 - exact.0).hypot(other.1 - exact.1) < 0.01);
+    ///                         match shared {
+    ///                             Some((_, other)) => assert_eq!(*other, corner),
+    ///                             None => seen.push((exact, corner)),
+    ///                         }
+    ///                     }
+    ///                 }
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn hex_polygon_i32<T: Number>(
+        &self,
+        pos: HexPosition<T>,
+        rounding: RoundingMode,
+    ) -> [(i32, i32); 6] {
+        let directions = HexDirection::iter();
+        std::array::from_fn(|i| {
+            // The corner `i` is shared with the neighbors in these two directions,
+            // so it is the average of the three centers, taken in a canonical order.
+            let mut hexes = [
+                pos,
+                pos + directions[(6 - i) % 6].to_vector(),
+                pos + directions[(7 - i) % 6].to_vector(),
+            ]
+            .map(|hex| (hex.0.to_isize(), hex.1.to_isize()));
+            hexes.sort_unstable();
+
+            let (x, y) = hexes.iter().fold((0.0, 0.0), |sum, (q, r)| {
+                let (x, y) = self.hex_to_pixel_f64(*q, *r);
+                (sum.0 + x, sum.1 + y)
+            });
+            (rounding.apply(x / 3.0), rounding.apply(y / 3.0))
+        })
+    }
+
+    /// Returns the width and the height, in pixels, of the hexagons drawn with [PixelLayout::hex_polygon_i32].
+    ///
+    /// Depending on the rounding of the corners, some hexagons can be one pixel larger or smaller:
+    /// this is the size of the hexagon `(0, 0)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{pixel::{PixelLayout, RoundingMode}, HexOrientation};
+    ///
+    /// let layout = PixelLayout::new(HexOrientation::PointyTop, (10.0, 10.0), (0.0, 0.0));
+    /// assert_eq!(layout.hex_dimensions_i32(RoundingMode::Round), (18, 20));
+    ///
+    /// let layout = PixelLayout::new(HexOrientation::FlatTop, (32.0, 32.0), (0.0, 0.0));
+    /// assert_eq!(layout.hex_dimensions_i32(RoundingMode::Round), (64, 56));
+    /// ```
+    pub fn hex_dimensions_i32(&self, rounding: RoundingMode) -> (i32, i32) {
+        let corners = self.hex_polygon_i32(HexPosition(0, 0), rounding);
+        let (xs, ys): (Vec<i32>, Vec<i32>) = corners.into_iter().unzip();
+        let width = xs.iter().max().unwrap_or(&0) - xs.iter().min().unwrap_or(&0);
+        let height = ys.iter().max().unwrap_or(&0) - ys.iter().min().unwrap_or(&0);
+        (width, height)
+    }
+
+    /// Returns the pixel position of the center of the hexagon `(q, r)`, in double precision.
+    fn hex_to_pixel_f64(&self, q: isize, r: isize) -> (f64, f64) {
+        let (q, r) = (q as f64, r as f64);
+        let (x, y) = match self.orientation {
+            HexOrientation::PointyTop => (3f64.sqrt() * r.mul_add(0.5, q), 1.5 * r),
+            HexOrientation::FlatTop => (1.5 * q, 3f64.sqrt() * q.mul_add(0.5, r)),
+        };
+        (
+            x.mul_add(f64::from(self.size.0), f64::from(self.origin.0)),
+            y.mul_add(f64::from(self.size.1), f64::from(self.origin.1)),
+        )
+    }
+
+    /// Returns the hexagons crossed by the segment from `a` to `b` (pixel positions), in the order they are crossed.
+    ///
+    /// A hexagon is included as soon as the segment touches it, so a segment going through a corner includes
+    /// the two hexagons on each side of that corner, and a segment going along an edge includes the hexagons
+    /// on both sides of the edge.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{pixel::PixelLayout, HexPosition};
+    ///
+    /// let layout = PixelLayout::default();
+    /// let (a, b) = ((-0.3, 0.2), (5.9, 2.8));
+    /// let hexes: Vec<HexPosition<i32>> = layout.segment_to_hexes(a, b);
+    ///
+    /// // Every hexagon found by sampling the segment is crossed, in the same order.
+    /// let mut sampled: Vec<HexPosition<i32>> = Vec::new();
+    /// for i in 0..=10000 {
+    ///     let t = i as f32 / 10000.0;
+    ///     let hex = layout.pixel_to_hex((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+    ///     if sampled.last() != Some(&hex) {
+    ///         sampled.push(hex);
+    ///     }
+    /// }
+    /// let mut remaining = hexes.iter();
+    /// assert!(sampled.iter().all(|hex| remaining.any(|other| other == hex)));
+    ///
+    /// // A short segment only crosses one or two hexagons.
+    /// assert_eq!(layout.segment_to_hexes::<i32>((0.1, 0.0), (0.2, 0.1)), vec![HexPosition(0, 0)]);
+    ///
+    /// // A segment along the edge between (0, 0) and (1, 0) includes both hexagons.
+    /// let x = 3f32.sqrt() / 2.0;
+    /// let hexes: Vec<HexPosition<i32>> = layout.segment_to_hexes((x, -0.4), (x, 0.4));
+    /// assert!(hexes.contains(&HexPosition(0, 0)) && hexes.contains(&HexPosition(1, 0)));
+    /// ```
+    pub fn segment_to_hexes<T: Number>(&self, a: (f32, f32), b: (f32, f32)) -> Vec<HexPosition<T>> {
+        let start = self.pixel_to_fractional_hex(a);
+        let end = self.pixel_to_fractional_hex(b);
+        let start = (start.0 as f64, start.1 as f64);
+        let delta = (end.0 as f64 - start.0, end.1 as f64 - start.1);
+
+        let mut current = axial_round_f64(start);
+        let mut result = vec![current];
+        let mut seen = HashSet::from([current]);
+        let max_steps = 3 * (delta.0.abs() + delta.1.abs()) as usize + 8;
+
+        for _ in 0..max_steps {
+            for neighbor in edges_along_segment(current, start, delta) {
+                if seen.insert(neighbor) {
+                    result.push(neighbor);
+                }
+            }
+
+            let crossings: Vec<(f64, HexPosition<i64>)> =
+                side_crossings(current, start, delta).collect();
+            let exit = crossings
+                .iter()
+                .map(|(t, _)| *t)
+                .fold(f64::INFINITY, f64::min);
+            if exit > 1.0 + EPSILON {
+                break;
+            }
+
+            // When the segment goes through a corner, both hexagons next to it are touched:
+            // the segment goes on in the one it leaves last.
+            let mut crossed: Vec<(f64, HexPosition<i64>)> = crossings
+                .into_iter()
+                .filter(|(t, _)| *t <= exit + EPSILON)
+                .map(|(_, next)| {
+                    let next_exit = side_crossings(next, start, delta)
+                        .map(|(t, _)| t)
+                        .fold(f64::INFINITY, f64::min);
+                    (next_exit, next)
+                })
+                .collect();
+            crossed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let Some((_, next)) = crossed.last().copied() else {
+                break;
+            };
+            for (_, neighbor) in crossed {
+                if seen.insert(neighbor) {
+                    result.push(neighbor);
+                }
+            }
+            current = next;
+        }
+
+        result
+            .into_iter()
+            .map(|pos| HexPosition(T::from_isize(pos.0 as isize), T::from_isize(pos.1 as isize)))
+            .collect()
+    }
+
+    /// Returns the hexagons covered by the circle of the given `center` and `radius` (in pixels).
+    ///
+    /// With [RasterMode::CenterInside], a hexagon is covered if its center is in the circle, and with
+    /// [RasterMode::AnyOverlap], it is covered as soon as it touches the circle. In both modes,
+    /// the hexagon containing the center of the circle is always covered, even if the circle is very small.
+    /// The hexagons are returned from the closest to the farthest from the center of the circle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashSet;
+    ///
+    /// use hexing::{pixel::{PixelLayout, RasterMode}, HexPosition};
+    ///
+    /// let layout = PixelLayout::default();
+    /// let corner = layout.hex_corners(HexPosition(0, 0))[0];
+    ///
+    /// for (center, radius) in [((0.3, 0.2), 0.1), ((0.3, 0.2), 2.5), (corner, 1.0), (corner, 0.01)] {
+    ///     let hexes: HashSet<HexPosition<i32>> =
+    ///         layout.circle_to_hexes(center, radius, RasterMode::AnyOverlap).into_iter().collect();
+    ///
+    ///     // Sample the circle, and a slightly larger one.
+    ///     let (mut inside, mut around) = (HashSet::new(), HashSet::new());
+    ///     let extended = radius + 0.05;
+    ///     for i in -200..=200 {
+    ///         for j in -200..=200 {
+    ///             let (x, y) = (i as f32 / 200.0 * extended, j as f32 / 200.0 * extended);
+    ///             let hex = layout.pixel_to_hex((center.0 + x, center.1 + y));
+    ///             if x.hypot(y) <= radius {
+    ///                 inside.insert(hex);
+    ///             }
+    ///             if x.hypot(y) <= extended {
+    ///                 around.insert(hex);
+    ///             }
+    ///         }
+    ///     }
+    ///     assert!(inside.is_subset(&hexes) && hexes.is_subset(&around));
+    /// }
+    ///
+    /// // A small circle on a corner touches the three hexagons around it.
+    /// assert_eq!(layout.circle_to_hexes::<i32>(corner, 0.01, RasterMode::AnyOverlap).len(), 3);
+    /// assert_eq!(layout.circle_to_hexes::<i32>(corner, 0.01, RasterMode::CenterInside).len(), 1);
+    ///
+    /// let hexes: Vec<HexPosition<i32>> = layout.circle_to_hexes((0.0, 0.0), 1.8, RasterMode::CenterInside);
+    /// assert_eq!(hexes.len(), 7);
+    /// ```
+    pub fn circle_to_hexes<T: Number>(
+        &self,
+        center: (f32, f32),
+        radius: f32,
+        mode: RasterMode,
+    ) -> Vec<HexPosition<T>> {
+        let containing: HexPosition<T> = self.pixel_to_hex(center);
+
+        containing
+            .spiral(self.covering_range(radius))
+            .filter(|pos| {
+                if *pos == containing {
+                    return true;
+                }
+                match mode {
+                    RasterMode::CenterInside => {
+                        let (x, y) = self.hex_to_pixel(*pos);
+                        (x - center.0).hypot(y - center.1) <= radius
+                    }
+                    RasterMode::AnyOverlap => {
+                        let corners = self.hex_corners(*pos);
+                        (0..6).any(|i| {
+                            segment_distance(center, corners[i], corners[(i + 1) % 6]) <= radius
+                        })
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the hexagons covered by the polygon of the given `vertices` (in pixels).
+    ///
+    /// The polygon can be concave, but its edges shouldn't cross each other. With [RasterMode::CenterInside],
+    /// a hexagon is covered if its center is in the polygon (or on its border), and with [RasterMode::AnyOverlap],
+    /// it is covered as soon as it touches the polygon. A polygon too small to contain any center covers,
+    /// in both modes, at least the hexagon containing the average of its vertices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{pixel::{PixelLayout, RasterMode}, HexPosition};
+    ///
+    /// let layout = PixelLayout::default();
+    /// let polygon = |vertices: &[(i32, i32)]| -> Vec<(f32, f32)> {
+    ///     vertices.iter().map(|(q, r)| layout.hex_to_pixel(HexPosition(*q, *r))).collect()
+    /// };
+    ///
+    /// // A triangle with its vertices on the centers of hexagons.
+    /// let triangle = polygon(&[(0, 0), (4, 0), (0, 4)]);
+    /// let hexes = layout.polygon_to_hexes::<i32>(&triangle, RasterMode::CenterInside);
+    /// assert_eq!(hexes.len(), 15);
+    /// assert!(hexes.contains(&HexPosition(0, 0)) && hexes.contains(&HexPosition(2, 2)));
+    /// assert!(!hexes.contains(&HexPosition(3, 2)));
+    ///
+    /// // A concave L-shape.
+    /// let l_shape = polygon(&[(0, 0), (4, 0), (4, 1), (1, 1), (1, 4), (0, 4)]);
+    /// let hexes = layout.polygon_to_hexes::<i32>(&l_shape, RasterMode::CenterInside);
+    /// assert_eq!(hexes.len(), 16);
+    /// assert!(!hexes.contains(&HexPosition(3, 3)));
+    ///
+    /// // The edges follow the lines between the centers, so they don't touch any other hexagon.
+    /// assert_eq!(layout.polygon_to_hexes::<i32>(&l_shape, RasterMode::AnyOverlap), hexes);
+    ///
+    /// // A small triangle over the edge between two hexagons.
+    /// let x = 3f32.sqrt() / 2.0;
+    /// let small = [(x - 0.1, 0.0), (x + 0.1, 0.0), (x, 0.1)];
+    /// assert_eq!(layout.polygon_to_hexes::<i32>(&small, RasterMode::CenterInside).len(), 1);
+    /// assert_eq!(layout.polygon_to_hexes::<i32>(&small, RasterMode::AnyOverlap).len(), 2);
+    /// ```
+    pub fn polygon_to_hexes<T: Number>(
+        &self,
+        vertices: &[(f32, f32)],
+        mode: RasterMode,
+    ) -> HashSet<HexPosition<T>> {
+        if vertices.is_empty() {
+            return HashSet::new();
+        }
+
+        let count = vertices.len() as f32;
+        let average = vertices.iter().fold((0.0, 0.0), |sum, vertex| {
+            (sum.0 + vertex.0, sum.1 + vertex.1)
+        });
+        let average = (average.0 / count, average.1 / count);
+        let radius = vertices
+            .iter()
+            .map(|vertex| (vertex.0 - average.0).hypot(vertex.1 - average.1))
+            .fold(0.0, f32::max);
+
+        let containing: HexPosition<T> = self.pixel_to_hex(average);
+        let mut result: HashSet<HexPosition<T>> = containing
+            .spiral(self.covering_range(radius))
+            .filter(|pos| {
+                if point_in_polygon(self.hex_to_pixel(*pos), vertices) {
+                    return true;
+                }
+                if mode == RasterMode::CenterInside {
+                    return false;
+                }
+
+                let corners = self.hex_corners(*pos);
+                vertices
+                    .iter()
+                    .any(|vertex| point_in_polygon(*vertex, &corners))
+                    || (0..corners.len()).any(|i| {
+                        (0..vertices.len()).any(|j| {
+                            segments_intersect(
+                                corners[i],
+                                corners[(i + 1) % corners.len()],
+                                vertices[j],
+                                vertices[(j + 1) % vertices.len()],
+                            )
+                        })
+                    })
+            })
+            .collect();
+
+        if result.is_empty() {
+            result.insert(containing);
+        }
+        result
+    }
+
+    /// Returns a radius, in the grid, large enough for the hexagonal area around the hexagon containing a point
+    /// to contain every hexagon touching the circle of the given radius (in pixels) around that point.
+    fn covering_range(&self, radius: f32) -> usize {
+        let (min_size, max_size) = (
+            self.size.0.abs().min(self.size.1.abs()),
+            self.size.0.abs().max(self.size.1.abs()),
+        );
+        // Two hexagons at a distance `n` in the grid have their centers at least `1.5 * n * min_size` pixels apart.
+        ((radius + max_size) / (1.5 * min_size)).ceil() as usize + 1
+    }
+}
+
+/// How hexagons are selected when converting a pixel-space shape into hexagons.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum RasterMode {
+    /// A hexagon is selected if its center is in the shape.
+    #[default]
+    CenterInside,
+
+    /// A hexagon is selected if it overlaps the shape, even partially.
+    AnyOverlap,
+}
+
+/// How pixel positions are converted into integers.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum RoundingMode {
+    /// The position is rounded down.
+    Floor,
+
+    /// The position is rounded to the nearest integer.
+    #[default]
+    Round,
+}
+
+impl RoundingMode {
+    /// Converts a pixel coordinate into an integer with this rounding mode.
+    const fn apply(self, value: f64) -> i32 {
+        match self {
+            Self::Floor => value.floor() as i32,
+            Self::Round => value.round() as i32,
+        }
+    }
+}
+
+/// Checks if `point` is inside the polygon of the given `vertices`, or on its border.
+fn point_in_polygon(point: (f32, f32), vertices: &[(f32, f32)]) -> bool {
+    let mut inside = false;
+    for i in 0..vertices.len() {
+        let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+        if segment_distance(point, a, b) <= PIXEL_EPSILON {
+            return true;
+        }
+        if (a.1 > point.1) != (b.1 > point.1)
+            && point.0 < (b.0 - a.0) * (point.1 - a.1) / (b.1 - a.1) + a.0
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Checks if the segment from `a` to `b` and the segment from `c` to `d` cross or touch each other.
+fn segments_intersect(a: (f32, f32), b: (f32, f32), c: (f32, f32), d: (f32, f32)) -> bool {
+    let cross = |o: (f32, f32), p: (f32, f32), q: (f32, f32)| {
+        (p.0 - o.0).mul_add(q.1 - o.1, -(p.1 - o.1) * (q.0 - o.0))
+    };
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+
+    segment_distance(a, c, d) <= PIXEL_EPSILON
+        || segment_distance(b, c, d) <= PIXEL_EPSILON
+        || segment_distance(c, a, b) <= PIXEL_EPSILON
+        || segment_distance(d, a, b) <= PIXEL_EPSILON
+}
+
+/// Returns the distance between `point` and the segment from `a` to `b`.
+fn segment_distance(point: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = dx.mul_add(dx, dy * dy);
+    let t = if length > 0.0 {
+        ((point.0 - a.0).mul_add(dx, (point.1 - a.1) * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (t.mul_add(dx, a.0) - point.0).hypot(t.mul_add(dy, a.1) - point.1)
+}
+
+/// Returns the parameters at which the segment `start + t * delta` (in fractional axial coordinates)
+/// leaves the hexagon `center` through each of its sides, with the hexagon on the other side.
+fn side_crossings(
+    center: HexPosition<i64>,
+    start: (f64, f64),
+    delta: (f64, f64),
+) -> impl Iterator<Item = (f64, HexPosition<i64>)> {
+    side_functions(center, start, delta).filter_map(move |(value, slope, direction)| {
+        if slope > 0.0 {
+            Some(((1.0 - value) / slope, center + direction))
+        } else if slope < 0.0 {
+            Some(((-1.0 - value) / slope, center - direction))
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the hexagons sharing with `center` an edge along which the segment `start + t * delta` goes.
+fn edges_along_segment(
+    center: HexPosition<i64>,
+    start: (f64, f64),
+    delta: (f64, f64),
+) -> impl Iterator<Item = HexPosition<i64>> {
+    side_functions(center, start, delta).filter_map(move |(value, slope, direction)| {
+        if slope.abs() >= EPSILON {
+            None
+        } else if (value - 1.0).abs() < EPSILON {
+            Some(center + direction)
+        } else if (value + 1.0).abs() < EPSILON {
+            Some(center - direction)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns, for each pair of opposite sides of the hexagon `center`, the value at `t = 0` and the slope
+/// along the segment `start + t * delta` of the function that is `1` on one side and `-1` on the other,
+/// with the direction of the hexagon behind the side where it is `1`.
+fn side_functions(
+    center: HexPosition<i64>,
+    start: (f64, f64),
+    delta: (f64, f64),
+) -> impl Iterator<Item = (f64, f64, HexPosition<i64>)> {
+    let (q, r) = (start.0 - center.0 as f64, start.1 - center.1 as f64);
+    let s = -q - r;
+    let (dq, dr) = delta;
+    let ds = -dq - dr;
+
+    [
+        (q - r, dq - dr, HexPosition(1, -1)),
+        (r - s, dr - ds, HexPosition(0, 1)),
+        (s - q, ds - dq, HexPosition(-1, 0)),
+    ]
+    .into_iter()
+}
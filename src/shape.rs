@@ -0,0 +1,235 @@
+//! # Multi-hexagon shapes.
+//!
+//! A [HexShape] is a set of offsets from an anchor position, used to represent everything
+//! that covers more than one hexagon (large units, buildings, ...).
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::*;
+
+/// A shape made of several hexagons, stored as offsets from an anchor position.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{shape::HexShape, HexPosition};
+///
+/// let line = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0)]);
+///
+/// let covered: Vec<_> = line.translated_to(HexPosition(3, -1)).collect();
+/// assert_eq!(covered, vec![HexPosition(3, -1), HexPosition(4, -1), HexPosition(5, -1)]);
+/// ```
+///
+/// A deserialized shape goes through [HexShape::new], so its offsets are normalized too.
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use hexing::{shape::HexShape, HexPosition};
+/// use serde::de::{value::{Error, SeqDeserializer}, Deserialize, IntoDeserializer};
+///
+/// let offsets: SeqDeserializer<_, Error> = vec![vec![1, 0], vec![0, 0], vec![1, 0]].into_deserializer();
+/// let shape = HexShape::<i32>::deserialize(offsets).unwrap();
+/// assert_eq!(shape, HexShape::new([HexPosition(0, 0), HexPosition(1, 0)]));
+/// # }
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Vec<HexPosition<T>>", into = "Vec<HexPosition<T>>")
+)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HexShape<T: Number>(Vec<HexPosition<T>>);
+
+impl<T: Number> HexShape<T> {
+    /// Creates a new [HexShape] from its offsets from the anchor. Duplicated offsets are removed.
+    ///
+    /// The offsets are stored sorted, so two shapes covering the same offsets are equal
+    /// whatever the order they were given in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let shape = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(0, 0)]);
+    /// assert_eq!(shape.len(), 2);
+    /// ```
+    pub fn new(offsets: impl IntoIterator<Item = HexPosition<T>>) -> Self {
+        let mut result: Vec<HexPosition<T>> = offsets.into_iter().collect();
+        result.sort_by_key(|offset| (offset.0.to_isize(), offset.1.to_isize()));
+        result.dedup();
+        Self(result)
+    }
+
+    /// Creates a [HexShape] covering only its anchor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let shape: HexShape<i32> = HexShape::single();
+    /// assert_eq!(shape.offsets(), &[HexPosition(0, 0)]);
+    /// ```
+    pub fn single() -> Self {
+        Self(vec![HexPosition::ORIGIN])
+    }
+
+    /// Creates a [HexShape] covering every hexagon within `radius` of its anchor.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let blob: HexShape<i32> = HexShape::hexagon(1);
+    /// assert_eq!(blob.len(), 7);
+    /// assert_eq!(blob, HexShape::new(HexPosition::ORIGIN.spiral(1)));
+    /// assert_eq!(blob.rotated(1), blob);
+    /// ```
+    pub fn hexagon(radius: usize) -> Self {
+        Self::new(HexPosition::ORIGIN.spiral(radius))
+    }
+
+    /// Returns the offsets of the shape from its anchor.
+    pub fn offsets(&self) -> &[HexPosition<T>] {
+        &self.0
+    }
+
+    /// Returns the number of hexagons covered by the shape.
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the shape doesn't cover any hexagon.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the shape rotated by 60 degrees `n` times around its anchor.
+    /// Like [HexPosition::rotation], the rotation is counterclockwise, and `n` can be negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let line = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0)]);
+    /// let rotated = line.rotated(1);
+    ///
+    /// assert_eq!(rotated.offsets(), &[HexPosition(0, 0), HexPosition(0, 1), HexPosition(0, 2)]);
+    /// assert_eq!(rotated.rotated(-1), line);
+    ///
+    /// // Rotating six times gives the original shape back.
+    /// let triomino = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(0, 1)]);
+    /// let mut shape = triomino.clone();
+    /// for _ in 0..6 {
+    ///     shape = shape.rotated(1);
+    /// }
+    /// assert_eq!(shape, triomino);
+    /// ```
+    pub fn rotated(&self, n: i32) -> Self {
+        let n = n.rem_euclid(6);
+        Self::new(self.0.iter().map(|offset| offset.rotation(n)))
+    }
+
+    /// Returns the mirror image of the shape, across the axis going through its anchor in the [HexDirection::Right] direction.
+    /// Combined with [HexShape::rotated], this gives every orientation of the shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let shape = HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(0, 1)]);
+    ///
+    /// assert_eq!(
+    ///     shape.reflected(),
+    ///     HexShape::new([HexPosition(0, 0), HexPosition(1, 0), HexPosition(1, -1)])
+    /// );
+    /// assert_eq!(shape.reflected().reflected(), shape);
+    /// ```
+    pub fn reflected(&self) -> Self {
+        Self::new(
+            self.0
+                .iter()
+                .map(|offset| HexPosition(offset.0 + offset.1, -offset.1)),
+        )
+    }
+
+    /// Returns the same shape, translated so that its smallest offset (in `(q, r)` order) is the anchor.
+    ///
+    /// Two shapes that only differ by a translation have the same normalized shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let a = HexShape::new([HexPosition(0, 0), HexPosition(1, 0)]);
+    /// let b = HexShape::new([HexPosition(3, -2), HexPosition(4, -2)]);
+    ///
+    /// assert_ne!(a, b);
+    /// assert_eq!(a.normalized(), b.normalized());
+    /// ```
+    pub fn normalized(&self) -> Self {
+        let first = self.0.first().copied().unwrap_or(HexPosition::ORIGIN);
+        Self::new(self.0.iter().map(|offset| *offset - first))
+    }
+
+    /// Returns an iterator over the hexagons covered by the shape when its anchor is at `anchor`.
+    pub fn translated_to(
+        &self,
+        anchor: HexPosition<T>,
+    ) -> impl Iterator<Item = HexPosition<T>> + '_ {
+        self.0.iter().map(move |offset| anchor + *offset)
+    }
+
+    /// Returns `true` if the two shapes share at least one hexagon, when anchored at `anchor` and `other_anchor`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{shape::HexShape, HexPosition};
+    ///
+    /// let blob: HexShape<i32> = HexShape::hexagon(1);
+    ///
+    /// assert!(blob.overlaps(&blob, HexPosition(0, 0), HexPosition(2, 0)));
+    /// assert!(!blob.overlaps(&blob, HexPosition(0, 0), HexPosition(3, 0)));
+    /// ```
+    pub fn overlaps(
+        &self,
+        other: &Self,
+        anchor: HexPosition<T>,
+        other_anchor: HexPosition<T>,
+    ) -> bool {
+        let covered: Vec<_> = other.translated_to(other_anchor).collect();
+        self.translated_to(anchor).any(|pos| covered.contains(&pos))
+    }
+}
+
+impl<T: Number> From<Vec<HexPosition<T>>> for HexShape<T> {
+    fn from(offsets: Vec<HexPosition<T>>) -> Self {
+        Self::new(offsets)
+    }
+}
+
+impl<T: Number> From<HexShape<T>> for Vec<HexPosition<T>> {
+    fn from(shape: HexShape<T>) -> Self {
+        shape.0
+    }
+}
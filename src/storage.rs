@@ -0,0 +1,190 @@
+//! # Binary storage of layouts.
+//!
+//! This module contains the [TileData] trait, used by [HexLayout::save](crate::layout::HexLayout::save),
+//! [HexLayout::save_compact](crate::layout::HexLayout::save_compact) and [HexLayout::load](crate::layout::HexLayout::load)
+//! to write and read the data of each position.
+//!
+//! Every saved layout starts with a header byte giving its format, so [HexLayout::load](crate::layout::HexLayout::load)
+//! reads both formats. All the numbers are written in little-endian.
+//!
+//! ## Copyright (C) 2024  CoCoSol
+//!
+//! This program is free software: you can redistribute it and/or modify
+//! it under the terms of the GNU General Public License as published by
+//! the Free Software Foundation, either version 3 of the License, or
+//! (at your option) any later version.
+//!
+//! This program is distributed in the hope that it will be useful,
+//! but WITHOUT ANY WARRANTY; without even the implied warranty of
+//! MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//! GNU General Public License for more details.
+//!
+//! You should have received a copy of the GNU General Public License
+//! along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use std::io::{self, Read, Write};
+
+use utils::HexBound;
+
+use crate::*;
+
+/// The header byte of a layout saved with [HexLayout::save](crate::layout::HexLayout::save).
+pub(crate) const PLAIN_FORMAT: u8 = 1;
+
+/// The header byte of a layout saved with [HexLayout::save_compact](crate::layout::HexLayout::save_compact).
+pub(crate) const COMPACT_FORMAT: u8 = 2;
+
+/// Data that can be written to and read from a binary stream, to save a [HexLayout](crate::layout::HexLayout).
+///
+/// It is implemented for `bool` and all the primitive numbers.
+///
+/// # Example
+///
+/// ```
+/// use hexing::storage::TileData;
+///
+/// let mut bytes = Vec::new();
+/// 1.5f32.write_to(&mut bytes).unwrap();
+/// assert_eq!(bytes.len(), 4);
+/// assert_eq!(f32::read_from(&mut bytes.as_slice()).unwrap(), 1.5);
+/// ```
+pub trait TileData: Sized {
+    /// Writes the data to `writer`.
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Reads data written by [TileData::write_to] from `reader`.
+    fn read_from<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+/// Implements [TileData] for primitive numbers.
+macro_rules! impl_tile_data {
+    ($($t:ty),*) => {
+        $(
+            impl TileData for $t {
+                fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$t>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$t>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_tile_data!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64
+);
+
+impl TileData for bool {
+    fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        u8::from(*self).write_to(writer)
+    }
+
+    fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::read_from(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid boolean")),
+        }
+    }
+}
+
+/// Writes a position, each coordinate as an `i64`.
+pub(crate) fn write_position<T: Number, W: Write>(
+    writer: &mut W,
+    pos: HexPosition<T>,
+) -> io::Result<()> {
+    (pos.0.to_isize() as i64).write_to(writer)?;
+    (pos.1.to_isize() as i64).write_to(writer)
+}
+
+/// Reads a position written by [write_position].
+pub(crate) fn read_position<T: Number, R: Read>(reader: &mut R) -> io::Result<HexPosition<T>> {
+    let q = i64::read_from(reader)?;
+    let r = i64::read_from(reader)?;
+    Ok(HexPosition(
+        T::from_isize(q as isize),
+        T::from_isize(r as isize),
+    ))
+}
+
+/// Writes a set of flags, eight per byte.
+pub(crate) fn write_bits<W: Write>(writer: &mut W, bits: &[bool]) -> io::Result<()> {
+    let bytes: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (i, bit)| byte | (u8::from(*bit) << i))
+        })
+        .collect();
+    writer.write_all(&bytes)
+}
+
+/// Reads `len` flags written by [write_bits].
+///
+/// The buffer grows with the bytes actually read, so a wrong `len` fails at the end of the data
+/// instead of allocating a huge buffer first.
+pub(crate) fn read_bits<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<bool>> {
+    let size = len.div_ceil(8);
+    let mut bytes = Vec::new();
+    reader.take(size as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != size {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok((0..len)
+        .map(|i| bytes[i / 8] & (1 << (i % 8)) != 0)
+        .collect())
+}
+
+/// Reads the bounding hex of a layout of `len` positions, written as its center and its radius.
+///
+/// The bound is rejected if it can't hold `len` positions, if its number of positions overflows,
+/// or if some of its positions can't be represented with `T`.
+pub(crate) fn read_bound<T: Number, R: Read>(
+    reader: &mut R,
+    len: usize,
+) -> io::Result<HexBound<T>> {
+    let q = i64::read_from(reader)?;
+    let r = i64::read_from(reader)?;
+    let radius = u64::read_from(reader)?;
+
+    let invalid_bound = || invalid_data("invalid bounding hex");
+    let radius = isize::try_from(radius).map_err(|_| invalid_bound())?;
+    let bound_len = radius
+        .checked_add(1)
+        .and_then(|n| n.checked_mul(radius))
+        .and_then(|n| n.checked_mul(3))
+        .and_then(|n| n.checked_add(1))
+        .ok_or_else(invalid_bound)?;
+    if (bound_len as usize) < len {
+        return Err(invalid_bound());
+    }
+
+    let (q, r) = (q as isize, r as isize);
+    for coordinate in [q, r] {
+        for extreme in [
+            coordinate.checked_sub(radius),
+            coordinate.checked_add(radius),
+        ] {
+            match extreme {
+                Some(x) if T::from_isize(x).to_isize() == x => {}
+                _ => return Err(invalid_bound()),
+            }
+        }
+    }
+    Ok(HexBound::new(
+        HexPosition(T::from_isize(q), T::from_isize(r)),
+        radius as usize,
+    ))
+}
+
+/// Creates an error for malformed saved data.
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -15,7 +15,10 @@
 //! You should have received a copy of the GNU General Public License
 //! along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use std::cmp::{max, min};
+
 use super::*;
+use pixel::PixelLayout;
 
 /// An utility function for axial round.
 /// for more information, see the [documentation](https://www.redblobgames.com/grids/hexagons/#rounding).
@@ -31,20 +34,64 @@ use super::*;
 /// ```
 pub fn axial_round(pos: (f32, f32)) -> HexPosition<i32> {
     let (q, r) = pos;
-    let s = -q - r;
+    cube_round(q, r, -q - r)
+}
+
+/// An utility function for cube round.
+///
+/// The fractional cube coordinates `(q, r, s)` are rounded to the nearest hexagon, which is returned in axial coordinates.
+/// The coordinate with the largest rounding error is recomputed from the two others, so that `q + r + s = 0`.
+/// for more information, see the [documentation](https://www.redblobgames.com/grids/hexagons/#rounding).
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexPosition;
+///
+/// let rounded = hexing::utils::cube_round(1.4, -0.3, -1.1);
+/// assert_eq!(rounded, HexPosition(1, 0));
+/// ```
+pub fn cube_round(q: f32, r: f32, s: f32) -> HexPosition<i32> {
+    // Every `f32` is exactly a `f64`, and so are the rounding errors, so the result is the same as in `f32`.
+    let (q, r) = round_cube(q as f64, r as f64, s as f64);
+    HexPosition(q as i32, r as i32)
+}
 
+/// Rounds the fractional cube coordinates `(q, r, s)` to the nearest hexagon, see [cube_round],
+/// and returns its axial coordinates as integral floats.
+fn round_cube(q: f64, r: f64, s: f64) -> (f64, f64) {
     let (rq, rr, rs) = (q.round(), r.round(), s.round());
     let (q_diff, r_diff, s_diff) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
 
     if q_diff > r_diff && q_diff > s_diff {
-        HexPosition((-rr - rs) as i32, rr as i32)
+        (-rr - rs, rr)
     } else if r_diff > s_diff {
-        HexPosition(rq as i32, (-rq - rs) as i32)
+        (rq, -rq - rs)
     } else {
-        HexPosition(rq as i32, rr as i32)
+        (rq, rr)
     }
 }
 
+/// An utility function for axial round, in double precision.
+///
+/// This is the same as [axial_round], but it keeps the precision of `f64` coordinates,
+/// which is needed for positions too far from the origin to be represented exactly by a `f32`.
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexPosition;
+///
+/// let position = (16_777_217.25, -0.1);
+/// let rounded = hexing::utils::axial_round_f64(position);
+/// assert_eq!(rounded, HexPosition(16_777_217, 0));
+/// ```
+pub fn axial_round_f64(pos: (f64, f64)) -> HexPosition<i64> {
+    let (q, r) = pos;
+    let (q, r) = round_cube(q, r, -q - r);
+    HexPosition(q as i64, r as i64)
+}
+
 /// An utility function for lerp between two hexagonal positions.
 /// for more information, see the [documentation](https://www.redblobgames.com/grids/hexagons/#line-drawing).
 ///
@@ -65,6 +112,26 @@ pub fn hexagonal_lerp<T: Number>(a: HexPosition<T>, b: HexPosition<T>, t: f32) -
     (lerp(a.0, b.0, t), lerp(a.1, b.1, t))
 }
 
+/// An utility function for lerp between two hexagonal positions, in double precision.
+/// This is the same as [hexagonal_lerp], but the result keeps the precision of `f64`.
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexPosition;
+///
+/// let position = HexPosition(16_777_216_i64, 0);
+/// let other_position = HexPosition(16_777_218, 0);
+/// let lerp = hexing::utils::hexagonal_lerp_f64(position, other_position, 0.5);
+/// assert_eq!(lerp, (16_777_217.0, 0.0));
+/// ```
+pub fn hexagonal_lerp_f64<T: Number>(a: HexPosition<T>, b: HexPosition<T>, t: f64) -> (f64, f64) {
+    let a = (a.0.to_isize() as f64, a.1.to_isize() as f64);
+    let b = (b.0.to_isize() as f64, b.1.to_isize() as f64);
+
+    (a.0.mul_add(1.0 - t, b.0 * t), a.1.mul_add(1.0 - t, b.1 * t))
+}
+
 /// An utility function for linear interpolation.
 /// for more information, see the [documentation](https://en.wikipedia.org/wiki/Linear_interpolation).
 ///
@@ -124,6 +191,23 @@ pub fn neighbors<T: Number>(pos: HexPosition<T>) -> Vec<HexPosition<T>> {
     neighbors
 }
 
+/// An utility function for getting the neighbors of a hexagonal position, without allocating.
+/// The neighbors are in the order of [HexDirection::iter], like with [neighbors].
+///
+/// # Example
+///
+/// ```
+/// use hexing::{utils::{neighbors, neighbors_array}, HexDirection, HexPosition};
+///
+/// let position = HexPosition(2, -1);
+/// let neighbors_array = neighbors_array(position);
+/// assert_eq!(neighbors_array[0], position + HexDirection::Right.to_vector());
+/// assert_eq!(neighbors_array.to_vec(), neighbors(position));
+/// ```
+pub fn neighbors_array<T: Number>(pos: HexPosition<T>) -> [HexPosition<T>; 6] {
+    HexDirection::iter().map(|direction| pos + direction.to_vector())
+}
+
 /// The `HexBound` struct defines a hexagonal area centered around a specific hexagonal position.
 ///
 /// This struct encapsulates a central position (`HexPosition<T>`) and a radius (`usize`).
@@ -144,6 +228,8 @@ pub fn neighbors<T: Number>(pos: HexPosition<T>) -> Vec<HexPosition<T>> {
 /// let pos = HexPosition::new(1, -1);
 /// assert!(bound.contains(pos));
 /// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct HexBound<T: Number>(HexPosition<T>, usize);
 
 impl<T: Number> HexBound<T> {
@@ -219,11 +305,1129 @@ impl<T: Number> HexBound<T> {
     ///
     /// let pos_outside = HexPosition::new(3, -3);
     /// assert!(!bound.contains(pos_outside));
+    ///
+    /// // The position can also be given as a tuple or an array.
+    /// assert!(bound.contains((1, -1)));
+    /// assert!(!bound.contains([3, -3]));
     /// ```
-    pub fn contains(&self, pos: HexPosition<T>) -> bool {
-        if self.0.distance(pos).to_isize() > self.1 as isize {
+    pub fn contains(&self, pos: impl ToHexPosition<T>) -> bool {
+        if self.0.distance(pos.to_hex()).to_isize() > self.1 as isize {
             return false;
         }
         true
     }
+
+    /// Returns the number of positions within the hexagonal area.
+    ///
+    /// # Example
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// assert_eq!(HexBound::new(HexPosition::new(0, 0), 0).len(), 1);
+    /// assert_eq!(HexBound::new(HexPosition::new(0, 0), 2).len(), 19);
+    /// ```
+    pub const fn len(&self) -> usize {
+        3 * self.1 * (self.1 + 1) + 1
+    }
+
+    /// Returns `false`: a hexagonal area always contains at least its center.
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the index of a position in a dense storage of the hexagonal area, in the given order.
+    ///
+    /// The indices go from `0` to `len() - 1`, each position of the area having a different one.
+    /// Returns `None` if the position is outside the area.
+    ///
+    /// # Arguments
+    /// - `pos`: The hexagonal position to index.
+    /// - `indexing`: The order of the positions in the storage.
+    ///
+    /// # Example
+    /// ```
+    /// use hexing::{utils::{HexBound, HexIndexing}, HexPosition};
+    ///
+    /// let bound = HexBound::new(HexPosition::new(2, -1), 5);
+    ///
+    /// for indexing in [HexIndexing::RowMajor, HexIndexing::Spiral] {
+    ///     // The indices follow the order of `iter`, and `position_at` is the reverse of `index_of`.
+    ///     for (index, pos) in bound.iter(indexing).enumerate() {
+    ///         assert_eq!(bound.index_of(pos, indexing), Some(index));
+    ///         assert_eq!(bound.position_at(index, indexing), Some(pos));
+    ///     }
+    ///     assert_eq!(bound.iter(indexing).count(), bound.len());
+    ///     assert_eq!(bound.index_of(HexPosition::new(8, -1), indexing), None);
+    ///     assert_eq!(bound.position_at(bound.len(), indexing), None);
+    /// }
+    ///
+    /// // The spiral order is the order of `HexPosition::spiral`.
+    /// assert!(bound.iter(HexIndexing::Spiral).eq(HexPosition::new(2, -1).spiral(5)));
+    /// ```
+    pub fn index_of(&self, pos: impl ToHexPosition<T>, indexing: HexIndexing) -> Option<usize> {
+        let pos = pos.to_hex();
+        if !self.contains(pos) {
+            return None;
+        }
+
+        let radius = self.1 as isize;
+        let offset = pos - self.0;
+        let (q, r) = (offset.0.to_isize(), offset.1.to_isize());
+
+        let index = match indexing {
+            HexIndexing::RowMajor => return row_major_index(radius, q, r),
+            HexIndexing::Spiral => {
+                let k = self.0.distance(pos).to_isize();
+                if k == 0 {
+                    return Some(0);
+                }
+                // The index in the ring, which starts at `(-k, k)` and goes on like `HexPosition::ring`.
+                let in_ring = if r > 0 && (r == k || q >= 0) {
+                    q + k
+                } else if q == k && r <= 0 && r > -k {
+                    2 * k - r
+                } else if r < 0 && (r == -k || q <= 0) {
+                    4 * k - q
+                } else {
+                    5 * k + r
+                };
+                1 + 3 * k * (k - 1) + in_ring
+            }
+        };
+        Some(index as usize)
+    }
+
+    /// Returns the position at an index of a dense storage of the hexagonal area, in the given order.
+    ///
+    /// This is the reverse of [HexBound::index_of]. Returns `None` if the index is not lower than `len()`.
+    ///
+    /// # Arguments
+    /// - `index`: The index in the storage.
+    /// - `indexing`: The order of the positions in the storage.
+    pub fn position_at(&self, index: usize, indexing: HexIndexing) -> Option<HexPosition<T>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let radius = self.1 as isize;
+        let index = index as isize;
+        let (q, r) = match indexing {
+            HexIndexing::RowMajor => {
+                let mut row = 0;
+                while rows_before(radius, row + 1) <= index {
+                    row += 1;
+                }
+                let r = row - radius;
+                (row_span(radius, r).0 + index - rows_before(radius, row), r)
+            }
+            HexIndexing::Spiral => {
+                if index == 0 {
+                    return Some(self.0);
+                }
+                let mut k = 1;
+                while 3 * (k + 1) * k < index {
+                    k += 1;
+                }
+                let in_ring = index - 1 - 3 * k * (k - 1);
+                let (side, step) = (in_ring / k, in_ring % k);
+                let (corner, direction) = match side {
+                    0 => ((-k, k), (1, 0)),
+                    1 => ((0, k), (1, -1)),
+                    2 => ((k, 0), (0, -1)),
+                    3 => ((k, -k), (-1, 0)),
+                    4 => ((0, -k), (-1, 1)),
+                    _ => ((-k, 0), (0, 1)),
+                };
+                (corner.0 + direction.0 * step, corner.1 + direction.1 * step)
+            }
+        };
+        Some(self.0 + HexPosition(T::from_isize(q), T::from_isize(r)))
+    }
+
+    /// Returns an iterator over the positions of the hexagonal area, in the order of their index.
+    ///
+    /// # Arguments
+    /// - `indexing`: The order of the positions.
+    pub fn iter(&self, indexing: HexIndexing) -> impl Iterator<Item = HexPosition<T>> + '_ {
+        let (rows, spiral) = match indexing {
+            HexIndexing::RowMajor => (Some(self.rows()), None),
+            HexIndexing::Spiral => (None, Some(self.0.spiral(self.1))),
+        };
+        rows.into_iter()
+            .flatten()
+            .chain(spiral.into_iter().flatten())
+    }
+
+    /// Returns an iterator over the positions of the hexagonal area in the order of [HexIndexing::RowMajor],
+    /// with their index and the indices of their neighbors within the area, in the order of [HexDirection::iter].
+    ///
+    /// The positions are visited row after row, so a pass reading the neighbors of every position of a dense
+    /// row-major storage only works on three consecutive rows at a time, which stay in cache.
+    /// The indices of the neighbors are computed along the way, without calling [HexBound::index_of].
+    ///
+    /// # Example
+    /// ```
+    /// use hexing::{utils::{neighbors, HexBound, HexIndexing}, HexPosition};
+    ///
+    /// let bound = HexBound::new(HexPosition::new(-3, 1), 4);
+    /// let mut count = 0;
+    /// for (index, pos, indices) in bound.iter_neighbor_runs() {
+    ///     assert_eq!(bound.index_of(pos, HexIndexing::RowMajor), Some(index));
+    ///     for (neighbor, expected) in indices.into_iter().zip(neighbors(pos)) {
+    ///         assert_eq!(neighbor, bound.index_of(expected, HexIndexing::RowMajor));
+    ///     }
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, bound.len());
+    /// ```
+    pub fn iter_neighbor_runs(
+        &self,
+    ) -> impl Iterator<Item = (usize, HexPosition<T>, [Option<usize>; 6])> + '_ {
+        let radius = self.1 as isize;
+        self.rows().enumerate().map(move |(index, pos)| {
+            let offset = pos - self.0;
+            let (q, r) = (offset.0.to_isize(), offset.1.to_isize());
+            let neighbors = HexDirection::iter().map(|direction| {
+                let vector = direction.to_vector::<isize>();
+                row_major_index(radius, q + vector.0, r + vector.1)
+            });
+            (index, pos, neighbors)
+        })
+    }
+
+    /// Returns an iterator over the positions of the hexagonal area, row after row.
+    fn rows(&self) -> impl Iterator<Item = HexPosition<T>> {
+        let (center, radius) = (self.0, self.1 as isize);
+        (-radius..=radius).flat_map(move |r| {
+            let (first_q, last_q) = row_span(radius, r);
+            (first_q..=last_q)
+                .map(move |q| center + HexPosition(T::from_isize(q), T::from_isize(r)))
+        })
+    }
+
+    /// Returns the positions within both hexagonal areas.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// let a = HexBound::new(HexPosition(0, 0), 2);
+    /// let b = HexBound::new(HexPosition(3, 0), 2);
+    ///
+    /// let both = a.intersection(&b);
+    /// assert_eq!(both.len(), 4);
+    /// assert!(both.contains(&HexPosition(1, 0)));
+    /// assert!(a.intersection(&HexBound::new(HexPosition(5, 0), 2)).is_empty());
+    /// ```
+    pub fn intersection(&self, other: &Self) -> Vec<HexPosition<T>> {
+        let (small, large) = if self.1 <= other.1 {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small
+            .0
+            .spiral(small.1)
+            .filter(|pos| large.contains(*pos))
+            .collect()
+    }
+
+    /// Returns the positions within at least one of the hexagonal areas, each position only once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// let a = HexBound::new(HexPosition(0, 0), 2);
+    /// let b = HexBound::new(HexPosition(3, 0), 2);
+    ///
+    /// assert_eq!(a.union(&b).len(), 19 + 19 - 4);
+    /// assert_eq!(a.union(&a).len(), 19);
+    /// ```
+    pub fn union(&self, other: &Self) -> Vec<HexPosition<T>> {
+        self.0
+            .spiral(self.1)
+            .chain(other.0.spiral(other.1).filter(|pos| !self.contains(*pos)))
+            .collect()
+    }
+
+    /// Returns the hexagonal area with the same center and a radius larger by `delta`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// let bound = HexBound::new(HexPosition(1, 2), 3);
+    /// assert_eq!(bound.expanded(2), HexBound::new(HexPosition(1, 2), 5));
+    /// assert_eq!(bound.shrunk(2), HexBound::new(HexPosition(1, 2), 1));
+    /// assert_eq!(bound.shrunk(5), HexBound::new(HexPosition(1, 2), 0));
+    /// ```
+    pub const fn expanded(&self, delta: usize) -> Self {
+        Self(self.0, self.1 + delta)
+    }
+
+    /// Returns the hexagonal area with the same center and a radius smaller by `delta`, down to `0`.
+    pub const fn shrunk(&self, delta: usize) -> Self {
+        Self(self.0, self.1.saturating_sub(delta))
+    }
+
+    /// Returns the smallest hexagonal area containing both hexagonal areas, see [HexBound::from_positions].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// let a = HexBound::new(HexPosition(0, 0), 2);
+    /// let b = HexBound::new(HexPosition(4, 0), 2);
+    ///
+    /// let bound = a.union_bound(&b);
+    /// assert_eq!(bound.radius(), 4);
+    /// assert!(a.union(&b).iter().all(|pos| bound.contains(*pos)));
+    ///
+    /// assert_eq!(a.union_bound(&a.shrunk(1)), a);
+    /// ```
+    pub fn union_bound(&self, other: &Self) -> Self {
+        let corners = [self, other].into_iter().flat_map(|bound| {
+            let radius = bound.1 as isize;
+            [
+                (radius, 0),
+                (radius, -radius),
+                (0, -radius),
+                (-radius, 0),
+                (-radius, radius),
+                (0, radius),
+            ]
+            .map(|(q, r)| bound.0 + HexPosition(T::from_isize(q), T::from_isize(r)))
+        });
+        // The areas are convex, so containing their corners is enough to contain them.
+        Self::from_positions(corners).unwrap_or(*self)
+    }
+
+    /// Returns the number of positions within both hexagonal areas, without listing them like [HexBound::intersection].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// let a = HexBound::new(HexPosition(0, 0), 3);
+    /// for q in -8..=8 {
+    ///     for r in -8..=8 {
+    ///         for radius in 0..4 {
+    ///             let b = HexBound::new(HexPosition(q, r), radius);
+    ///             assert_eq!(a.overlap_count(&b), a.intersection(&b).len());
+    ///             assert_eq!(b.overlap_count(&a), a.intersection(&b).len());
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(a.overlap_count(&a), a.len());
+    /// ```
+    pub fn overlap_count(&self, other: &Self) -> usize {
+        // The intersection is the set of positions whose cube coordinates are each in an interval.
+        let range =
+            |center: isize, radius: usize| (center - radius as isize, center + radius as isize);
+        let intersect = |a: (isize, isize), b: (isize, isize)| (max(a.0, b.0), min(a.1, b.1));
+        let cube = |bound: &Self| {
+            let (q, r) = (bound.0.0.to_isize(), bound.0.1.to_isize());
+            (range(q, bound.1), range(r, bound.1), range(-q - r, bound.1))
+        };
+
+        let (a, b) = (cube(self), cube(other));
+        let (q, r, s) = (
+            intersect(a.0, b.0),
+            intersect(a.1, b.1),
+            intersect(a.2, b.2),
+        );
+        (q.0..=q.1)
+            .map(|q| {
+                let first_r = max(r.0, -s.1 - q);
+                let last_r = min(r.1, -s.0 - q);
+                max(last_r - first_r + 1, 0) as usize
+            })
+            .sum()
+    }
+
+    /// Returns the smallest hexagonal area containing all the positions, or `None` if there is no position.
+    ///
+    /// The center is chosen to minimize the radius, so the result is an exact 1-center of the positions
+    /// for the hexagonal distance. When several centers give the same radius, any of them can be returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use hexing::{utils::HexBound, HexPosition};
+    ///
+    /// assert_eq!(HexBound::<i32>::from_positions([]), None);
+    /// assert_eq!(HexBound::from_positions([HexPosition(2, 1)]), Some(HexBound::new(HexPosition(2, 1), 0)));
+    ///
+    /// // Compare with an exhaustive search of the center on small sets.
+    /// let mut seed = 11u64;
+    /// let mut random = || {
+    ///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    ///     (seed >> 33) as i32 % 11 - 5
+    /// };
+    /// for size in 1..30 {
+    ///     let positions: Vec<HexPosition<i32>> = (0..size % 6 + 1).map(|_| HexPosition(random(), random())).collect();
+    ///     let bound = HexBound::from_positions(positions.iter().copied()).unwrap();
+    ///     assert!(positions.iter().all(|pos| bound.contains(*pos)));
+    ///
+    ///     let best = HexPosition(0, 0)
+    ///         .spiral(15)
+    ///         .map(|center| positions.iter().map(|pos| pos.distance(center)).max().unwrap())
+    ///         .min()
+    ///         .unwrap();
+    ///     assert_eq!(bound.radius(), best as usize);
+    /// }
+    /// ```
+    pub fn from_positions(positions: impl IntoIterator<Item = HexPosition<T>>) -> Option<Self> {
+        let mut positions = positions.into_iter().map(|pos| {
+            let (q, r) = (pos.0.to_isize(), pos.1.to_isize());
+            [q, r, -q - r]
+        });
+        let first = positions.next()?;
+        let (mut lowest, mut highest) = (first, first);
+        for cube in positions {
+            for axis in 0..3 {
+                lowest[axis] = min(lowest[axis], cube[axis]);
+                highest[axis] = max(highest[axis], cube[axis]);
+            }
+        }
+
+        // The center must be within `radius` of the extremes on each axis, with `q + r + s = 0`.
+        let spread = (0..3).map(|axis| highest[axis] - lowest[axis]).fold(0, max);
+        let mut radius = (spread + 1) / 2;
+        loop {
+            let low = |axis: usize| highest[axis] - radius;
+            let high = |axis: usize| lowest[axis] + radius;
+            let sum_low = max(low(0) + low(1), -high(2));
+            let sum_high = min(high(0) + high(1), -low(2));
+            if sum_low <= sum_high {
+                let q = max(low(0), sum_low - high(1));
+                let center = HexPosition(T::from_isize(q), T::from_isize(sum_low - q));
+                return Some(Self(center, radius as usize));
+            }
+            radius += 1;
+        }
+    }
+}
+
+/// The order of the positions of a [HexBound] in a dense storage, see [HexBound::index_of].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum HexIndexing {
+    /// The positions are sorted by `r`, then by `q`.
+    #[default]
+    RowMajor,
+
+    /// The positions are sorted like [HexPosition::spiral]: the center first, then ring after ring.
+    /// Positions close to each other are more likely to have close indices than with [HexIndexing::RowMajor].
+    Spiral,
+}
+
+/// Returns the first and the last `q` of the row `r` of a hexagonal area of the given radius, centered on the origin.
+const fn row_span(radius: isize, r: isize) -> (isize, isize) {
+    let first_q = if r < 0 { -radius - r } else { -radius };
+    let last_q = if r > 0 { radius - r } else { radius };
+    (first_q, last_q)
+}
+
+/// Returns the [HexIndexing::RowMajor] index of `(q, r)` in a hexagonal area of the given radius,
+/// centered on the origin, or `None` if the position is outside the area.
+const fn row_major_index(radius: isize, q: isize, r: isize) -> Option<usize> {
+    if r < -radius || r > radius {
+        return None;
+    }
+    let (first_q, last_q) = row_span(radius, r);
+    if q < first_q || q > last_q {
+        return None;
+    }
+    Some((rows_before(radius, r + radius) + q - first_q) as usize)
+}
+
+/// Returns the number of positions in the `rows` first rows of a hexagonal area of the given radius.
+const fn rows_before(radius: isize, rows: isize) -> isize {
+    if rows <= radius + 1 {
+        rows * (radius + 1) + rows * (rows - 1) / 2
+    } else {
+        let top = (radius + 1) * (radius + 1) + (radius + 1) * radius / 2;
+        let bottom = rows - radius - 1;
+        top + bottom * (3 * radius + 1) - bottom * (radius + rows) / 2
+    }
+}
+
+/// The layout of a rectangular grid of hexagons, stored as rows and columns (offset coordinates).
+///
+/// With the `R` layouts, the hexagons are pointy-topped and every other row is shifted by half a hexagon.
+/// With the `Q` layouts, the hexagons are flat-topped and every other column is shifted by half a hexagon.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum OffsetLayout {
+    /// The odd rows are shifted to the right.
+    #[default]
+    OddR,
+
+    /// The even rows are shifted to the right.
+    EvenR,
+
+    /// The odd columns are shifted down.
+    OddQ,
+
+    /// The even columns are shifted down.
+    EvenQ,
+}
+
+impl OffsetLayout {
+    /// Converts offset coordinates to the axial position of the hexagon, relative to the hexagon at `(0, 0)`.
+    ///
+    /// # Example
+    /// ```
+    /// use hexing::{utils::OffsetLayout, HexPosition};
+    ///
+    /// assert_eq!(OffsetLayout::OddR.to_hex::<i32>(2, 1), HexPosition(2, 1));
+    /// assert_eq!(OffsetLayout::OddR.to_hex::<i32>(2, 2), HexPosition(1, 2));
+    /// assert_eq!(OffsetLayout::EvenR.to_hex::<i32>(2, 1), HexPosition(1, 1));
+    /// assert_eq!(OffsetLayout::OddQ.to_hex::<i32>(1, 2), HexPosition(1, 2));
+    /// assert_eq!(OffsetLayout::EvenQ.to_hex::<i32>(1, 2), HexPosition(1, 1));
+    ///
+    /// // Consecutive hexagons of a row are always neighbors.
+    /// let layout = OffsetLayout::EvenQ;
+    /// assert_eq!(layout.to_hex::<i32>(3, 0).distance(layout.to_hex(4, 0)), 1);
+    /// ```
+    pub fn to_hex<T: Number>(self, col: isize, row: isize) -> HexPosition<T> {
+        let (q, r) = match self {
+            Self::OddR => (col - (row - (row & 1)) / 2, row),
+            Self::EvenR => (col - (row + (row & 1)) / 2, row),
+            Self::OddQ => (col, row - (col - (col & 1)) / 2),
+            Self::EvenQ => (col, row - (col + (col & 1)) / 2),
+        };
+        HexPosition(T::from_isize(q), T::from_isize(r))
+    }
+}
+
+/// The `MultiHexBound` struct defines an area made of the union of several [HexBound].
+///
+/// It allows representing organic (blob-shaped) areas without enumerating all of their positions.
+///
+/// # Type Parameters
+/// - `T`: A type that implements the `Number` trait. This type is used for the hexagonal coordinates in the `HexPosition` struct.
+///
+/// # Example
+/// ```
+/// use hexing::{utils::{HexBound, MultiHexBound}, HexPosition};
+///
+/// let bounds = MultiHexBound::new(vec![
+///     HexBound::new(HexPosition(0, 0), 2),
+///     HexBound::new(HexPosition(3, 0), 2),
+/// ]);
+///
+/// assert!(bounds.contains(HexPosition(-2, 0)));
+/// assert!(bounds.contains(HexPosition(5, 0)));
+/// assert!(!bounds.contains(HexPosition(6, 0)));
+///
+/// // The position (1, 0) is in both bounds, but appears only once.
+/// let positions: Vec<_> = bounds.iter().collect();
+/// assert_eq!(positions.iter().filter(|pos| **pos == HexPosition(1, 0)).count(), 1);
+/// assert_eq!(positions.len(), 19 + 19 - 4);
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiHexBound<T: Number>(Vec<HexBound<T>>);
+
+impl<T: Number> MultiHexBound<T> {
+    /// Creates a new area from the union of the given hexagonal areas.
+    ///
+    /// # Arguments
+    /// - `bounds`: The hexagonal areas composing the area.
+    ///
+    /// # Returns
+    /// Returns a new instance of `MultiHexBound`.
+    pub const fn new(bounds: Vec<HexBound<T>>) -> Self {
+        Self(bounds)
+    }
+
+    /// Returns the hexagonal areas composing the area.
+    pub fn bounds(&self) -> &[HexBound<T>] {
+        &self.0
+    }
+
+    /// Checks if a given position is within at least one of the hexagonal areas.
+    ///
+    /// # Arguments
+    /// - `pos`: The hexagonal position to check.
+    ///
+    /// # Returns
+    /// Returns `true` if the position is within the area, `false` otherwise.
+    pub fn contains(&self, pos: HexPosition<T>) -> bool {
+        self.0.iter().any(|bound| bound.contains(pos))
+    }
+
+    /// Returns an iterator over all the positions of the area.
+    ///
+    /// Each position is yielded once, even if it is within several hexagonal areas.
+    pub fn iter(&self) -> impl Iterator<Item = HexPosition<T>> + '_ {
+        self.0.iter().enumerate().flat_map(move |(i, bound)| {
+            bound
+                .center()
+                .spiral(bound.radius())
+                .filter(move |pos| !self.0[..i].iter().any(|other| other.contains(*pos)))
+        })
+    }
+}
+
+/// An utility function for connecting positions with the shortest network of straight links,
+/// where the length of a link is the distance between its positions.
+///
+/// Returns the links of a [minimum spanning tree](https://en.wikipedia.org/wiki/Minimum_spanning_tree)
+/// of the complete graph over `nodes`, as pairs of indices into `nodes`, computed with Prim's algorithm in `O(n²)`.
+/// See [minimum_spanning_tree_with_loops] to add some loops to the network.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{utils::minimum_spanning_tree, HexPosition};
+///
+/// let rooms = [HexPosition(0, 0), HexPosition(5, 0), HexPosition(9, -3), HexPosition(-2, 6), HexPosition(6, 2)];
+/// let tree = minimum_spanning_tree(&rooms);
+/// assert_eq!(tree.len(), rooms.len() - 1);
+///
+/// let weight = |edges: &[(usize, usize)]| edges.iter().map(|(a, b)| rooms[*a].distance(rooms[*b])).sum::<i32>();
+///
+/// // Every spanning tree is at least as long, checked over all the sets of 4 links.
+/// let all: Vec<(usize, usize)> = (0..5).flat_map(|a| (a + 1..5).map(move |b| (a, b))).collect();
+/// for mask in 0..1u32 << all.len() {
+///     if mask.count_ones() != 4 {
+///         continue;
+///     }
+///     let edges: Vec<_> = (0..all.len()).filter(|i| mask & (1 << i) != 0).map(|i| all[i]).collect();
+///     let mut group: Vec<usize> = (0..5).collect();
+///     for (a, b) in &edges {
+///         let (ga, gb) = (group[*a], group[*b]);
+///         group.iter_mut().filter(|g| **g == gb).for_each(|g| *g = ga);
+///     }
+///     if group.iter().all(|g| *g == group[0]) {
+///         assert!(weight(&tree) <= weight(&edges));
+///     }
+/// }
+/// ```
+pub fn minimum_spanning_tree<T: Number>(nodes: &[HexPosition<T>]) -> Vec<(usize, usize)> {
+    let mut edges = Vec::with_capacity(nodes.len().saturating_sub(1));
+    if nodes.is_empty() {
+        return edges;
+    }
+
+    // For each node outside of the tree, its distance to the tree and its closest node in the tree.
+    let mut closest: Vec<Option<(isize, usize)>> = nodes
+        .iter()
+        .map(|node| Some((node.distance(nodes[0]).to_isize(), 0)))
+        .collect();
+    closest[0] = None;
+
+    while let Some((next, (_, parent))) = closest
+        .iter()
+        .enumerate()
+        .filter_map(|(i, candidate)| candidate.map(|candidate| (i, candidate)))
+        .min_by_key(|(i, (distance, _))| (*distance, *i))
+    {
+        edges.push((parent, next));
+        closest[next] = None;
+        for (i, candidate) in closest.iter_mut().enumerate() {
+            if let Some((distance, parent)) = candidate {
+                let new_distance = nodes[i].distance(nodes[next]).to_isize();
+                if new_distance < *distance {
+                    *distance = new_distance;
+                    *parent = next;
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// An utility function for connecting positions like [minimum_spanning_tree], with some extra links making loops.
+///
+/// `extra_loops` is the proportion (between `0.0` and `1.0`) of the links not in the tree that are added back,
+/// the shortest ones first.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{utils::{minimum_spanning_tree, minimum_spanning_tree_with_loops}, HexPosition};
+///
+/// let rooms = [HexPosition(0, 0), HexPosition(5, 0), HexPosition(9, -3), HexPosition(-2, 6), HexPosition(6, 2)];
+///
+/// assert_eq!(minimum_spanning_tree_with_loops(&rooms, 0.0), minimum_spanning_tree(&rooms));
+/// // 10 links between 5 rooms, 4 of them in the tree.
+/// assert_eq!(minimum_spanning_tree_with_loops(&rooms, 0.5).len(), 4 + 3);
+/// assert_eq!(minimum_spanning_tree_with_loops(&rooms, 1.0).len(), 10);
+/// ```
+pub fn minimum_spanning_tree_with_loops<T: Number>(
+    nodes: &[HexPosition<T>],
+    extra_loops: f32,
+) -> Vec<(usize, usize)> {
+    let mut edges = minimum_spanning_tree(nodes);
+    let in_tree = |a: usize, b: usize, edges: &[(usize, usize)]| {
+        edges.iter().any(|edge| *edge == (a, b) || *edge == (b, a))
+    };
+
+    let mut others: Vec<(usize, usize)> = (0..nodes.len())
+        .flat_map(|a| (a + 1..nodes.len()).map(move |b| (a, b)))
+        .filter(|(a, b)| !in_tree(*a, *b, &edges))
+        .collect();
+    others.sort_by_key(|(a, b)| (nodes[*a].distance(nodes[*b]).to_isize(), *a, *b));
+
+    let count = (others.len() as f32 * extra_loops.clamp(0.0, 1.0)).round() as usize;
+    edges.extend(others.into_iter().take(count));
+    edges
+}
+
+/// An utility function for getting the center, in base coordinates, of a super-hex.
+///
+/// A super-hex of radius `level_radius` groups all the hexagons within `level_radius` of its center
+/// (7 hexagons for a radius of 1, 19 for a radius of 2, ...).
+///
+/// The super-hexes are themselves laid out as a hexagonal grid, so `super_pos` is an axial position
+/// in that coarser grid: two adjacent super positions are two adjacent super-hexes.
+/// for more information, see the [documentation](https://www.redblobgames.com/grids/hexagons/#wraparound).
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexPosition;
+///
+/// let center = hexing::utils::super_hex_center(HexPosition(1, 0), 1);
+/// assert_eq!(center, HexPosition(3, -1));
+/// ```
+pub fn super_hex_center<T: Number>(
+    super_pos: HexPosition<T>,
+    level_radius: usize,
+) -> HexPosition<T> {
+    let radius = T::from_usize(level_radius);
+    let first_axis = HexPosition(radius + radius + T::ONE, -radius);
+    let second_axis = first_axis.rotation(1);
+    first_axis * super_pos.0 + second_axis * super_pos.1
+}
+
+/// An utility function for getting the super-hex containing a hexagonal position.
+/// Every hexagonal position belongs to exactly one super-hex, see [super_hex_center] for the layout of super-hexes.
+///
+/// # Example
+///
+/// ```
+/// use hexing::HexPosition;
+/// use hexing::utils::{super_hex_members, to_super_hex};
+///
+/// assert_eq!(to_super_hex(HexPosition(3, -1), 1), HexPosition(1, 0));
+/// assert_eq!(to_super_hex(HexPosition(0, 0), 2), HexPosition(0, 0));
+///
+/// // Every position of a large spiral is a member of its super-hex.
+/// for pos in HexPosition(0, 0).spiral(30) {
+///     let super_pos = to_super_hex(pos, 2);
+///     assert!(super_hex_members(super_pos, 2).any(|member| member == pos));
+/// }
+/// ```
+pub fn to_super_hex<T: Number>(pos: HexPosition<T>, level_radius: usize) -> HexPosition<T> {
+    let radius = level_radius as isize;
+    let (q, r) = (pos.0.to_isize(), pos.1.to_isize());
+
+    // Coordinates of the position in the basis of the super-hex axes, scaled by the determinant.
+    let determinant = 3 * radius * radius + 3 * radius + 1;
+    let first = (q * (radius + 1) - r * radius).div_euclid(determinant);
+    let second = (r * (2 * radius + 1) + q * radius).div_euclid(determinant);
+
+    for first_offset in -1..=2 {
+        for second_offset in -1..=2 {
+            let candidate = HexPosition(
+                T::from_isize(first + first_offset),
+                T::from_isize(second + second_offset),
+            );
+            let center = super_hex_center(candidate, level_radius);
+            if center.distance(pos).to_isize() <= radius {
+                return candidate;
+            }
+        }
+    }
+
+    unreachable!("super-hexes tile the whole plane")
+}
+
+/// An utility function for iterating over all the hexagonal positions of a super-hex.
+/// The positions are yielded in spiral order, starting from the center of the super-hex.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashSet;
+///
+/// use hexing::HexPosition;
+/// use hexing::utils::{super_hex_members, to_super_hex};
+///
+/// assert_eq!(super_hex_members(HexPosition(0, 0), 1).count(), 7);
+/// assert_eq!(super_hex_members(HexPosition(2, -1), 2).count(), 19);
+///
+/// // Members of distinct super-hexes are disjoint.
+/// let super_positions: HashSet<_> = HexPosition(0, 0)
+///     .spiral(20)
+///     .map(|pos| to_super_hex(pos, 2))
+///     .collect();
+/// let mut members = HashSet::new();
+/// for super_pos in super_positions {
+///     for member in super_hex_members(super_pos, 2) {
+///         assert!(members.insert(member));
+///     }
+/// }
+/// ```
+pub fn super_hex_members<T: Number>(
+    super_pos: HexPosition<T>,
+    level_radius: usize,
+) -> HexSpiral<T> {
+    super_hex_center(super_pos, level_radius).spiral(level_radius)
+}
+
+/// An utility function for getting the length, in pixels, of the polyline going through the centers of the hexagons of a path.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{pixel::PixelLayout, utils::path_pixel_length, HexPosition};
+///
+/// let path = [HexPosition(0, 0), HexPosition(1, 0), HexPosition(1, 1)];
+/// let length = path_pixel_length(&path, &PixelLayout::default());
+/// assert!((length - 2.0 * 3f32.sqrt()).abs() < 1e-5);
+/// ```
+pub fn path_pixel_length<T: Number>(path: &[HexPosition<T>], layout: &PixelLayout) -> f32 {
+    path.windows(2)
+        .map(|pair| {
+            let (a, b) = (layout.hex_to_pixel(pair[0]), layout.hex_to_pixel(pair[1]));
+            (a.0 - b.0).hypot(a.1 - b.1)
+        })
+        .sum()
+}
+
+/// An utility function for getting the pixel position at a given fraction of a path.
+///
+/// The position moves along the polyline going through the centers of the hexagons of the path,
+/// at a constant speed: `t = 0.0` is the center of the first hexagon, `t = 1.0` is the center of the last one,
+/// and `t` is clamped to this range. A path of a single hexagon always returns its center,
+/// and an empty path returns the origin of the layout.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{pixel::PixelLayout, utils::sample_path, HexPosition};
+///
+/// let layout = PixelLayout::default();
+/// let path = [HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0)];
+///
+/// assert_eq!(sample_path(&path, &layout, 0.0), layout.hex_to_pixel(path[0]));
+/// assert_eq!(sample_path(&path, &layout, 1.0), layout.hex_to_pixel(path[2]));
+///
+/// // The middle of a two-hexagon path is halfway between the two centers.
+/// let (x, y) = sample_path(&path[..2], &layout, 0.5);
+/// assert!((x - 3f32.sqrt() / 2.0).abs() < 1e-5 && y.abs() < 1e-5);
+///
+/// // The position progresses along the path.
+/// let xs: Vec<f32> = (0..=10).map(|i| sample_path(&path, &layout, i as f32 / 10.0).0).collect();
+/// assert!(xs.windows(2).all(|pair| pair[0] < pair[1]));
+///
+/// assert_eq!(sample_path(&path[..1], &layout, 0.7), layout.hex_to_pixel(path[0]));
+/// ```
+pub fn sample_path<T: Number>(path: &[HexPosition<T>], layout: &PixelLayout, t: f32) -> (f32, f32) {
+    let points: Vec<(f32, f32)> = path.iter().map(|pos| layout.hex_to_pixel(*pos)).collect();
+    if points.len() < 2 {
+        return points.first().copied().unwrap_or(layout.origin);
+    }
+
+    let (i, u) = locate_on_polyline(&points, t);
+    (
+        lerp(points[i].0, points[i + 1].0, u),
+        lerp(points[i].1, points[i + 1].1, u),
+    )
+}
+
+/// An utility function for getting the pixel position at a given fraction of a path, following a smooth curve.
+///
+/// This is the same as [sample_path], but the position follows a
+/// uniform [Catmull-Rom spline](https://en.wikipedia.org/wiki/Cubic_Hermite_spline#Catmull%E2%80%93Rom_spline)
+/// going through the centers of the hexagons, instead of straight lines.
+/// The consecutive hexagons of a path being equally spaced, the uniform parameterization doesn't make loops.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{pixel::PixelLayout, utils::sample_path_smooth, HexPosition};
+///
+/// let layout = PixelLayout::default();
+/// let path = [HexPosition(0, 0), HexPosition(1, 0), HexPosition(1, 1)];
+///
+/// let close = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 1e-5 && (a.1 - b.1).abs() < 1e-5;
+///
+/// assert!(close(sample_path_smooth(&path, &layout, 0.0), layout.hex_to_pixel(path[0])));
+/// assert!(close(sample_path_smooth(&path, &layout, 1.0), layout.hex_to_pixel(path[2])));
+///
+/// // The curve still goes through the center of every hexagon.
+/// assert!(close(sample_path_smooth(&path, &layout, 0.5), layout.hex_to_pixel(path[1])));
+/// ```
+pub fn sample_path_smooth<T: Number>(
+    path: &[HexPosition<T>],
+    layout: &PixelLayout,
+    t: f32,
+) -> (f32, f32) {
+    let points: Vec<(f32, f32)> = path.iter().map(|pos| layout.hex_to_pixel(*pos)).collect();
+    if points.len() < 2 {
+        return points.first().copied().unwrap_or(layout.origin);
+    }
+
+    let (i, u) = locate_on_polyline(&points, t);
+    let p0 = points[i.saturating_sub(1)];
+    let (p1, p2) = (points[i], points[i + 1]);
+    let p3 = points[std::cmp::min(i + 2, points.len() - 1)];
+    (
+        catmull_rom(p0.0, p1.0, p2.0, p3.0, u),
+        catmull_rom(p0.1, p1.1, p2.1, p3.1, u),
+    )
+}
+
+/// Returns the segment of the polyline (at least two points) at the fraction `t` of its length,
+/// and the fraction of that segment where it is.
+fn locate_on_polyline(points: &[(f32, f32)], t: f32) -> (usize, f32) {
+    let lengths: Vec<f32> = points
+        .windows(2)
+        .map(|pair| (pair[0].0 - pair[1].0).hypot(pair[0].1 - pair[1].1))
+        .collect();
+
+    let mut remaining = t.clamp(0.0, 1.0) * lengths.iter().sum::<f32>();
+    for (i, length) in lengths.iter().enumerate() {
+        if remaining <= *length && *length > 0.0 {
+            return (i, remaining / length);
+        }
+        remaining -= length;
+    }
+    (points.len() - 2, 1.0)
+}
+
+/// Evaluates one coordinate of a uniform Catmull-Rom spline between `p1` and `p2`.
+fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, u: f32) -> f32 {
+    let a = 2.0 * p1;
+    let b = p2 - p0;
+    let c = 2.0f32.mul_add(p0, -5.0 * p1) + 4.0f32.mul_add(p2, -p3);
+    let d = 3.0f32.mul_add(p1, -p0) + 3.0f32.mul_add(-p2, p3);
+    0.5 * u.mul_add(u.mul_add(u.mul_add(d, c), b), a)
+}
+
+/// An utility function for getting the direction of each step of a path.
+///
+/// Returns `None` if two consecutive positions of the path are not neighbors.
+/// Paths with less than two positions have no step, so the result is empty.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{utils::path_directions, HexDirection, HexPosition};
+///
+/// let path = [HexPosition(0, 0), HexPosition(1, 0), HexPosition(1, 1)];
+/// assert_eq!(path_directions(&path), Some(vec![HexDirection::Right, HexDirection::DownRight]));
+///
+/// assert_eq!(path_directions(&[HexPosition(0, 0), HexPosition(2, 0)]), None);
+/// assert_eq!(path_directions::<i32>(&[]), Some(vec![]));
+/// assert_eq!(path_directions(&[HexPosition(0, 0)]), Some(vec![]));
+/// ```
+pub fn path_directions<T: Number>(path: &[HexPosition<T>]) -> Option<Vec<HexDirection>> {
+    path.windows(2)
+        .map(|step| {
+            HexDirection::iter()
+                .into_iter()
+                .find(|direction| step[0] + direction.to_vector() == step[1])
+        })
+        .collect()
+}
+
+/// An utility function for splitting a path into straight runs: each run is a direction and its number of steps.
+///
+/// Returns an empty vector if the path has no step or if two consecutive positions are not neighbors.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{utils::path_runs, HexDirection, HexPosition};
+///
+/// // An L-shaped path.
+/// let path = [HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0), HexPosition(2, 1), HexPosition(2, 2)];
+/// assert_eq!(path_runs(&path), vec![(HexDirection::Right, 2), (HexDirection::DownRight, 2)]);
+///
+/// // A straight path.
+/// let line: Vec<_> = HexPosition(0, 0).line_to(HexPosition(0, -4)).collect();
+/// assert_eq!(path_runs(&line), vec![(HexDirection::UpLeft, 4)]);
+///
+/// assert!(path_runs(&[HexPosition(0, 0)]).is_empty());
+/// ```
+pub fn path_runs<T: Number>(path: &[HexPosition<T>]) -> Vec<(HexDirection, usize)> {
+    let mut runs: Vec<(HexDirection, usize)> = Vec::new();
+    for direction in path_directions(path).unwrap_or_default() {
+        match runs.last_mut() {
+            Some((last, count)) if *last == direction => *count += 1,
+            _ => runs.push((direction, 1)),
+        }
+    }
+    runs
+}
+
+/// An utility function for getting the corners of a path: the positions where its direction changes.
+///
+/// The first and last positions are not corners. Returns an empty vector if two consecutive positions are not neighbors.
+///
+/// # Example
+///
+/// ```
+/// use hexing::{utils::path_corners, HexPosition};
+///
+/// // An L-shaped path.
+/// let path = [HexPosition(0, 0), HexPosition(1, 0), HexPosition(2, 0), HexPosition(2, 1), HexPosition(2, 2)];
+/// assert_eq!(path_corners(&path), vec![HexPosition(2, 0)]);
+///
+/// // A straight path.
+/// let line: Vec<_> = HexPosition(0, 0).line_to(HexPosition(3, -3)).collect();
+/// assert!(path_corners(&line).is_empty());
+///
+/// assert!(path_corners::<i32>(&[]).is_empty());
+/// ```
+pub fn path_corners<T: Number>(path: &[HexPosition<T>]) -> Vec<HexPosition<T>> {
+    let runs = path_runs(path);
+    let mut index = 0;
+    runs.iter()
+        .take(runs.len().saturating_sub(1))
+        .map(|(_, count)| {
+            index += count;
+            path[index]
+        })
+        .collect()
+}
+
+/// A fast, non-cryptographic hasher for hexagonal positions, enabled by the `fxhash` feature.
+///
+/// Hashing a position only takes a few multiplications, which is much faster than the default hasher
+/// of the standard library. It is based on the `FxHash` algorithm used by the Rust compiler.
+/// Note that it is not resistant to collision attacks, so it shouldn't be used with untrusted keys.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "fxhash")]
+/// # {
+/// use std::collections::HashMap;
+///
+/// use hexing::{layout::HexLayout, utils::FxBuildHasher, HexPosition};
+///
+/// let mut costs: HashMap<HexPosition<i32>, u32, FxBuildHasher> = HashMap::default();
+/// costs.insert(HexPosition(-3, 2), 5);
+/// assert_eq!(costs.get(&HexPosition(-3, 2)), Some(&5));
+///
+/// // Any layout can use it, whatever the features.
+/// let map: HexLayout<u8, i32, FxBuildHasher> = HexPosition(0, 0).spiral(2).map(|pos| (pos, 1)).collect();
+/// assert_eq!(map.len(), 19);
+/// # }
+/// ```
+#[cfg(feature = "fxhash")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HexHasher(u64);
+
+/// The [BuildHasher](std::hash::BuildHasher) creating [HexHasher]s, to use with a `HashMap` or a `HashSet`.
+#[cfg(feature = "fxhash")]
+pub type FxBuildHasher = std::hash::BuildHasherDefault<HexHasher>;
+
+/// The default hasher of [HexLayout](crate::layout::HexLayout) and of the maps used by the algorithms of the crate.
+///
+/// It is the `RandomState` of the standard library, or `FxBuildHasher` with the `fxhash` feature.
+/// A layout can also use any other hasher through its last type parameter.
+#[cfg(not(feature = "fxhash"))]
+pub type HexBuildHasher = std::collections::hash_map::RandomState;
+
+/// The default hasher of [HexLayout](crate::layout::HexLayout) and of the maps used by the algorithms of the crate.
+///
+/// It is the `RandomState` of the standard library, or [FxBuildHasher] with the `fxhash` feature.
+/// A layout can also use any other hasher through its last type parameter.
+#[cfg(feature = "fxhash")]
+pub type HexBuildHasher = FxBuildHasher;
+
+#[cfg(feature = "fxhash")]
+impl HexHasher {
+    /// The multiplier of the `FxHash` algorithm.
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    /// Mixes a word into the hash.
+    const fn add_to_hash(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(Self::SEED);
+    }
+}
+
+#[cfg(feature = "fxhash")]
+impl std::hash::Hasher for HexHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            self.add_to_hash(u64::from_le_bytes(word));
+        }
+        for byte in chunks.remainder() {
+            self.add_to_hash(u64::from(*byte));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(u64::from(i));
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add_to_hash(i as u64);
+        self.add_to_hash((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_i8(&mut self, i: i8) {
+        self.write_u8(i as u8);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_usize(i as usize);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
 }